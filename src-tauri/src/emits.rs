@@ -68,6 +68,9 @@ struct EmitsInner {
     last_speed_calc_instant: Instant,
     last_speed_calc_bytes: u64,
     last_speed_kbps: f64,
+    /// No total size exists (live recording); bytes and rate are reported
+    /// without a percentage.
+    unbounded: bool,
 }
 
 impl Default for EmitsInner {
@@ -82,6 +85,7 @@ impl Default for EmitsInner {
             last_speed_calc_bytes: 0,
             is_complete: false,
             last_speed_kbps: 0.0,
+            unbounded: false,
         }
     }
 }
@@ -117,6 +121,31 @@ impl Emits {
     ///
     /// Returns a new `Emits` instance with an active background update task.
     pub fn new(app: AppHandle, download_id: String, filesize_bytes: Option<u64>) -> Self {
+        Self::start(app, download_id, filesize_bytes, false)
+    }
+
+    /// Creates a progress emitter for a transfer without a known end.
+    ///
+    /// Used by live recordings: events carry the downloaded size, transfer
+    /// rate and elapsed time, while `percentage` stays 0 until
+    /// [`complete()`](Self::complete).
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - Tauri application handle for event emission
+    /// * `download_id` - Unique identifier for this transfer
+    pub fn new_unbounded(app: AppHandle, download_id: String) -> Self {
+        Self::start(app, download_id, None, true)
+    }
+
+    /// Shared constructor for [`new`](Self::new) and
+    /// [`new_unbounded`](Self::new_unbounded).
+    fn start(
+        app: AppHandle,
+        download_id: String,
+        filesize_bytes: Option<u64>,
+        unbounded: bool,
+    ) -> Self {
         fn bytes_to_mb(bytes: u64) -> f64 {
             bytes as f64 / (1024.0 * 1024.0)
         }
@@ -131,6 +160,7 @@ impl Emits {
             start_instant: now,
             last_instant: now,
             last_speed_calc_instant: now,
+            unbounded,
             ..Default::default()
         }));
 
@@ -187,6 +217,22 @@ impl Emits {
         let _ = self.progress_tx.send(downloaded_bytes);
     }
 
+    /// Seeds the progress with bytes already on disk from a resumed download.
+    ///
+    /// Moves the transfer-rate baseline along with the byte count so the
+    /// resumed bytes are shown as progress without being reported as a
+    /// one-second burst of speed.
+    ///
+    /// # Arguments
+    ///
+    /// * `resumed_bytes` - Bytes recovered from a previous partial download
+    pub async fn set_resumed_bytes(&self, resumed_bytes: u64) {
+        let mut guard = self.inner.lock().await;
+        guard.last_speed_calc_bytes = resumed_bytes;
+        let _ = self.progress_tx.send(resumed_bytes);
+        Self::send_progress_locked(&self.app, &mut guard, resumed_bytes);
+    }
+
     /// Sets the current download stage and emits an immediate update.
     ///
     /// This method is used to indicate different phases of the download process
@@ -321,7 +367,7 @@ impl Emits {
             }
 
             // Round values for display
-            if inner.progress.filesize.is_some() {
+            if inner.progress.filesize.is_some() || inner.unbounded {
                 inner.progress.downloaded =
                     Some(round_to(current_bytes as f64 / (1024.0 * 1024.0), 1));
                 // Cap percentage at 100 to prevent display issues (e.g., 101%)
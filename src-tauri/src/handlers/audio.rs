@@ -1,12 +1,17 @@
 //! Audio Extraction
 //!
 //! Extracts the audio track from a local MP4 file into MP3 (`.mp3`) or AAC
-//! (`.m4a`) using ffmpeg. Independent of the Bilibili download pipeline: it
-//! operates only on local files specified by absolute paths.
+//! (`.m4a`) using ffmpeg. [`extract_audio`] is independent of the Bilibili
+//! download pipeline: it operates only on local files specified by absolute
+//! paths. The audio-only download mode ([`AudioOnlyOptions`]) reuses the
+//! same codec arguments to convert a DASH audio stream directly, without
+//! fetching the video.
 
+use crate::models::frontend_dto::VideoMetadata;
 use crate::models::settings::AudioFormat;
 use crate::utils::ffmpeg_probe::probe_duration_sec;
 use crate::utils::ffmpeg_progress::parse_out_time;
+use crate::utils::metadata::ffmpeg_metadata_args;
 use crate::utils::paths::get_ffmpeg_path;
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -35,6 +40,24 @@ fn codec_args(format: AudioFormat, bitrate_kbps: u32) -> Vec<String> {
     ]
 }
 
+/// Bitrate used by the audio-only download mode when none is given.
+pub const DEFAULT_AUDIO_ONLY_BITRATE_KBPS: u32 = 320;
+
+/// Audio-only download mode of a DASH download.
+///
+/// Only the audio stream is fetched and converted to `format`; the video
+/// stream and the merge are skipped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioOnlyOptions {
+    /// Target audio format.
+    pub format: AudioFormat,
+    /// Target bitrate in kbps when transcoding; defaults to
+    /// [`DEFAULT_AUDIO_ONLY_BITRATE_KBPS`].
+    #[serde(default)]
+    pub bitrate_kbps: Option<u32>,
+}
+
 /// Options for an audio extraction operation.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -47,6 +70,10 @@ pub struct AudioOptions {
     pub format: AudioFormat,
     /// Target bitrate in kbps (e.g. 128, 192, 256, 320).
     pub bitrate_kbps: u32,
+    /// Optional absolute path to a cover image embedded as artwork
+    /// (`attached_pic` in M4a, ID3 APIC frame in MP3).
+    #[serde(default)]
+    pub cover_path: Option<String>,
 }
 
 /// Result of a successful audio extraction.
@@ -69,6 +96,9 @@ pub struct AudioProgressPayload {
 /// Builds the ffmpeg argument list for an audio extraction.
 ///
 /// `-vn` discards the video stream so only audio is decoded and re-encoded.
+/// With a cover, the audio and the image are mapped explicitly instead and
+/// the image is copied as the attached picture; for MP3 the ffmpeg muxer
+/// writes it as an ID3v2.3 APIC frame.
 /// `-progress pipe:2` emits structured `key=value` lines to stderr at 1-second
 /// cadence so we can drive the progress bar from `out_time=`.
 pub fn build_ffmpeg_args(options: &AudioOptions) -> Vec<String> {
@@ -80,15 +110,106 @@ pub fn build_ffmpeg_args(options: &AudioOptions) -> Vec<String> {
         "pipe:2".to_string(),
         "-i".to_string(),
         options.input_path.clone(),
-        // Discard video; extract and re-encode the audio track only.
-        "-vn".to_string(),
     ];
+    match &options.cover_path {
+        Some(cover) => args.extend(cover_args(options.format, cover)),
+        // Discard video; extract and re-encode the audio track only.
+        None => args.push("-vn".to_string()),
+    }
     args.extend(codec_args(options.format, options.bitrate_kbps));
     args.push("-y".to_string());
     args.push(options.output_path.clone());
     args
 }
 
+/// Arguments that add `cover` (the second input) as the attached picture.
+fn cover_args(format: AudioFormat, cover: &str) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "-i",
+        cover,
+        "-map",
+        "0:a",
+        "-map",
+        "1:v",
+        "-c:v",
+        "copy",
+        "-disposition:v",
+        "attached_pic",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    if format == AudioFormat::Mp3 {
+        args.extend(
+            [
+                "-id3v2_version",
+                "3",
+                "-metadata:s:v",
+                "title=Album cover",
+                "-metadata:s:v",
+                "comment=Cover (front)",
+            ]
+            .iter()
+            .map(|s| s.to_string()),
+        );
+    }
+    args
+}
+
+/// Builds the ffmpeg argument list that converts a downloaded DASH audio
+/// stream straight to `format`.
+///
+/// With `copy` the AAC stream is remuxed into the `.m4a` container instead
+/// of being re-encoded. The metadata is written as tags (`title`, `artist`
+/// from the uploader, ...); MP3 files always use ID3v2.3 so the tags and
+/// cover are readable by older players. Progress is reported on stdout
+/// (`-progress pipe:1`) like a merge.
+///
+/// # Arguments
+///
+/// * `input_path` - Downloaded audio stream (`.m4s`)
+/// * `output_path` - Output file; its extension must match `format`
+/// * `options` - Target format and bitrate
+/// * `copy` - Remux the stream instead of transcoding it
+/// * `metadata` - Descriptive metadata written as tags
+/// * `cover_path` - Cover image embedded as the attached picture
+pub fn build_stream_args(
+    input_path: &str,
+    output_path: &str,
+    options: &AudioOnlyOptions,
+    copy: bool,
+    metadata: Option<&VideoMetadata>,
+    cover_path: Option<&str>,
+) -> Vec<String> {
+    let mut args = vec!["-i".to_string(), input_path.to_string()];
+    match cover_path {
+        Some(cover) => args.extend(cover_args(options.format, cover)),
+        None => args.push("-vn".to_string()),
+    }
+    if copy {
+        args.extend(["-c:a".to_string(), "copy".to_string()]);
+    } else {
+        let bitrate = options
+            .bitrate_kbps
+            .filter(|&kbps| kbps > 0)
+            .unwrap_or(DEFAULT_AUDIO_ONLY_BITRATE_KBPS);
+        args.extend(codec_args(options.format, bitrate));
+    }
+    // With a cover, `cover_args` already selected ID3v2.3.
+    if options.format == AudioFormat::Mp3 && cover_path.is_none() {
+        args.extend(["-id3v2_version".to_string(), "3".to_string()]);
+    }
+    if let Some(metadata) = metadata {
+        args.extend(ffmpeg_metadata_args(metadata));
+    }
+    args.extend(
+        ["-progress", "pipe:1", "-y", output_path]
+            .iter()
+            .map(|s| s.to_string()),
+    );
+    args
+}
+
 fn is_mp4(path: &Path) -> bool {
     path.extension()
         .and_then(|e| e.to_str())
@@ -132,6 +253,7 @@ fn is_same_file(input: &Path, output: &Path) -> bool {
 /// - `ERR::AUDIO_UNSUPPORTED_OUTPUT_FORMAT` (extension does not match `format`)
 /// - `ERR::AUDIO_SAME_PATH`
 /// - `ERR::AUDIO_INVALID_BITRATE`
+/// - `ERR::AUDIO_COVER_NOT_FOUND`
 /// - `ERR::AUDIO_FFMPEG_FAILED`
 pub async fn extract_audio(app: &AppHandle, options: &AudioOptions) -> Result<AudioResult, String> {
     let input_path = Path::new(&options.input_path);
@@ -143,11 +265,7 @@ pub async fn extract_audio(app: &AppHandle, options: &AudioOptions) -> Result<Au
     if !is_mp4(input_path) {
         return Err("ERR::AUDIO_UNSUPPORTED_FORMAT".to_string());
     }
-    let expected_ext = match options.format {
-        AudioFormat::Mp3 => "mp3",
-        AudioFormat::M4a => "m4a",
-    };
-    if !has_extension(output_path, expected_ext) {
+    if !has_extension(output_path, options.format.extension()) {
         return Err("ERR::AUDIO_UNSUPPORTED_OUTPUT_FORMAT".to_string());
     }
     if is_same_file(input_path, output_path) {
@@ -156,6 +274,13 @@ pub async fn extract_audio(app: &AppHandle, options: &AudioOptions) -> Result<Au
     if options.bitrate_kbps == 0 {
         return Err("ERR::AUDIO_INVALID_BITRATE".to_string());
     }
+    if options
+        .cover_path
+        .as_ref()
+        .is_some_and(|cover| !Path::new(cover).exists())
+    {
+        return Err("ERR::AUDIO_COVER_NOT_FOUND".to_string());
+    }
 
     let ffmpeg_path = get_ffmpeg_path(app);
     let args = build_ffmpeg_args(options);
@@ -242,6 +367,7 @@ mod tests {
             output_path: "out.mp3".to_string(),
             format: AudioFormat::Mp3,
             bitrate_kbps: 192,
+            cover_path: None,
         };
         let args = build_ffmpeg_args(&options);
         assert!(args.contains(&"-vn".to_string()));
@@ -258,6 +384,7 @@ mod tests {
             output_path: "out.m4a".to_string(),
             format: AudioFormat::M4a,
             bitrate_kbps: 256,
+            cover_path: None,
         };
         let args = build_ffmpeg_args(&options);
         assert!(args.contains(&"aac".to_string()));
@@ -271,6 +398,7 @@ mod tests {
             output_path: "out.mp3".to_string(),
             format: AudioFormat::Mp3,
             bitrate_kbps: 128,
+            cover_path: None,
         };
         let args = build_ffmpeg_args(&options);
         assert!(args.contains(&"-nostats".to_string()));
@@ -278,6 +406,61 @@ mod tests {
         assert!(args.contains(&"pipe:2".to_string()));
     }
 
+    #[test]
+    fn build_args_embeds_cover_as_attached_pic() {
+        let options = AudioOptions {
+            input_path: "input.mp4".to_string(),
+            output_path: "out.mp3".to_string(),
+            format: AudioFormat::Mp3,
+            bitrate_kbps: 192,
+            cover_path: Some("cover.jpg".to_string()),
+        };
+        let args = build_ffmpeg_args(&options);
+        let joined = args.join(" ");
+        assert!(!args.contains(&"-vn".to_string()));
+        assert!(joined.contains("-i cover.jpg -map 0:a -map 1:v -c:v copy"));
+        assert!(joined.contains("-disposition:v attached_pic"));
+        assert!(joined.contains("-id3v2_version 3"));
+
+        let options = AudioOptions {
+            output_path: "out.m4a".to_string(),
+            format: AudioFormat::M4a,
+            ..options
+        };
+        assert!(!build_ffmpeg_args(&options).contains(&"-id3v2_version".to_string()));
+    }
+
+    #[test]
+    fn stream_args_remux_or_transcode_with_tags() {
+        let options = AudioOnlyOptions {
+            format: AudioFormat::M4a,
+            bitrate_kbps: None,
+        };
+        let metadata = VideoMetadata {
+            title: "Title".to_string(),
+            uploader: Some("up".to_string()),
+            ..Default::default()
+        };
+        let args = build_stream_args("a.m4s", "out.m4a", &options, true, Some(&metadata), None);
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-i a.m4s -vn -c:a copy"));
+        assert!(joined.contains("-metadata title=Title -metadata artist=up"));
+        assert!(joined.ends_with("-progress pipe:1 -y out.m4a"));
+
+        let options = AudioOnlyOptions {
+            format: AudioFormat::Mp3,
+            bitrate_kbps: None,
+        };
+        let args = build_stream_args("a.m4s", "out.mp3", &options, false, None, None);
+        let joined = args.join(" ");
+        assert!(joined.contains("-c:a libmp3lame -b:a 320k -id3v2_version 3"));
+
+        let args = build_stream_args("a.m4s", "out.mp3", &options, false, None, Some("cover.jpg"));
+        let joined = args.join(" ");
+        assert!(joined.contains("-i cover.jpg -map 0:a -map 1:v"));
+        assert_eq!(args.iter().filter(|a| *a == "-id3v2_version").count(), 1);
+    }
+
     #[test]
     fn is_mp4_checks_extension_case_insensitively() {
         assert!(is_mp4(Path::new("video.mp4")));
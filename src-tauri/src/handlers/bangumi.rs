@@ -0,0 +1,234 @@
+//! Bangumi Season Download Module
+//!
+//! This module queues a whole bangumi season (main episodes and, optionally,
+//! the PV/OVA extra sections) in one call.
+//!
+//! ## Main Features
+//!
+//! - **Availability Check**: Each episode is probed with the playurl API
+//!   before it is queued, using its `status` (VIP-only) and the returned
+//!   preview flag
+//! - **Partial Enqueue**: Episodes that cannot be downloaded in full are
+//!   reported as skipped instead of failing the whole season half way
+//!
+//! Season metadata comes from [`bilibili::fetch_bangumi_info`], so `ep`,
+//! `ss` and `md` IDs are all accepted.
+
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::handlers::bilibili::{self, BangumiId};
+use crate::handlers::cookie::read_cookie;
+use crate::handlers::queue::{self, QueueJob};
+use crate::models::cookie::CookieEntry;
+use crate::models::frontend_dto::{Video, VideoPart};
+use crate::utils::download_archive::ArchiveMode;
+
+/// Episode `status` of VIP-only episodes in the season API.
+const STATUS_VIP_ONLY: i32 = 13;
+
+/// Parallel playurl probes while checking a season.
+const PROBE_CONCURRENCY: usize = 4;
+
+/// Outcome of [`enqueue_bangumi_season`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SeasonEnqueueResult {
+    /// Jobs added to the queue, in episode order
+    pub jobs: Vec<QueueJob>,
+    /// Episodes that were not queued
+    pub skipped: Vec<SkippedEpisode>,
+}
+
+/// Episode left out of a season download.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEpisode {
+    pub ep_id: i64,
+    pub title: String,
+    pub reason: SkipReason,
+}
+
+/// Why an episode was left out of a season download.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// VIP-only episode and the current account cannot play it in full
+    VipOnly,
+    /// Only a preview (first few minutes) is available
+    Preview,
+    /// Playurl request failed (region/copyright restriction, no stream, ...)
+    Unavailable,
+}
+
+/// Enqueues every downloadable episode of a bangumi season.
+///
+/// Episodes are checked before anything is queued, so a VIP-only episode in
+/// the middle of the season no longer stops the episodes after it.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `id` - Any episode, season or media ID of the season
+/// * `include_extras` - Also queue the extra sections (PVs, OVAs, specials)
+/// * `allow_previews` - Queue preview-only episodes instead of skipping them
+/// * `archive_mode` - How items already in the download archive are handled
+///
+/// # Returns
+///
+/// The queued jobs and the skipped episodes with the reason.
+///
+/// # Errors
+///
+/// Returns an error only if the season itself cannot be fetched.
+pub async fn enqueue_bangumi_season(
+    app: &AppHandle,
+    id: BangumiId,
+    include_extras: bool,
+    allow_previews: bool,
+    archive_mode: ArchiveMode,
+) -> Result<SeasonEnqueueResult, String> {
+    log::info!(
+        "[BE] enqueue_bangumi_season: {:?}, include_extras={}, allow_previews={}",
+        id,
+        include_extras,
+        allow_previews
+    );
+    let video = bilibili::fetch_bangumi_info(app, id).await?;
+    let cookies = read_cookie(app)?.unwrap_or_default();
+
+    let mut parts = video.parts.clone();
+    if include_extras {
+        parts.extend(
+            video
+                .extra_sections
+                .iter()
+                .flat_map(|s| s.parts.iter().cloned()),
+        );
+    }
+
+    // Why: parts are moved into the probe futures; a closure over
+    //   `&VideoPart` is not general enough for the `Send` bound of Tauri
+    //   command futures.
+    let cookies = &cookies;
+    let reasons: Vec<Option<SkipReason>> = stream::iter(parts.clone())
+        .map(|part| async move { check_episode(cookies, &part, allow_previews).await })
+        .buffered(PROBE_CONCURRENCY)
+        .collect()
+        .await;
+
+    let mut eligible = Vec::new();
+    let mut skipped = Vec::new();
+    for (part, reason) in parts.into_iter().zip(reasons) {
+        match reason {
+            None => eligible.push(part),
+            Some(reason) => skipped.push(SkippedEpisode {
+                ep_id: part.ep_id.unwrap_or_default(),
+                title: part.part,
+                reason,
+            }),
+        }
+    }
+
+    let batch = Video {
+        parts: eligible,
+        ..video
+    };
+    let options = queue::part_download_options(&batch)
+        .into_iter()
+        .zip(&batch.parts)
+        .map(|(mut opts, part)| {
+            // Each episode is its own archive; keep its AID for history.
+            if let Some(aid) = part.aid {
+                opts.bvid = format!("av{aid}");
+            }
+            opts
+        })
+        .collect();
+    let jobs = queue::enqueue_many(app, options, archive_mode).await;
+
+    log::info!(
+        "[BE] enqueue_bangumi_season: queued {} job(s), skipped {} episode(s)",
+        jobs.len(),
+        skipped.len()
+    );
+    Ok(SeasonEnqueueResult { jobs, skipped })
+}
+
+/// Probes one episode and returns why it must be skipped, if at all.
+async fn check_episode(
+    cookies: &[CookieEntry],
+    part: &VideoPart,
+    allow_previews: bool,
+) -> Option<SkipReason> {
+    let is_vip_only = part.status == Some(STATUS_VIP_ONLY);
+    let Some(ep_id) = part.ep_id else {
+        return Some(SkipReason::Unavailable);
+    };
+    // Why: without cookies a VIP-only episode can at best be a preview, so
+    //   skip the request.
+    if is_vip_only && cookies.is_empty() {
+        return Some(SkipReason::VipOnly);
+    }
+    let probe = bilibili::fetch_bangumi_player_result(cookies, ep_id, part.cid)
+        .await
+        .map(|result| result.is_preview == Some(1));
+    if let Err(e) = &probe {
+        log::warn!(
+            "[BE] enqueue_bangumi_season: ep_id={} is unavailable: {}",
+            ep_id,
+            e
+        );
+    }
+    skip_reason(is_vip_only, probe, allow_previews)
+}
+
+/// Classifies a probe result (`Ok(is_preview)`) into a skip reason.
+fn skip_reason(
+    is_vip_only: bool,
+    probe: Result<bool, String>,
+    allow_previews: bool,
+) -> Option<SkipReason> {
+    match probe {
+        Ok(false) => None,
+        Ok(true) if allow_previews => None,
+        Ok(true) | Err(_) if is_vip_only => Some(SkipReason::VipOnly),
+        Ok(true) => Some(SkipReason::Preview),
+        Err(_) => Some(SkipReason::Unavailable),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_episodes_are_kept() {
+        assert_eq!(skip_reason(false, Ok(false), false), None);
+        assert_eq!(skip_reason(true, Ok(false), false), None);
+    }
+
+    #[test]
+    fn previews_are_skipped_unless_allowed() {
+        assert_eq!(
+            skip_reason(false, Ok(true), false),
+            Some(SkipReason::Preview)
+        );
+        assert_eq!(
+            skip_reason(true, Ok(true), false),
+            Some(SkipReason::VipOnly)
+        );
+        assert_eq!(skip_reason(true, Ok(true), true), None);
+    }
+
+    #[test]
+    fn failed_probes_are_reported_by_status() {
+        let err = || Err("ERR::BANGUMI_NO_DASH".to_string());
+        assert_eq!(skip_reason(true, err(), true), Some(SkipReason::VipOnly));
+        assert_eq!(
+            skip_reason(false, err(), false),
+            Some(SkipReason::Unavailable)
+        );
+    }
+}
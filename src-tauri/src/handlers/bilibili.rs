@@ -34,14 +34,21 @@
 //! - `ERR::API_ERROR` - Generic API request failure
 //! - `ERR::BANGUMI_*` - Bangumi-specific errors (VIP only, region restricted, etc.)
 
-use crate::utils::codec::{select_video_stream, VideoStreamSelection, CODECID_AVC};
+use crate::utils::codec::{
+    codec_label, select_video_stream, select_video_stream_per_quality, AudioStreamKind,
+    DynamicRange, OutputContainer, VideoCodecPriority, VideoStreamSelection, CODECID_AVC,
+    QUALITY_8K,
+};
+use crate::utils::danmaku::DanmakuOptions;
+use crate::utils::download_archive::ArchiveMode;
+use crate::utils::filename_template::{render_template, TemplateValues};
 use serde::{Deserialize, Serialize};
 use tauri::Emitter;
 
 /// Subtitle configuration options for video downloads.
 ///
 /// Specifies how subtitles should be embedded into the output file.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubtitleOptions {
     /// Subtitle embedding mode: "off" (no subtitles), "soft" (soft-sub), or "hard" (burned-in)
@@ -54,10 +61,46 @@ pub struct SubtitleOptions {
     pub subtitles: Vec<SubtitleInfo>,
 }
 
+/// What a DASH download keeps on disk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StreamMode {
+    /// Merge video and audio into one file and delete the streams
+    #[default]
+    Merged,
+    /// Merge, and also keep the untouched streams next to the output as
+    /// `{name}.video.m4v` and `{name}.audio.m4a` (`.audio.mp4` for Dolby
+    /// and Hi-Res audio)
+    KeepStreams,
+    /// Download only the video stream and write a silent video file
+    VideoOnly,
+}
+
+impl StreamMode {
+    /// Returns the mode label stored in the history entry, or `None` for a
+    /// plain merged download.
+    pub fn history_label(self) -> Option<&'static str> {
+        match self {
+            StreamMode::Merged => None,
+            StreamMode::KeepStreams => Some("streams"),
+            StreamMode::VideoOnly => Some("video"),
+        }
+    }
+
+    /// Returns the archive kind a DASH download in this mode is recorded
+    /// as; only video-only downloads lack the audio of a full download.
+    pub fn archive_kind(self) -> ArchiveKind {
+        match self {
+            StreamMode::Merged | StreamMode::KeepStreams => ArchiveKind::Video,
+            StreamMode::VideoOnly => ArchiveKind::VideoOnly,
+        }
+    }
+}
+
 /// Subtitle information passed from frontend.
 ///
 /// Contains all data needed to download and process a subtitle.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SubtitleInfo {
     /// Language code (e.g., "zh-CN", "en")
@@ -119,7 +162,7 @@ pub struct SubtitleResolvedPayload {
 ///
 /// Groups all parameters required for downloading a video part,
 /// preventing function parameter bloat.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DownloadOptions {
     /// Bilibili video ID (BV identifier, e.g., "BV1xx411c7XD")
@@ -152,25 +195,71 @@ pub struct DownloadOptions {
     /// Episode ID for bangumi content (optional)
     #[serde(default)]
     pub ep_id: Option<i64>,
+    /// Watch Later item (aid) to remove once every part sharing this
+    /// `parent_id` has completed (queue downloads only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub watch_later_aid: Option<i64>,
+    /// Danmaku rendering options; when set, danmaku are fetched and stored
+    /// as raw XML next to the video. The ASS track is embedded as an extra
+    /// soft-sub track in MKV output and written as `{name}.danmaku.ass` next
+    /// to MP4/MOV output, whose `mov_text` subtitles cannot move or place
+    /// text
+    #[serde(default)]
+    pub danmaku: Option<DanmakuOptions>,
+    /// Descriptive metadata written as MP4 container tags (DASH downloads)
+    #[serde(default)]
+    pub metadata: Option<VideoMetadata>,
+    /// Chapters written into the merged MP4; `None` fetches them from the
+    /// Player v2 API before the merge
+    #[serde(default)]
+    pub chapters: Option<Vec<Chapter>>,
+    /// Audio-only mode; when set, only the DASH audio stream is downloaded
+    /// and converted to the chosen format (durl streams, which embed the
+    /// audio, are downloaded as usual)
+    #[serde(default)]
+    pub audio_only: Option<AudioOnlyOptions>,
+    /// Whether the raw streams are kept, or only the video is downloaded
+    /// (DASH only; ignored in audio-only mode)
+    #[serde(default)]
+    pub stream_mode: StreamMode,
+    /// Values of the filename template; when the settings define a
+    /// template, the output filename is rendered from these instead of
+    /// using `filename`
+    #[serde(default)]
+    pub template_values: Option<TemplateValues>,
+    /// How an item already in the download archive is handled; `None`
+    /// downloads it again next to the existing file without checking the
+    /// archive (finished downloads are recorded either way)
+    #[serde(default)]
+    pub archive_mode: Option<ArchiveMode>,
 }
 
 use crate::constants::REFERER;
+use crate::handlers::audio::AudioOnlyOptions;
+use crate::handlers::collection;
 use crate::handlers::cookie::read_cookie;
 use crate::handlers::settings;
 use crate::models::bilibili_api::{
-    BangumiPlayerApiResponse, BangumiPlayerResult, BangumiSeasonApiResponse, PlayerV2ApiResponse,
-    UserApiResponse, WatchHistoryApiResponse, WebInterfaceApiResponse, XPlayerApiResponse,
-    XPlayerApiResponseData, XPlayerApiResponseVideo,
+    ArchiveTagsApiResponse, BangumiEpisode, BangumiMediaApiResponse, BangumiPlayerApiResponse,
+    BangumiPlayerResult, BangumiSeasonApiResponse, BangumiSeasonResult, PlayerV2ApiData,
+    PlayerV2ApiResponse, PlayerV2ViewPoint, UserApiResponse, WatchHistoryApiResponse,
+    WebInterfaceApiResponse, XPlayerApiResponse, XPlayerApiResponseData, XPlayerApiResponseVideo,
 };
 use crate::models::cookie::CookieEntry;
 use crate::models::frontend_dto::{
-    DownloadRetrying, Quality, SubtitleDto, Thumbnail, UserData, Video, VideoPart,
-    WatchHistoryCursor, WatchHistoryEntry,
+    Chapter, DownloadRetrying, Quality, SubtitleDto, Thumbnail, UserData, Video, VideoMetadata,
+    VideoPart, VideoSection, WatchHistoryCursor, WatchHistoryEntry,
 };
-use crate::models::settings::Settings;
+use crate::models::settings::{Settings, TitleReplacement};
+use crate::utils::download_archive::{self, ArchiveKey, ArchiveKind};
+use crate::utils::download_manifest;
 use crate::utils::downloads::download_url;
-use crate::utils::paths::get_lib_path;
+use crate::utils::metadata::ffmetadata_chapters;
+use crate::utils::paths::{get_download_archive_path, get_lib_path};
+use crate::utils::proxy::{self, ProxyTarget};
+use crate::utils::sanitize::{sanitize_filename, FILENAME_HEADROOM_BYTES, MAX_FILENAME_BYTES};
 use crate::{constants::USER_AGENT, models::frontend_dto::User};
+use futures::stream::{self, StreamExt};
 use reqwest::header;
 use reqwest::Client;
 use std::collections::BTreeMap;
@@ -182,7 +271,8 @@ use tauri::AppHandle;
 ///
 /// Creates a new HTTP client configured with the application's user agent
 /// for making requests to Bilibili's API. The client is configured with
-/// connection pooling and keep-alive for efficient repeated requests.
+/// connection pooling and keep-alive for efficient repeated requests, and
+/// routed through the API proxy when one is configured.
 ///
 /// # Returns
 ///
@@ -199,7 +289,23 @@ use tauri::AppHandle;
 /// let response = client.get("https://api.bilibili.com/...").send().await?;
 /// ```
 pub fn build_client() -> Result<Client, String> {
-    Client::builder()
+    proxy::apply(Client::builder(), ProxyTarget::Api)
+        .user_agent(USER_AGENT)
+        .build()
+        .map_err(|e| format!("failed to build client: {e}"))
+}
+
+/// Builds a reqwest HTTP client for CDN and image hosts.
+///
+/// Like [`build_client`], but routed through the proxy when its scope
+/// covers CDN traffic, so probes and small CDN fetches (stream size checks,
+/// covers) take the same route as the downloads themselves.
+///
+/// # Errors
+///
+/// Returns an error if the client builder fails to create the client.
+pub fn build_cdn_client() -> Result<Client, String> {
+    proxy::apply(Client::builder(), ProxyTarget::Cdn)
         .user_agent(USER_AGENT)
         .build()
         .map_err(|e| format!("failed to build client: {e}"))
@@ -286,35 +392,53 @@ fn validate_bangumi_response(code: i64, message: &str) -> Result<(), String> {
     }
 }
 
-/// Extracts bangumi episode ID from a redirect URL.
+/// Bangumi identifier as found in a URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BangumiId {
+    /// Episode ID (`/bangumi/play/ep3051843`)
+    Episode(i64),
+    /// Season ID (`/bangumi/play/ss41410`)
+    Season(i64),
+    /// Media ID (`/bangumi/media/md28234679`)
+    Media(i64),
+}
+
+/// Extracts a bangumi episode, season or media ID from a URL.
 ///
-/// Parses URLs like `https://www.bilibili.com/bangumi/play/ep3051843`
-/// and returns the episode ID (3051843). This is used when short URLs
-/// or player links redirect to bangumi episodes.
+/// Parses URLs like `https://www.bilibili.com/bangumi/play/ep3051843`,
+/// `https://www.bilibili.com/bangumi/play/ss41410` and
+/// `https://www.bilibili.com/bangumi/media/md28234679`. This is used when
+/// short URLs or player links redirect to bangumi pages.
 ///
 /// # Arguments
 ///
-/// * `url` - The redirect URL to parse
+/// * `url` - The URL to parse
 ///
 /// # Returns
 ///
-/// Returns `Some(ep_id)` if the URL matches the bangumi pattern, `None` otherwise.
+/// Returns `Some(id)` if the URL matches a bangumi pattern, `None` otherwise.
 ///
 /// # Example
 ///
 /// ```
 /// let url = "https://www.bilibili.com/bangumi/play/ep3051843";
-/// assert_eq!(extract_bangumi_ep_id(url), Some(3051843));
+/// assert_eq!(parse_bangumi_url(url), Some(BangumiId::Episode(3051843)));
 /// ```
-fn extract_bangumi_ep_id(url: &str) -> Option<i64> {
-    url.split("/bangumi/play/ep").nth(1).and_then(|suffix| {
-        suffix
-            .chars()
-            .take_while(|c| c.is_ascii_digit())
-            .collect::<String>()
-            .parse()
-            .ok()
-    })
+pub fn parse_bangumi_url(url: &str) -> Option<BangumiId> {
+    let numeric_suffix = |marker: &str| -> Option<i64> {
+        url.split(marker).nth(1).and_then(|suffix| {
+            suffix
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .ok()
+        })
+    };
+    numeric_suffix("/bangumi/play/ep")
+        .map(BangumiId::Episode)
+        .or_else(|| numeric_suffix("/bangumi/play/ss").map(BangumiId::Season))
+        .or_else(|| numeric_suffix("/bangumi/media/md").map(BangumiId::Media))
 }
 
 /// Downloads a bangumi episode using durl format (direct MP4 URL).
@@ -327,7 +451,7 @@ fn extract_bangumi_ep_id(url: &str) -> Option<i64> {
 /// 1. Register cancellation token
 /// 2. Select requested quality or best quality entry
 /// 3. Send quality resolution event to frontend
-/// 4. Check disk space
+/// 4. Determine the output path and check disk space
 /// 5. Direct download with retry logic
 /// 6. Save download history (async)
 /// 7. Remove cancellation token
@@ -336,7 +460,6 @@ fn extract_bangumi_ep_id(url: &str) -> Option<i64> {
 ///
 /// * `app` - Tauri application handle
 /// * `options` - Download options (bvid, cid, quality, etc.)
-/// * `output_path` - Output file path
 /// * `cookie_header` - Cookie header for authentication
 /// * `player_result` - Bangumi player API response
 ///
@@ -355,7 +478,6 @@ fn extract_bangumi_ep_id(url: &str) -> Option<i64> {
 async fn download_bangumi_durl(
     app: &AppHandle,
     options: &DownloadOptions,
-    output_path: &Path,
     cookie_header: &str,
     cookies: &[CookieEntry],
     player_result: BangumiPlayerResult,
@@ -426,10 +548,22 @@ async fn download_bangumi_durl(
     let settings = settings::get_settings(app).await.ok();
     let segment_concurrency = Settings::resolve_segment_concurrency(&settings);
 
+    // durl streams are MP4 files written as-is.
+    let output_path = part_output_path(
+        app,
+        options,
+        settings.as_ref(),
+        Some(resolved_quality),
+        Some(CODECID_AVC),
+        "mp4",
+    )
+    .await?;
+    create_output_dir(&output_path).await?;
+
     // Capacity check
     if let Some(vs) = head_content_length(video_url, Some(cookie_header)).await {
         let total_needed = vs + (5 * 1024 * 1024); // 5MB buffer
-        ensure_free_space(output_path, total_needed)?;
+        ensure_free_space(&output_path, total_needed)?;
     }
 
     // Refetch inputs for attempt > 1 (bilibili signed URLs expire after 120 min).
@@ -439,7 +573,7 @@ async fn download_bangumi_durl(
     let bd_ep_id = options.ep_id;
     let bd_video_url = video_url.clone();
     let bd_backup_urls = backup_urls.clone();
-    let bd_output_path = output_path.to_path_buf();
+    let bd_output_path = output_path.clone();
     let bd_cookie_header = cookie_header.to_string();
     let bd_download_id = options.download_id.clone();
     // Download directly. Capture the result so we always remove the token
@@ -510,14 +644,27 @@ async fn download_bangumi_durl(
     match result {
         Ok(()) => {
             let output_path_str = output_path.to_string_lossy().to_string();
-            let actual_file_size = tokio::fs::metadata(output_path).await.ok().map(|m| m.len());
+            let actual_file_size = tokio::fs::metadata(&output_path)
+                .await
+                .ok()
+                .map(|m| m.len());
             // Save to history asynchronously (success only)
-            spawn_save_to_history(app, options, actual_file_size);
+            spawn_save_to_history(
+                app,
+                options,
+                &output_path,
+                actual_file_size,
+                None,
+                None,
+                Vec::new(),
+            );
+            record_in_archive(app, options, ArchiveKind::Video, Some(resolved_quality)).await;
             Ok(output_path_str)
         }
         Err(e) => {
             // Remove partial output on failure/cancel to avoid leftover garbage.
-            let _ = tokio::fs::remove_file(output_path).await;
+            let _ = tokio::fs::remove_file(&output_path).await;
+            download_manifest::remove_manifest(&output_path).await;
             Err(e)
         }
     }
@@ -526,11 +673,13 @@ async fn download_bangumi_durl(
 /// Downloads a Bilibili video with the specified quality settings.
 ///
 /// This function orchestrates the entire download process:
-/// 1. Output path determination with auto-rename handling
+/// 1. Download archive check
 /// 2. Cookie presence validation
 /// 3. Video details and stream URL fetching
-/// 4. Pre-download disk space check
-/// 5. Parallel audio/video stream download with retry logic
+/// 4. Output path determination with auto-rename handling (the filename
+///    template may use the resolved quality and codec) and disk space check
+/// 5. Parallel audio/video stream download with retry logic, resuming
+///    partial temp streams left by an interrupted earlier run
 /// 6. Stream merging via ffmpeg (DASH) or direct save (durl)
 ///
 /// Sends progress updates to the frontend throughout the process.
@@ -554,6 +703,9 @@ async fn download_bangumi_durl(
 /// - Download fails after retry attempts (`ERR::NETWORK`)
 /// - ffmpeg merge fails (`ERR::MERGE_FAILED`)
 /// - Download is cancelled (`ERR::CANCELLED`)
+/// - The item is already archived in [`ArchiveMode::Skip`]
+///   (`ERR::ARCHIVED`, followed by the path its history entry recorded;
+///   see [`download_archive::archived_error`])
 pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Result<String, String> {
     use crate::handlers::concurrency::DOWNLOAD_CANCEL_REGISTRY;
 
@@ -580,14 +732,28 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
         return Err("ERR::CANCELLED".to_string());
     }
 
+    // 1. Skip items already in the download archive before any network work.
+    //    The output path is only determined once the streams are resolved,
+    //    since the filename template may use their quality and codec.
+    let archive_key = requested_archive_key(options);
+    if options.archive_mode == Some(ArchiveMode::Skip)
+        && download_archive::contains(&get_download_archive_path(app), &archive_key).await
+    {
+        log::info!(
+            "[BE] download_video: skipping archived download id={}, bvid={}, cid={}",
+            options.download_id,
+            options.bvid,
+            options.cid
+        );
+        let output_path = archived_output_path(app, &archive_key).await;
+        return Err(download_archive::archived_error(output_path.as_deref()));
+    }
+
     // Register cancellation token for this download
     let cancel_token = DOWNLOAD_CANCEL_REGISTRY
         .register(&options.download_id)
         .await;
 
-    // 1. Determine output file path + auto-rename
-    let output_path = auto_rename(&build_output_path(app, &options.filename).await?);
-
     // 2. Get cookies (WBI signing enables non-logged-in usage)
     let cookies = read_cookie(app)?.unwrap_or_default();
     let cookie_header = build_cookie_header(&cookies);
@@ -602,15 +768,8 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
 
         // durl format (direct MP4 URL): consume player_result and return early.
         if player_result.dash.is_none() {
-            return download_bangumi_durl(
-                app,
-                options,
-                &output_path,
-                &cookie_header,
-                &cookies,
-                player_result,
-            )
-            .await;
+            return download_bangumi_durl(app, options, &cookie_header, &cookies, player_result)
+                .await;
         }
         // DASH format: convert the already-fetched result instead of re-fetching.
         (
@@ -641,6 +800,16 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
     // all early returns funnel through the cleanup below — this path otherwise
     // bypasses download_video's final cleanup (remove + clear_cancelled).
     if data.dash.is_none() {
+        // durl streams are MP4 files written as-is.
+        let output_path = part_output_path(
+            app,
+            options,
+            settings.as_ref(),
+            data.quality,
+            Some(CODECID_AVC),
+            "mp4",
+        )
+        .await?;
         let result: Result<String, String> = async {
             let durl_segments = data
                 .durl
@@ -678,6 +847,7 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
             )
             .ok();
 
+            create_output_dir(&output_path).await?;
             if let Some(vs) = head_content_length(&video_url, Some(&cookie_header)).await {
                 ensure_free_space(&output_path, vs + 5 * 1024 * 1024)?;
             }
@@ -743,13 +913,34 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
                 .await
                 .ok()
                 .map(|m| m.len());
-            spawn_save_to_history(app, options, actual_file_size);
+            spawn_save_to_history(
+                app,
+                options,
+                &output_path,
+                actual_file_size,
+                None,
+                None,
+                Vec::new(),
+            );
+            record_in_archive(
+                app,
+                options,
+                ArchiveKind::Video,
+                Some(resolved_video_quality),
+            )
+            .await;
             Ok(output_path_str)
         }
         .await;
 
         // Cleanup: remove token and clear the pre-cancel flag (this path
         // bypasses download_video's final cleanup).
+        // Note: durl downloads write straight to the user-visible output and
+        //   auto_rename picks a fresh name on the next run, so a resume
+        //   manifest left beside it could never be used; drop it.
+        if result.is_err() {
+            download_manifest::remove_manifest(&output_path).await;
+        }
         DOWNLOAD_CANCEL_REGISTRY.remove(&options.download_id).await;
         DOWNLOAD_CANCEL_REGISTRY
             .clear_cancelled(&options.download_id)
@@ -759,27 +950,162 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
     }
 
     let dash_data = data.dash.unwrap();
+    // Standard AAC streams plus the VIP-only Dolby/Hi-Res streams.
+    let audio_streams = dash_data.audio_streams();
 
-    // Diagnostic: record the audio stream landscape and any VIP-only
-    // objects (dolby/flac) present in the manifest. Does not affect
-    // selection; lets us confirm from reporter logs whether a VIP account's
-    // manifest contained Hi-Res/Dolby entries (issue #467 investigation).
+    // Diagnostic: record the audio stream landscape (including Dolby/Hi-Res
+    // entries) and any unparsed DASH objects present in the manifest.
     log::info!(
         "[BE] download_video: dash audio landscape id={} audio_ids={:?} extra_keys={:?}",
         options.download_id,
-        dash_data.audio.iter().map(|a| a.id).collect::<Vec<_>>(),
+        audio_streams.iter().map(|a| a.id).collect::<Vec<_>>(),
         dash_data.extra.keys().collect::<Vec<_>>(),
     );
 
+    let audio_quality = options
+        .audio_quality
+        .unwrap_or(audio_streams.first().map(|a| a.id).unwrap_or(30280));
+    let (audio_url, audio_backup_urls, raw_audio_fallback) =
+        select_stream_url(&audio_streams, audio_quality)?;
+    // Only warn when the user explicitly chose an audio quality.
+    let audio_quality_fallback = options.audio_quality.is_some() && raw_audio_fallback;
+    // Get the actual resolved audio quality ID
+    let resolved_audio_quality = audio_streams
+        .iter()
+        .find(|a| a.base_url == audio_url)
+        .map(|a| a.id);
+
+    // Audio-only mode: fetch just the audio stream and convert it directly,
+    // skipping the video stream and the merge. Wrapped in a block like the
+    // durl path so all early returns funnel through the cleanup below.
+    if let Some(audio_only) = &options.audio_only {
+        let page = options.page.unwrap_or(1);
+        let output_path = part_output_path(
+            app,
+            options,
+            settings.as_ref(),
+            None,
+            None,
+            audio_only.format.extension(),
+        )
+        .await?;
+        let audio_kind = AudioStreamKind::from_quality(resolved_audio_quality);
+        let temps = DashTempFiles::new(app, options);
+
+        let result: Result<String, String> = async {
+            app.emit(
+                "download-quality-resolved",
+                QualityResolvedPayload {
+                    download_id: options.download_id.clone(),
+                    page,
+                    // No video stream is downloaded in audio-only mode.
+                    video_quality: 0,
+                    video_quality_fallback: false,
+                    video_codecid: 0,
+                    video_codec_fallback: false,
+                    audio_quality: resolved_audio_quality,
+                    audio_quality_fallback,
+                    is_preview: bangumi_preview_info,
+                },
+            )
+            .ok();
+
+            create_output_dir(&output_path).await?;
+            if let Some(size) = head_content_length(&audio_url, Some(&cookie_header)).await {
+                ensure_free_space(&output_path, size + 5 * 1024 * 1024)?;
+            }
+
+            let permit = acquire_video_permit().await?;
+
+            let audio_refetch_ctx = AudioRefetchCtx {
+                cookies: cookies.clone(),
+                bvid: options.bvid.clone(),
+                cid: options.cid,
+                ep_id: options.ep_id,
+                audio_quality: resolved_audio_quality,
+            };
+            download_audio_with_fallback(
+                app,
+                &options.download_id,
+                audio_url.clone(),
+                audio_backup_urls.clone(),
+                temps.audio.clone(),
+                Some(cookie_header.clone()),
+                &fallback_audio_streams(&audio_streams, audio_kind),
+                &audio_refetch_ctx,
+            )
+            .await?;
+
+            if cancel_token.is_cancelled() {
+                return Err("ERR::CANCELLED".to_string());
+            }
+
+            let cover = PartCover::fetch(options, settings.as_ref(), &temps.lib_path).await;
+
+            crate::handlers::ffmpeg::convert_audio_stream(
+                app,
+                &temps.audio,
+                &output_path,
+                &options.download_id,
+                Some((options.duration_seconds * 1000) as u64),
+                audio_kind,
+                audio_only,
+                options.metadata.as_ref(),
+                cover.embedded(),
+                Some(cancel_token.clone()),
+            )
+            .await
+            .map_err(|e| {
+                log::error!(
+                    "[BE] download_video: audio conversion failed id={}: {}",
+                    options.download_id,
+                    e
+                );
+                merge_error(e)
+            })?;
+            drop(permit);
+
+            let _ = tokio::fs::remove_file(&temps.audio).await;
+            cover.finish(&output_path).await;
+
+            let actual_file_size = tokio::fs::metadata(&output_path)
+                .await
+                .ok()
+                .map(|m| m.len());
+            log::info!(
+                "[BE] download_video: audio-only download complete id={}, size={:?}bytes",
+                options.download_id,
+                actual_file_size
+            );
+            spawn_save_to_history(
+                app,
+                options,
+                &output_path,
+                actual_file_size,
+                None,
+                Some("audio"),
+                Vec::new(),
+            );
+            record_in_archive(app, options, ArchiveKind::Audio, resolved_audio_quality).await;
+            Ok(output_path.to_string_lossy().to_string())
+        }
+        .await;
+
+        temps
+            .finish(&options.download_id, &result, &[&temps.audio])
+            .await;
+        return result;
+    }
+
     // Resolve codec priority and filter streams. Falls back to all streams
     // when the preferred codec is unavailable so the download never fails.
-    let (streams_for_selection, codec_selection) =
-        select_streams_by_codec_priority(app, &dash_data.video).await;
-
     // Fallback if selected quality is unavailable (first = highest quality)
     // None means best available → -1 won't match any real quality ID.
     let requested_quality = options.quality.unwrap_or(-1);
 
+    let (streams_for_selection, codec_selection) =
+        select_streams_by_codec_priority(app, &dash_data.video, requested_quality).await;
+
     let (video_url, video_backup_urls, raw_video_fallback) =
         select_stream_url(&streams_for_selection, requested_quality)?;
     // Only treat as fallback when the user explicitly selected a quality.
@@ -803,19 +1129,30 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
         .map(|sel| sel.fallback)
         .unwrap_or(true);
 
-    let audio_quality = options
-        .audio_quality
-        .unwrap_or(dash_data.audio.first().map(|a| a.id).unwrap_or(30280));
-    let (audio_url, audio_backup_urls, raw_audio_fallback) =
-        select_stream_url(&dash_data.audio, audio_quality)?;
-    // Same logic: only warn when the user explicitly chose an audio quality.
-    let audio_quality_fallback = options.audio_quality.is_some() && raw_audio_fallback;
-    // Get the actual resolved audio quality ID
-    let resolved_audio_quality = dash_data
-        .audio
-        .iter()
-        .find(|a| a.base_url == audio_url)
-        .map(|a| a.id);
+    // Hi-Res FLAC is merged into MKV; everything else uses the configured
+    // container.
+    let video_only = options.stream_mode == StreamMode::VideoOnly;
+    let audio_kind = if video_only {
+        AudioStreamKind::default()
+    } else {
+        AudioStreamKind::from_quality(resolved_audio_quality)
+    };
+    let container = settings
+        .as_ref()
+        .and_then(|s| s.output_container)
+        .unwrap_or_default()
+        .for_audio(audio_kind);
+    // The filename template may use the resolved quality and codec.
+    let output_path = part_output_path(
+        app,
+        options,
+        settings.as_ref(),
+        Some(resolved_video_quality),
+        Some(resolved_video_codecid),
+        container.extension(),
+    )
+    .await?;
+    create_output_dir(&output_path).await?;
 
     log::info!(
         "[BE] download_video: resolved audio quality id={:?} (requested {:?}) for id={}",
@@ -835,8 +1172,8 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
             video_quality_fallback,
             video_codecid: resolved_video_codecid,
             video_codec_fallback,
-            audio_quality: resolved_audio_quality,
-            audio_quality_fallback,
+            audio_quality: resolved_audio_quality.filter(|_| !video_only),
+            audio_quality_fallback: audio_quality_fallback && !video_only,
             is_preview: bangumi_preview_info,
         },
     )
@@ -844,26 +1181,24 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
 
     // 5. Pre-check disk space (skip if size cannot be determined)
     let video_size = head_content_length(&video_url, Some(&cookie_header)).await;
-    let audio_size = head_content_length(&audio_url, Some(&cookie_header)).await;
+    let audio_size = if video_only {
+        Some(0)
+    } else {
+        head_content_length(&audio_url, Some(&cookie_header)).await
+    };
     if let (Some(vs), Some(asz)) = (video_size, audio_size) {
         let total_needed = vs + asz + (5 * 1024 * 1024); // 5MB buffer
         ensure_free_space(&output_path, total_needed)?;
     }
 
     // 6. Generate temp file paths
-    let lib_path = get_lib_path(app);
-    let temp_video_path = lib_path.join(format!("temp_video_{}.m4s", options.download_id));
-    let temp_audio_path = lib_path.join(format!("temp_audio_{}.m4s", options.download_id));
+    let temps = DashTempFiles::new(app, options);
 
     // Result to track success/failure for cleanup
     let result = async {
         // 7. Acquire semaphore + parallel download + merge
         // Semaphore is held until merge completes; concurrency is based on merge load
-        let permit = crate::handlers::concurrency::VIDEO_SEMAPHORE
-            .clone()
-            .acquire_owned()
-            .await
-            .map_err(|e| format!("Failed to acquire video semaphore permit: {}", e))?;
+        let permit = acquire_video_permit().await?;
 
         let cookie = Some(cookie_header);
 
@@ -876,16 +1211,23 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
             ep_id: options.ep_id,
             audio_quality: resolved_audio_quality,
         };
-        let audio_download = download_audio_with_fallback(
-            app,
-            &options.download_id,
-            audio_url.clone(),
-            audio_backup_urls.clone(),
-            temp_audio_path.clone(),
-            cookie.clone(),
-            &dash_data.audio,
-            &audio_refetch_ctx,
-        );
+        let fallback_audio_streams = fallback_audio_streams(&audio_streams, audio_kind);
+        let audio_download = async {
+            if video_only {
+                return Ok(());
+            }
+            download_audio_with_fallback(
+                app,
+                &options.download_id,
+                audio_url.clone(),
+                audio_backup_urls.clone(),
+                temps.audio.clone(),
+                cookie.clone(),
+                &fallback_audio_streams,
+                &audio_refetch_ctx,
+            )
+            .await
+        };
         // Refetch inputs for attempt > 1 (bilibili signed URLs expire after
         // 120 min). Cloned here because the move closure must own them, while
         // `cookie` is shared with audio_download and `cookies` with subtitle prep.
@@ -897,7 +1239,7 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
         let v_download_id = options.download_id.clone();
         let v_video_url = video_url.clone();
         let v_video_backups = video_backup_urls.clone();
-        let v_temp_video_path = temp_video_path.clone();
+        let v_temp_video_path = temps.video.clone();
         let v_cookie = cookie.clone();
         let video_download = retry_download(
             app,
@@ -971,7 +1313,7 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
                 &options.bvid,
                 options.cid,
                 &options.download_id,
-                &lib_path,
+                &temps.lib_path,
                 Some(options.duration_seconds as f64),
             )
             .await?;
@@ -1005,6 +1347,67 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
             .ok();
         }
 
+        // Danmaku are an extra soft-sub track (MKV) or an ASS file next to the
+        // output (MP4/MOV); a failure only loses the track.
+        let mut danmaku_xml = None;
+        let mut danmaku_ass = None;
+        let subtitle_mode = match &options.danmaku {
+            Some(danmaku_opts) => {
+                match crate::handlers::danmaku::prepare_danmaku_track(
+                    cookie.as_deref(),
+                    options.cid,
+                    options.duration_seconds,
+                    &options.download_id,
+                    &temps.lib_path,
+                    danmaku_opts,
+                )
+                .await
+                {
+                    Ok(track) => {
+                        log::info!(
+                            "[BE] download_video: prepared {} danmaku id={}",
+                            track.count,
+                            options.download_id
+                        );
+                        danmaku_xml = Some(track.raw_xml);
+                        // Why: mov_text drops the `\move`/`\pos` tags that
+                        //   make danmaku scroll, so only Matroska embeds them.
+                        if !container.is_matroska() {
+                            danmaku_ass = Some(track.subtitle.path);
+                            subtitle_mode
+                        } else {
+                            match subtitle_mode {
+                                crate::handlers::ffmpeg::MergeMode::None => {
+                                    crate::handlers::ffmpeg::MergeMode::SoftSub(vec![
+                                        track.subtitle,
+                                    ])
+                                }
+                                crate::handlers::ffmpeg::MergeMode::SoftSub(mut subs) => {
+                                    subs.push(track.subtitle);
+                                    crate::handlers::ffmpeg::MergeMode::SoftSub(subs)
+                                }
+                                // Why: only one track can be burned in; the raw
+                                //   XML is still stored.
+                                hard => {
+                                    let _ = tokio::fs::remove_file(&track.subtitle.path).await;
+                                    hard
+                                }
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!(
+                            "[BE] download_video: danmaku unavailable id={}: {}",
+                            options.download_id,
+                            e
+                        );
+                        subtitle_mode
+                    }
+                }
+            }
+            None => subtitle_mode,
+        };
+
         // Keep subtitle file paths for cleanup
         let subtitle_paths: Vec<PathBuf> = match &subtitle_mode {
             crate::handlers::ffmpeg::MergeMode::SoftSub(subs) => {
@@ -1016,6 +1419,29 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
             _ => vec![],
         };
 
+        let cover = PartCover::fetch(options, settings.as_ref(), &temps.lib_path).await;
+
+        // Chapters are best effort too; bangumi episodes have none.
+        let chapters = match &options.chapters {
+            Some(chapters) => chapters.clone(),
+            None if options.ep_id.is_none() => {
+                fetch_part_chapters(&cookies, &options.bvid, options.cid).await
+            }
+            None => Vec::new(),
+        };
+        let has_chapters = !chapters.is_empty()
+            && match tokio::fs::write(&temps.chapters, ffmetadata_chapters(&chapters)).await {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!(
+                        "[BE] download_video: failed to write chapters id={}: {}",
+                        options.download_id,
+                        e
+                    );
+                    false
+                }
+            };
+
         // Check cancellation before starting merge. A cancel that arrived
         // during the final chunk write can slip past download_url's check
         // (the chunk was already written), so without this guard we'd spawn
@@ -1031,12 +1457,20 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
         );
         crate::handlers::ffmpeg::merge_avs(
             app,
-            &temp_video_path,
-            &temp_audio_path,
+            &temps.video,
+            Some(temps.audio.as_path()).filter(|_| !video_only),
             &output_path,
             Some(options.download_id.clone()),
             Some((options.duration_seconds * 1000) as u64),
             subtitle_mode,
+            crate::handlers::ffmpeg::MergeOptions {
+                metadata: options.metadata.as_ref(),
+                cover_path: cover.embedded(),
+                chapters_path: Some(temps.chapters.as_path()).filter(|_| has_chapters),
+                container,
+                audio_kind,
+                dynamic_range: DynamicRange::from_quality(resolved_video_quality),
+            },
             Some(cancel_token.clone()),
         )
         .await
@@ -1046,24 +1480,61 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
                 options.download_id,
                 e
             );
-            // Preserve ERR::CANCELLED so the frontend can detect cancellation
-            // (otherwise it would be masked as ERR::MERGE_FAILED).
-            if e.contains("CANCELLED") {
-                e
-            } else {
-                String::from("ERR::MERGE_FAILED")
-            }
+            merge_error(e)
         })?;
 
         // Release semaphore after merge completes
         drop(permit);
 
-        // Delete temp files
-        let _ = tokio::fs::remove_file(&temp_video_path).await;
-        let _ = tokio::fs::remove_file(&temp_audio_path).await;
+        // Delete temp files, or keep the raw streams next to the output
+        let mut kept_streams = Vec::new();
+        if options.stream_mode == StreamMode::KeepStreams {
+            let streams = [
+                (&temps.video, output_path.with_extension("video.m4v")),
+                (
+                    &temps.audio,
+                    output_path.with_extension(format!("audio.{}", audio_kind.stream_extension())),
+                ),
+            ];
+            for (temp_path, kept_path) in streams {
+                match keep_temp_stream(temp_path, &kept_path).await {
+                    Ok(()) => kept_streams.push(kept_path),
+                    Err(e) => log::warn!(
+                        "[BE] download_video: failed to keep stream {}: {}",
+                        kept_path.display(),
+                        e
+                    ),
+                }
+            }
+        }
+        let _ = tokio::fs::remove_file(&temps.video).await;
+        let _ = tokio::fs::remove_file(&temps.audio).await;
         for sub_path in subtitle_paths {
             let _ = tokio::fs::remove_file(&sub_path).await;
         }
+        cover.finish(&output_path).await;
+        let _ = tokio::fs::remove_file(&temps.chapters).await;
+        if let Some(xml) = danmaku_xml {
+            let xml_path = crate::handlers::danmaku::raw_path_for(&output_path);
+            if let Err(e) = tokio::fs::write(&xml_path, xml).await {
+                log::warn!(
+                    "[BE] download_video: failed to store danmaku {}: {}",
+                    xml_path.display(),
+                    e
+                );
+            }
+        }
+        if let Some(temp_ass_path) = danmaku_ass {
+            let ass_path = crate::handlers::danmaku::ass_path_for(&output_path);
+            if let Err(e) = tokio::fs::copy(&temp_ass_path, &ass_path).await {
+                log::warn!(
+                    "[BE] download_video: failed to store danmaku {}: {}",
+                    ass_path.display(),
+                    e
+                );
+            }
+            let _ = tokio::fs::remove_file(&temp_ass_path).await;
+        }
 
         // Keep output path (clone for history saving)
         let output_path_str = output_path.to_string_lossy().to_string();
@@ -1081,33 +1552,278 @@ pub async fn download_video(app: &AppHandle, options: &DownloadOptions) -> Resul
         );
 
         // Save to history (async failure does not affect download)
-        spawn_save_to_history(app, options, actual_file_size);
+        let stream_files = kept_streams
+            .iter()
+            .filter_map(|p| p.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .collect();
+        spawn_save_to_history(
+            app,
+            options,
+            &output_path,
+            actual_file_size,
+            codec_label(resolved_video_codecid),
+            options.stream_mode.history_label(),
+            stream_files,
+        );
+        record_in_archive(
+            app,
+            options,
+            options.stream_mode.archive_kind(),
+            Some(resolved_video_quality),
+        )
+        .await;
 
         Ok(output_path_str)
     }
     .await;
 
-    // Cleanup: Remove cancellation token from registry and clear any
-    // pre-cancel flag so cancelled_ids doesn't accumulate.
-    DOWNLOAD_CANCEL_REGISTRY.remove(&options.download_id).await;
-    DOWNLOAD_CANCEL_REGISTRY
-        .clear_cancelled(&options.download_id)
-        .await;
+    let streams: &[&Path] = if video_only {
+        &[&temps.video]
+    } else {
+        &[&temps.video, &temps.audio]
+    };
+    temps.finish(&options.download_id, &result, streams).await;
+
+    result
+}
+
+/// Temporary files of a DASH download in the lib directory.
+struct DashTempFiles {
+    lib_path: PathBuf,
+    /// Content-based resume key, `Some` while this download holds it
+    resume_key: Option<String>,
+    video: PathBuf,
+    audio: PathBuf,
+    chapters: PathBuf,
+}
 
-    // On error, clean up temp files
-    if result.is_err() {
-        let _ = tokio::fs::remove_file(&temp_video_path).await;
-        let _ = tokio::fs::remove_file(&temp_audio_path).await;
-        // Clean up any subtitle files that may have been downloaded
-        cleanup_subtitle_files(&lib_path, &options.download_id);
+impl DashTempFiles {
+    /// Claims the resume key of the part and derives the temp file paths.
+    fn new(app: &AppHandle, options: &DownloadOptions) -> Self {
+        // Why: temp streams are named by content (bvid + cid) rather than by
+        //   download_id, which the frontend regenerates on every run. A download
+        //   restarted after a crash or app restart therefore finds the partial
+        //   files and their sidecar manifests, and download_url resumes only the
+        //   missing segments. A concurrent download of the same part falls back
+        //   to the per-download name so the two never share a file.
+        let lib_path = get_lib_path(app);
+        let key = format!("{}_{}", options.bvid, options.cid);
+        let resume_key = Some(key).filter(|key| download_manifest::claim_resume_key(key));
+        let temp_key = resume_key.as_deref().unwrap_or(&options.download_id);
+        Self {
+            video: lib_path.join(format!("temp_video_{}.m4s", temp_key)),
+            audio: lib_path.join(format!("temp_audio_{}.m4s", temp_key)),
+            chapters: lib_path.join(format!("temp_chapters_{}.txt", options.download_id)),
+            lib_path,
+            resume_key,
+        }
     }
 
-    result
+    /// Cleans up after a download attempt and releases the resume key.
+    ///
+    /// On error the temp files are removed. Streams interrupted mid-transfer
+    /// keep their partial data and manifest so the next attempt can resume,
+    /// unless the user cancelled. A pause is delivered as a cancel but keeps
+    /// them.
+    ///
+    /// # Arguments
+    ///
+    /// * `download_id` - Download ID
+    /// * `result` - Result of the download attempt
+    /// * `streams` - Temp streams that were in use
+    async fn finish(&self, download_id: &str, result: &Result<String, String>, streams: &[&Path]) {
+        use crate::handlers::concurrency::DOWNLOAD_CANCEL_REGISTRY;
+
+        // Remove the cancellation token from the registry and clear any
+        // pre-cancel flag so cancelled_ids doesn't accumulate.
+        DOWNLOAD_CANCEL_REGISTRY.remove(download_id).await;
+        DOWNLOAD_CANCEL_REGISTRY.clear_cancelled(download_id).await;
+
+        if let Err(e) = result {
+            let keep_resumable = !e.contains("ERR::CANCELLED")
+                || DOWNLOAD_CANCEL_REGISTRY.is_paused(download_id).await;
+            for stream in streams {
+                discard_temp_stream(stream, keep_resumable).await;
+            }
+            // Clean up any subtitle, cover and chapter files that may have
+            // been downloaded
+            cleanup_subtitle_files(&self.lib_path, download_id);
+            crate::handlers::cover::remove_temp_covers(&self.lib_path, download_id).await;
+            let _ = tokio::fs::remove_file(&self.chapters).await;
+        }
+        if let Some(resume_key) = &self.resume_key {
+            download_manifest::release_resume_key(resume_key);
+        }
+    }
+}
+
+/// Acquires a permit of the video semaphore.
+///
+/// The permit is held until the merge or conversion completes, so
+/// concurrency is bounded by the ffmpeg load.
+///
+/// # Errors
+///
+/// Returns an error if the semaphore has been closed.
+async fn acquire_video_permit() -> Result<tokio::sync::OwnedSemaphorePermit, String> {
+    crate::handlers::concurrency::VIDEO_SEMAPHORE
+        .clone()
+        .acquire_owned()
+        .await
+        .map_err(|e| format!("Failed to acquire video semaphore permit: {}", e))
+}
+
+/// Returns the audio streams the audio download may fall back to.
+fn fallback_audio_streams(
+    streams: &[XPlayerApiResponseVideo],
+    kind: AudioStreamKind,
+) -> Vec<XPlayerApiResponseVideo> {
+    // Why: falling back across codec kinds would put e.g. FLAC into an
+    //   MP4, so fallbacks stay within the selected kind.
+    streams
+        .iter()
+        .filter(|a| AudioStreamKind::from_quality(Some(a.id)) == kind)
+        .cloned()
+        .collect()
+}
+
+/// Maps an ffmpeg error to the error reported to the frontend.
+///
+/// Preserves `ERR::CANCELLED` so the frontend can detect cancellation
+/// (otherwise it would be masked as `ERR::MERGE_FAILED`).
+fn merge_error(e: String) -> String {
+    if e.contains("CANCELLED") {
+        e
+    } else {
+        String::from("ERR::MERGE_FAILED")
+    }
+}
+
+/// Cover art of a part, fetched per the cover settings.
+///
+/// Cover art is best effort: a failed fetch only loses the artwork.
+struct PartCover {
+    path: Option<PathBuf>,
+    embed: bool,
+    save_sidecar: bool,
+}
+
+impl PartCover {
+    /// Downloads the cover if it is to be embedded or saved as a sidecar.
+    async fn fetch(
+        options: &DownloadOptions,
+        settings: Option<&Settings>,
+        lib_path: &Path,
+    ) -> Self {
+        let embed = settings.and_then(|s| s.embed_cover).unwrap_or(true);
+        let save_sidecar = settings.and_then(|s| s.save_cover_sidecar).unwrap_or(false);
+        let path = if embed || save_sidecar {
+            download_part_cover(options, lib_path).await
+        } else {
+            None
+        };
+        Self {
+            path,
+            embed,
+            save_sidecar,
+        }
+    }
+
+    /// Returns the cover to embed in the output, if any.
+    fn embedded(&self) -> Option<&Path> {
+        self.path.as_deref().filter(|_| self.embed)
+    }
+
+    /// Saves the sidecar next to `output_path` if enabled and removes the
+    /// temporary cover.
+    async fn finish(&self, output_path: &Path) {
+        let Some(cover_path) = &self.path else {
+            return;
+        };
+        if self.save_sidecar {
+            let sidecar = crate::handlers::cover::sidecar_path_for(output_path, cover_path);
+            if let Err(e) = tokio::fs::copy(cover_path, &sidecar).await {
+                log::warn!(
+                    "[BE] download_video: failed to save cover {}: {}",
+                    sidecar.display(),
+                    e
+                );
+            }
+        }
+        let _ = tokio::fs::remove_file(cover_path).await;
+    }
+}
+
+/// Removes a temporary DASH stream and its resume manifest.
+///
+/// When `keep_resumable` is `true` and the stream still has a sidecar
+/// manifest (i.e. the transfer was interrupted), both files are kept so a
+/// later `download_video` for the same part resumes the missing segments.
+/// Completed streams have no manifest and are always removed.
+///
+/// # Arguments
+///
+/// * `path` - Temp stream path (e.g. `temp_video_{key}.m4s`)
+/// * `keep_resumable` - Whether an interrupted stream should be kept
+async fn discard_temp_stream(path: &Path, keep_resumable: bool) {
+    if keep_resumable && download_manifest::manifest_path(path).exists() {
+        log::info!(
+            "[BE] download_video: keeping partial stream for resume: {:?}",
+            path.file_name()
+        );
+        return;
+    }
+    let _ = tokio::fs::remove_file(path).await;
+    download_manifest::remove_manifest(path).await;
+}
+
+/// Moves a finished temp stream to `to`.
+///
+/// Falls back to copy + delete when the lib directory and the download
+/// directory are on different filesystems, where a rename fails.
+///
+/// # Errors
+///
+/// Returns an error if the stream can be neither renamed nor copied.
+async fn keep_temp_stream(from: &Path, to: &Path) -> Result<(), String> {
+    if tokio::fs::rename(from, to).await.is_ok() {
+        return Ok(());
+    }
+    tokio::fs::copy(from, to)
+        .await
+        .map_err(|e| format!("Failed to copy stream: {e}"))?;
+    let _ = tokio::fs::remove_file(from).await;
+    Ok(())
+}
+
+/// Downloads the cover of a part into the lib directory.
+///
+/// Uses the metadata cover, falling back to the part thumbnail. Returns
+/// the temporary cover file, or `None` when there is no cover or it cannot
+/// be fetched; the download then only loses the artwork.
+async fn download_part_cover(options: &DownloadOptions, lib_path: &Path) -> Option<PathBuf> {
+    let url = options
+        .metadata
+        .as_ref()
+        .and_then(|m| m.cover_url.clone())
+        .or_else(|| options.thumbnail_url.clone())?;
+    match crate::handlers::cover::download_cover(&url, lib_path, &options.download_id).await {
+        Ok(path) => Some(path),
+        Err(e) => {
+            log::warn!(
+                "[BE] download_video: cover unavailable id={}: {}",
+                options.download_id,
+                e
+            );
+            None
+        }
+    }
 }
 
 /// Cleans up temporary subtitle files for a download.
 ///
-/// Removes any `.srt` files matching the download ID prefix from the lib directory.
+/// Removes any `.srt` and `.ass` (danmaku) files matching the download ID prefix from the lib directory.
 /// This is called after download completion or failure to ensure temporary
 /// files are removed.
 ///
@@ -1129,7 +1845,7 @@ fn cleanup_subtitle_files(lib_path: &std::path::Path, download_id: &str) {
     if let Ok(entries) = std::fs::read_dir(lib_path) {
         for entry in entries.flatten() {
             if let Some(name) = entry.file_name().to_str() {
-                if name.starts_with(&prefix) && name.ends_with(".srt") {
+                if name.starts_with(&prefix) && (name.ends_with(".srt") || name.ends_with(".ass")) {
                     let _ = std::fs::remove_file(entry.path());
                 }
             }
@@ -1154,6 +1870,13 @@ mod tests {
         assert_eq!(quality_to_string(&32), "480P");
         assert_eq!(quality_to_string(&16), "360P");
         assert_eq!(quality_to_string(&999), "Q999");
+        for quality in [127, 126, 125, 116, 112, 80, 64, 32, 16, 999] {
+            assert_eq!(
+                quality_from_string(&quality_to_string(&quality)),
+                Some(quality)
+            );
+        }
+        assert_eq!(quality_from_string("best"), None);
     }
 
     /// Tests that all known quality IDs produce non-empty output.
@@ -1162,7 +1885,7 @@ mod tests {
     /// quality levels without returning empty strings.
     #[test]
     fn test_quality_to_string_coverage() {
-        let known_qualities = [116, 112, 80, 64, 32, 16];
+        let known_qualities = [127, 126, 125, 116, 112, 80, 64, 32, 16];
         for q in known_qualities {
             let result = quality_to_string(&q);
             assert!(
@@ -1173,6 +1896,27 @@ mod tests {
         }
     }
 
+    /// Tests bangumi URL parsing for episode, season and media links.
+    #[test]
+    fn test_parse_bangumi_url() {
+        assert_eq!(
+            parse_bangumi_url("https://www.bilibili.com/bangumi/play/ep3051843?from=search"),
+            Some(BangumiId::Episode(3051843))
+        );
+        assert_eq!(
+            parse_bangumi_url("https://www.bilibili.com/bangumi/play/ss41410/"),
+            Some(BangumiId::Season(41410))
+        );
+        assert_eq!(
+            parse_bangumi_url("https://m.bilibili.com/bangumi/media/md28234679"),
+            Some(BangumiId::Media(28234679))
+        );
+        assert_eq!(
+            parse_bangumi_url("https://www.bilibili.com/video/BV1xx411c7XD"),
+            None
+        );
+    }
+
     /// Tests DASH-format bangumi result conversion.
     ///
     /// Verifies that a `BangumiPlayerResult` with DASH data is converted into a
@@ -1187,6 +1931,8 @@ mod tests {
             dash: Some(XPlayerApiResponseDash {
                 video: vec![],
                 audio: vec![],
+                dolby: None,
+                flac: None,
                 extra: HashMap::new(),
             }),
             durl: None,
@@ -1227,28 +1973,160 @@ mod tests {
         let err = bangumi_player_result_to_xplayer(result).unwrap_err();
         assert_eq!(err, "ERR::BANGUMI_DURL_NOT_SUPPORTED");
     }
+
+    /// Tests that Dolby and Hi-Res streams are parsed and listed after the
+    /// standard audio streams.
+    #[test]
+    fn test_dash_audio_streams_include_dolby_and_flac() {
+        use crate::models::bilibili_api::XPlayerApiResponseDash;
+
+        let dash: XPlayerApiResponseDash = serde_json::from_value(serde_json::json!({
+            "video": [],
+            "audio": [{"id": 30280, "baseUrl": "aac", "bandwidth": 1, "codecid": 0}],
+            "dolby": {"type": 2, "audio": [{"id": 30250, "baseUrl": "ec3", "bandwidth": 1}]},
+            "flac": {"display": true, "audio": {"id": 30251, "baseUrl": "flac", "bandwidth": 1}},
+        }))
+        .unwrap();
+        let ids: Vec<i32> = dash.audio_streams().iter().map(|a| a.id).collect();
+        assert_eq!(ids, vec![30280, 30250, 30251]);
+        assert_eq!(convert_qualities(&dash.audio_streams()).len(), 3);
+
+        let plain: XPlayerApiResponseDash = serde_json::from_value(serde_json::json!({
+            "video": [],
+            "audio": [],
+            "dolby": {"type": 0, "audio": null},
+            "flac": null,
+        }))
+        .unwrap();
+        assert!(plain.audio_streams().is_empty());
+    }
+
+    /// Tests that container extensions are replaced and names stay in bounds.
+    #[test]
+    fn test_with_output_extension_replaces_container_extensions() {
+        assert_eq!(with_output_extension("video", "mkv"), "video.mkv");
+        assert_eq!(with_output_extension("video.MP4", "mkv"), "video.mkv");
+        assert_eq!(with_output_extension("video.mov", "mp4"), "video.mp4");
+        assert_eq!(with_output_extension("Vol.2 ep.1", "mp4"), "Vol.2 ep.1.mp4");
+        assert!(with_output_extension(&"字".repeat(100), "mkv").len() <= MAX_FILENAME_BYTES);
+    }
+
+    /// Tests that an explicitly requested HDR/Dolby Vision quality survives
+    /// a codec preference it is not offered in, while other qualities are
+    /// still filtered by the preferred codec.
+    #[test]
+    fn test_filter_streams_by_codec_priority_keeps_requested_quality() {
+        use crate::utils::codec::{CODECID_HEVC, QUALITY_DOLBY_VISION};
+
+        let stream = |id: i32, codecid: i16| XPlayerApiResponseVideo {
+            id,
+            codecid,
+            bandwidth: 0,
+            width: 0,
+            height: 0,
+            base_url: format!("{id}-{codecid}"),
+            backup_urls: None,
+        };
+        let streams = vec![
+            stream(QUALITY_DOLBY_VISION, CODECID_HEVC),
+            stream(80, CODECID_HEVC),
+            stream(80, CODECID_AVC),
+        ];
+
+        let (filtered, selection) = filter_streams_by_codec_priority(
+            &VideoCodecPriority::AvcOnly,
+            &streams,
+            QUALITY_DOLBY_VISION,
+        );
+        let urls: Vec<&str> = filtered.iter().map(|v| v.base_url.as_str()).collect();
+        assert_eq!(urls, vec!["126-12", "80-7"]);
+        assert!(selection.is_none());
+
+        let (filtered, selection) =
+            filter_streams_by_codec_priority(&VideoCodecPriority::AvcOnly, &streams, -1);
+        let urls: Vec<&str> = filtered.iter().map(|v| v.base_url.as_str()).collect();
+        assert_eq!(urls, vec!["80-7"]);
+        assert_eq!(selection.map(|s| s.codecid), Some(CODECID_AVC));
+    }
+
+    /// Tests view point to chapter conversion.
+    ///
+    /// Verifies that chapters are ordered by start time and that empty or
+    /// inverted ranges are dropped.
+    #[test]
+    fn test_chapters_from_view_points() {
+        let point = |content: &str, from: i64, to: i64| PlayerV2ViewPoint {
+            content: content.to_string(),
+            from,
+            to,
+        };
+        let chapters = chapters_from_view_points(vec![
+            point("Part 2 ", 120, 300),
+            point("Empty", 300, 300),
+            point("Part 1", 0, 120),
+            point("Inverted", 400, 350),
+        ]);
+        assert_eq!(
+            chapters,
+            vec![
+                Chapter {
+                    title: "Part 1".to_string(),
+                    start: 0,
+                    end: 120,
+                },
+                Chapter {
+                    title: "Part 2".to_string(),
+                    start: 120,
+                    end: 300,
+                },
+            ]
+        );
+    }
 }
 
 /// Spawns an async task to save download history.
 ///
 /// Extracts relevant fields from `options` and spawns a background task
-/// that calls [`save_to_history`]. Failures are logged but not propagated.
-fn spawn_save_to_history(app: &AppHandle, options: &DownloadOptions, file_size: Option<u64>) {
+/// that calls [`save_to_history`]. `codec` is the label of the downloaded
+/// video codec (`None` when unknown), `mode` the download mode label
+/// (see [`StreamMode::history_label`]; `"audio"` for audio-only downloads,
+/// which record no video quality) and `stream_files` the names of kept raw
+/// streams. Failures are logged but not propagated.
+fn spawn_save_to_history(
+    app: &AppHandle,
+    options: &DownloadOptions,
+    output_path: &Path,
+    file_size: Option<u64>,
+    codec: Option<&'static str>,
+    mode: Option<&'static str>,
+    stream_files: Vec<String>,
+) {
     let app = app.clone();
+    let output_path = output_path.to_string_lossy().to_string();
+    let duration_seconds = Some(options.duration_seconds).filter(|&d| d > 0);
     let bvid = options.bvid.clone();
+    let cid = options.cid;
     let filename = options.filename.clone();
-    let quality = options.quality;
+    let quality = options.quality.filter(|_| mode != Some("audio"));
     let thumbnail_url = options.thumbnail_url.clone();
+    let uploader = options.metadata.as_ref().and_then(|m| m.uploader.clone());
     let page = options.page;
     tokio::spawn(async move {
         if let Err(e) = save_to_history(
             &app,
             &bvid,
+            cid,
             quality,
             file_size,
             &filename,
             thumbnail_url,
+            uploader,
             page,
+            codec,
+            duration_seconds,
+            output_path,
+            mode,
+            stream_files,
         )
         .await
         {
@@ -1271,25 +2149,40 @@ fn spawn_save_to_history(app: &AppHandle, options: &DownloadOptions, file_size:
 ///
 /// * `app` - Tauri application handle
 /// * `bvid` - Bilibili video ID
+/// * `cid` - Content ID of the downloaded part
 /// * `quality` - Downloaded video quality ID
 /// * `file_size` - Actual file size in bytes (optional)
 /// * `filename` - Output filename used for title extraction
 /// * `thumbnail_url` - Video thumbnail URL (fetched if not provided)
+/// * `uploader` - Uploader name (optional)
 /// * `page` - Page number for multi-part videos (optional)
+/// * `codec` - Video codec label (optional)
+/// * `duration_seconds` - Duration of the part in seconds (optional)
+/// * `output_path` - Absolute path of the output file
+/// * `mode` - Download mode label (`None` for a merged video)
+/// * `stream_files` - File names of the raw streams kept next to the output
 ///
 /// # Returns
 ///
 /// Returns `Ok(())` on success, or an error if store operations fail.
+#[allow(clippy::too_many_arguments)]
 async fn save_to_history(
     app: &AppHandle,
     bvid: &str,
+    cid: i64,
     quality: Option<i32>,
     file_size: Option<u64>,
     filename: &str,
     thumbnail_url: Option<String>,
+    uploader: Option<String>,
     page: Option<i32>,
+    codec: Option<&str>,
+    duration_seconds: Option<i64>,
+    output_path: String,
+    mode: Option<&str>,
+    stream_files: Vec<String>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    use crate::models::history::HistoryEntry;
+    use crate::models::history::{HistoryEntry, CURRENT_HISTORY_VERSION};
     use crate::store::HistoryStore;
     use chrono::Utc;
     use std::path::Path;
@@ -1325,17 +2218,26 @@ async fn save_to_history(
     let entry = HistoryEntry {
         id,
         title,
+        uploader,
         bvid: Some(bvid.to_string()),
+        cid: Some(cid),
+        page,
         url,
         downloaded_at: Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
         status: "completed".to_string(),
         file_size,
         quality: quality.as_ref().map(quality_to_string),
+        codec: codec.map(str::to_string),
+        duration_seconds,
+        output_path: Some(output_path),
         thumbnail_url,
-        version: "1.0".to_string(),
+        mode: mode.map(str::to_string),
+        stream_files,
+        version: CURRENT_HISTORY_VERSION.to_string(),
     };
 
-    HistoryStore::new(app)?.add_entry(entry.clone())?;
+    let stored = entry.clone();
+    HistoryStore::run_blocking(app, move |store| store.add_entry(stored)).await?;
 
     // Emit event to notify frontend of new history entry
     let _ = app.emit("history:entry_added", &entry);
@@ -1343,6 +2245,102 @@ async fn save_to_history(
     Ok(())
 }
 
+/// Adds the completed downloads of the history to the download archive.
+///
+/// Lets downloads finished before the archive existed be skipped by batch
+/// jobs. Entries recorded before the CID was tracked get the CID of their
+/// page from the video's page list; entries whose page list cannot be
+/// fetched are left out.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// Number of items added to the archive.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be loaded or the archive cannot
+/// be written.
+pub async fn import_download_archive(app: &AppHandle) -> Result<usize, String> {
+    use crate::store::HistoryStore;
+
+    let entries = HistoryStore::run_blocking(app, |store| store.load()).await?;
+    let cids = fetch_legacy_history_cids(app, &entries).await?;
+    let keys = download_archive::keys_from_history(&entries, quality_from_string, |bvid, page| {
+        cids.get(&(bvid.to_string(), page)).copied()
+    });
+    let added = download_archive::record(&get_download_archive_path(app), &keys).await?;
+    log::info!(
+        "[BE] import_download_archive: {} of {} history entries added",
+        added,
+        entries.len()
+    );
+    Ok(added)
+}
+
+/// Parallel page list requests while importing the history into the archive.
+const PAGE_LIST_CONCURRENCY: usize = 4;
+
+/// Fetches the CIDs of the videos of history entries without a CID.
+///
+/// # Returns
+///
+/// The CID of every page of those videos, keyed by BV ID and page number.
+/// Videos whose page list cannot be fetched are logged and left out.
+///
+/// # Errors
+///
+/// Returns an error if the cookies cannot be read.
+async fn fetch_legacy_history_cids(
+    app: &AppHandle,
+    entries: &[crate::models::history::HistoryEntry],
+) -> Result<BTreeMap<(String, i32), i64>, String> {
+    let mut bvids: Vec<String> = entries
+        .iter()
+        .filter(|entry| entry.cid.is_none())
+        .filter(|entry| matches!(entry.status.as_str(), "completed" | "success"))
+        .filter_map(|entry| entry.bvid.clone())
+        .collect();
+    bvids.sort_unstable();
+    bvids.dedup();
+    if bvids.is_empty() {
+        return Ok(BTreeMap::new());
+    }
+
+    let cookies = read_cookie(app)?.unwrap_or_default();
+    let pages: Vec<Vec<((String, i32), i64)>> = stream::iter(bvids)
+        .map(|bvid| fetch_page_cids(bvid, &cookies))
+        .buffered(PAGE_LIST_CONCURRENCY)
+        .collect()
+        .await;
+    Ok(pages.into_iter().flatten().collect())
+}
+
+/// Fetches the CID of every page of a video, keyed by BV ID and page
+/// number. Failures are logged and yield no pages.
+async fn fetch_page_cids(bvid: String, cookies: &[CookieEntry]) -> Vec<((String, i32), i64)> {
+    match fetch_video_title_by_bvid(&bvid, cookies).await {
+        Ok(body) => body
+            .data
+            .and_then(|data| data.pages)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|page| ((bvid.clone(), page.page), page.cid))
+            .collect(),
+        Err(e) => {
+            log::warn!(
+                "[BE] import_download_archive: failed to fetch pages of {}: {}",
+                bvid,
+                e
+            );
+            Vec::new()
+        }
+    }
+}
+
 /// Returns the first non-empty string in a slice, or `None` if all are empty.
 ///
 /// Used to select the first valid (non-empty) string from multiple candidates.
@@ -1381,6 +2379,9 @@ fn first_non_empty(strings: &[&String]) -> Option<String> {
 /// Human-readable quality string.
 fn quality_to_string(quality: &i32) -> String {
     match quality {
+        127 => "8K".to_string(),
+        126 => "Dolby Vision".to_string(),
+        125 => "HDR".to_string(),
         116 => "4K".to_string(),
         112 => "1080P60".to_string(),
         80 => "1080P".to_string(),
@@ -1391,6 +2392,22 @@ fn quality_to_string(quality: &i32) -> String {
     }
 }
 
+/// Converts a label written by [`quality_to_string`] back to its quality ID.
+fn quality_from_string(label: &str) -> Option<i32> {
+    match label {
+        "8K" => Some(127),
+        "Dolby Vision" => Some(126),
+        "HDR" => Some(125),
+        "4K" => Some(116),
+        "1080P60" => Some(112),
+        "1080P" => Some(80),
+        "720P" => Some(64),
+        "480P" => Some(32),
+        "360P" => Some(16),
+        _ => label.strip_prefix('Q')?.parse().ok(),
+    }
+}
+
 /// Fetches video information for history entries.
 ///
 /// Used to retrieve video title and thumbnail when saving download history.
@@ -1789,8 +2806,10 @@ pub fn build_cookie_header_from_cache(app: &AppHandle) -> Result<String, String>
 
 /// Fetches video metadata from Bilibili.
 ///
-/// Retrieves video title, parts (pages), and basic information.
-/// Quality options and subtitles are fetched lazily via separate API calls.
+/// Retrieves video title, parts (pages), and basic information. When the
+/// video belongs to a collection (合集), the collection's episodes are
+/// returned in `collection`. Quality options and subtitles are fetched
+/// lazily via separate API calls, and chapters only by the download.
 ///
 /// # Arguments
 ///
@@ -1826,8 +2845,8 @@ pub async fn fetch_video_info(app: &AppHandle, id: &str) -> Result<Video, String
 
     // Check if this video redirects to a bangumi episode
     if let Some(redirect_url) = &data.redirect_url {
-        if let Some(ep_id) = extract_bangumi_ep_id(redirect_url) {
-            return fetch_bangumi_info(app, ep_id).await;
+        if let Some(bangumi_id) = parse_bangumi_url(redirect_url) {
+            return fetch_bangumi_info(app, bangumi_id).await;
         }
     }
 
@@ -1926,6 +2945,20 @@ pub async fn fetch_video_info(app: &AppHandle, id: &str) -> Result<Video, String
         content_type: "video".to_string(),
         ep_id: None,
         season_title: None,
+        extra_sections: vec![],
+        collection: data
+            .ugc_season
+            .as_ref()
+            .map(collection::collection_from_ugc_season),
+        metadata: Some(VideoMetadata {
+            title: data.title.clone(),
+            uploader: data.owner.as_ref().map(|o| o.name.clone()),
+            description: Some(data.desc.clone()).filter(|d| !d.is_empty()),
+            publish_date: Some(data.pubdate).filter(|&ts| ts > 0),
+            tags: fetch_video_tags(&cookies, id).await,
+            source_url: Some(format!("https://www.bilibili.com/video/{id}")),
+            cover_url: Some(data.pic.clone()),
+        }),
     })
 }
 
@@ -1968,10 +3001,18 @@ fn convert_qualities(video: &[XPlayerApiResponseVideo]) -> Vec<Quality> {
             id,
             codecid: v.codecid,
             quality: quality_to_string(&id),
+            dynamic_range: DynamicRange::from_quality(id),
         })
         .collect()
 }
 
+/// `fnval` flags requested from the playurl APIs.
+///
+/// 16 = DASH, 64 = HDR, 128 = 4K, 256 = Dolby audio, 512 = Dolby Vision,
+/// 1024 = 8K, 2048 = AV1. Hi-Res FLAC is returned with DASH for accounts
+/// that are entitled to it.
+const PLAYURL_FNVAL: u32 = 16 | 64 | 128 | 256 | 512 | 1024 | 2048;
+
 /// Fetches video title and page information from Bilibili Web Interface API.
 ///
 /// Retrieves basic video metadata including title, thumbnail, and page list.
@@ -1993,7 +3034,7 @@ fn convert_qualities(video: &[XPlayerApiResponseVideo]) -> Vec<Quality> {
 /// - Non-success HTTP status
 /// - API returns non-zero code
 /// - Video not found (`ERR::VIDEO_NOT_FOUND`)
-async fn fetch_video_title_by_bvid(
+pub(crate) async fn fetch_video_title_by_bvid(
     bvid: &str,
     cookies: &[CookieEntry],
 ) -> Result<WebInterfaceApiResponse, String> {
@@ -2017,6 +3058,39 @@ async fn fetch_video_title_by_bvid(
     Ok(body)
 }
 
+/// Fetches the tags of a video.
+///
+/// Tags are optional metadata, so any failure yields an empty list.
+async fn fetch_video_tags(cookies: &[CookieEntry], bvid: &str) -> Vec<String> {
+    let result = async {
+        let client = build_client()?;
+        let body: ArchiveTagsApiResponse = client
+            .get("https://api.bilibili.com/x/tag/archive/tags")
+            .query(&[("bvid", bvid)])
+            .header(header::COOKIE, build_cookie_header(cookies))
+            .header(reqwest::header::REFERER, REFERER)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch tags: {e}"))?
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse tags response: {e}"))?;
+        if body.code != 0 {
+            return Err(format!("API error (code {}): {}", body.code, body.message));
+        }
+        Ok::<_, String>(body.data.unwrap_or_default())
+    }
+    .await;
+
+    match result {
+        Ok(tags) => tags.into_iter().map(|t| t.tag_name).collect(),
+        Err(e) => {
+            log::warn!("[BE] fetch_video_tags: bvid={}: {}", bvid, e);
+            vec![]
+        }
+    }
+}
+
 /// Fetches video stream URLs and quality options from the Bilibili Player API.
 ///
 /// Uses WBI signature for authentication. Retrieves DASH stream URLs
@@ -2064,8 +3138,8 @@ async fn fetch_video_details(
     let mut params = BTreeMap::from([
         ("bvid".to_string(), bvid.to_string()),
         ("cid".to_string(), cid.to_string()),
-        ("qn".to_string(), "116".to_string()),
-        ("fnval".to_string(), "2064".to_string()),
+        ("qn".to_string(), QUALITY_8K.to_string()),
+        ("fnval".to_string(), PLAYURL_FNVAL.to_string()),
         ("fnver".to_string(), "0".to_string()),
         ("fourk".to_string(), "1".to_string()),
     ]);
@@ -2119,33 +3193,175 @@ fn auto_rename(path: &Path) -> PathBuf {
     let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
     let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("mp4");
 
-    for idx in 1..=10_000u32 {
-        let new_name = format!("{} ({}).{}", stem, idx, ext);
-        let new_path = parent.join(new_name);
-        if !new_path.exists() {
-            return new_path;
-        }
+    for idx in 1..=10_000u32 {
+        let new_name = format!("{} ({}).{}", stem, idx, ext);
+        let new_path = parent.join(new_name);
+        if !new_path.exists() {
+            return new_path;
+        }
+    }
+
+    // Fallback: use timestamp to ensure uniqueness
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let fallback_name = format!("{}_{}.{}", stem, timestamp, ext);
+    parent.join(fallback_name)
+}
+
+/// Returns the path a download writes to.
+///
+/// [`ArchiveMode::Overwrite`] replaces an existing file at `base`; every
+/// other mode picks a free name (see [`auto_rename`]).
+fn resolve_output_path(base: &Path, options: &DownloadOptions) -> PathBuf {
+    if options.archive_mode == Some(ArchiveMode::Overwrite) {
+        base.to_path_buf()
+    } else {
+        auto_rename(base)
+    }
+}
+
+/// Returns the output path of a part, renamed or overwritten per the
+/// archive mode (see [`resolve_output_path`]).
+///
+/// `quality` and `codecid` are the resolved stream IDs used by the filename
+/// template. The folder is not created; see [`create_output_dir`].
+///
+/// # Errors
+///
+/// Returns the errors of [`build_output_path`].
+async fn part_output_path(
+    app: &AppHandle,
+    options: &DownloadOptions,
+    settings: Option<&Settings>,
+    quality: Option<i32>,
+    codecid: Option<i16>,
+    extension: &str,
+) -> Result<PathBuf, String> {
+    let filename = output_filename(options, settings, quality, codecid);
+    let base = build_output_path(app, &filename, extension).await?;
+    Ok(resolve_output_path(&base, options))
+}
+
+/// Returns the archive item a download is checked against: its kind and
+/// requested quality (`None`, the best available, matches any quality).
+fn requested_archive_key(options: &DownloadOptions) -> ArchiveKey {
+    let (kind, quality) = match options.audio_only {
+        Some(_) => (ArchiveKind::Audio, options.audio_quality),
+        None => (options.stream_mode.archive_kind(), options.quality),
+    };
+    ArchiveKey {
+        kind,
+        bvid: options.bvid.clone(),
+        cid: options.cid,
+        quality,
+    }
+}
+
+/// Returns the output path the history recorded for an archived item.
+///
+/// Looks for the newest completed entry of the same kind, BV ID and CID.
+/// The history is best effort here: failures yield `None`.
+async fn archived_output_path(app: &AppHandle, archived: &ArchiveKey) -> Option<String> {
+    let entries = crate::store::HistoryStore::run_blocking(app, |store| store.load())
+        .await
+        .ok()?;
+    entries
+        .into_iter()
+        .filter(|entry| matches!(entry.status.as_str(), "completed" | "success"))
+        .filter(|entry| ArchiveKind::from_history_mode(entry.mode.as_deref()) == archived.kind)
+        .filter(|entry| entry.bvid.as_deref() == Some(archived.bvid.as_str()))
+        .filter(|entry| entry.cid == Some(archived.cid))
+        .find_map(|entry| entry.output_path)
+}
+
+/// Records a finished download in the download archive.
+///
+/// `quality` is the resolved quality ID. Failures are logged but not
+/// propagated, like the history entry.
+async fn record_in_archive(
+    app: &AppHandle,
+    options: &DownloadOptions,
+    kind: ArchiveKind,
+    quality: Option<i32>,
+) {
+    let key = ArchiveKey {
+        kind,
+        bvid: options.bvid.clone(),
+        cid: options.cid,
+        quality,
+    };
+    if let Err(e) = download_archive::record(&get_download_archive_path(app), &[key]).await {
+        log::warn!(
+            "[BE] download_video: failed to record {} in download archive: {}",
+            options.download_id,
+            e
+        );
     }
+}
 
-    // Fallback: use timestamp to ensure uniqueness
-    let timestamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis())
-        .unwrap_or(0);
-    let fallback_name = format!("{}_{}.{}", stem, timestamp, ext);
-    parent.join(fallback_name)
+/// Returns the output filename of a download (without extension).
+///
+/// Renders the filename template of the settings when one is set, falling
+/// back to `options.filename` if it fails. `quality` and `codecid` are the
+/// resolved stream IDs, `None` before the streams are selected.
+fn output_filename(
+    options: &DownloadOptions,
+    settings: Option<&Settings>,
+    quality: Option<i32>,
+    codecid: Option<i16>,
+) -> String {
+    let Some(template) = settings
+        .and_then(|s| s.filename_template.as_deref())
+        .filter(|t| !t.trim().is_empty())
+    else {
+        return options.filename.clone();
+    };
+    let metadata = options.metadata.as_ref();
+    let mut values = options
+        .template_values
+        .clone()
+        .unwrap_or_else(|| TemplateValues {
+            title: options.filename.clone(),
+            uploader: metadata.and_then(|m| m.uploader.clone()),
+            pubdate: metadata.and_then(|m| m.publish_date),
+            ..Default::default()
+        });
+    if values.bvid.is_empty() {
+        values.bvid = options.bvid.clone();
+    }
+    values.page = values.page.or(options.page);
+    values.quality = quality.map(|q| quality_to_string(&q)).or(values.quality);
+    values.codec = codecid
+        .and_then(codec_label)
+        .map(str::to_string)
+        .or(values.codec);
+    let replacements = settings.and_then(|s| s.title_replacements.as_deref());
+    render_template(template, &values, replacements).unwrap_or_else(|e| {
+        log::warn!(
+            "[BE] download_video: filename template failed id={}: {}",
+            options.download_id,
+            e
+        );
+        options.filename.clone()
+    })
 }
 
 /// Builds the full output path for a download file.
 ///
-/// Combines the user-configured download directory with the filename.
-/// Automatically appends `.mp4` extension if not already present.
-/// Sanitizes the filename by applying title replacement rules from settings.
+/// Combines the user-configured download directory with the filename and
+/// gives it the container extension (see [`with_output_extension`]).
+/// Folders in the filename (`/` or `\`, from a filename template) become
+/// folders below the download directory; `.` and `..` are ignored. Every
+/// component is made valid for the platform with [`sanitize_filename`].
+/// Nothing is created on disk; see [`create_output_dir`].
 ///
 /// # Arguments
 ///
 /// * `app` - Tauri application handle for settings access
 /// * `filename` - Desired output filename (with or without extension)
+/// * `extension` - Extension of the output container (e.g. `"mp4"`)
 ///
 /// # Returns
 ///
@@ -2156,7 +3372,11 @@ fn auto_rename(path: &Path) -> PathBuf {
 /// Returns errors in the following cases:
 /// - Cannot retrieve settings
 /// - Download output path is not configured
-async fn build_output_path(app: &AppHandle, filename: &str) -> Result<PathBuf, String> {
+pub(crate) async fn build_output_path(
+    app: &AppHandle,
+    filename: &str,
+    extension: &str,
+) -> Result<PathBuf, String> {
     let settings = settings::get_settings(app)
         .await
         .map_err(|e| format!("Failed to get settings: {e}"))?;
@@ -2164,13 +3384,63 @@ async fn build_output_path(app: &AppHandle, filename: &str) -> Result<PathBuf, S
         .dl_output_path
         .ok_or_else(|| "Download output path is not configured".to_string())?;
 
-    let filename_with_ext = if filename.to_lowercase().ends_with(".mp4") {
-        filename.to_string()
-    } else {
-        format!("{filename}.mp4")
-    };
+    let mut components: Vec<&str> = filename
+        .split(['/', '\\'])
+        .filter(|c| !matches!(c.trim(), "" | "." | ".."))
+        .collect();
+    let file = components.pop().unwrap_or_default();
+    let mut path = PathBuf::from(&output_path);
+    path.extend(
+        components
+            .into_iter()
+            .map(|folder| sanitize_filename(folder, "", MAX_FILENAME_BYTES)),
+    );
+    path.push(with_output_extension(file, extension));
+    Ok(path)
+}
+
+/// Creates the folder an output file is written to.
+///
+/// Called once for the final output path, so folders from a filename
+/// template only appear for downloads that actually write there.
+///
+/// # Errors
+///
+/// Returns an error if the folder cannot be created.
+pub(crate) async fn create_output_dir(output_path: &Path) -> Result<(), String> {
+    match output_path.parent() {
+        Some(parent) => tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create output folder: {e}")),
+        None => Ok(()),
+    }
+}
 
-    Ok(PathBuf::from(&output_path).join(filename_with_ext))
+/// Returns `filename` with the given container extension.
+///
+/// A container extension the frontend may already have added (`.mp4`,
+/// `.mkv`, `.mov`) is replaced; anything else is kept as part of the name,
+/// since titles often contain dots. The name is sanitized and shortened to
+/// leave [`FILENAME_HEADROOM_BYTES`] for sidecar files and `auto_rename`.
+fn with_output_extension(filename: &str, extension: &str) -> String {
+    let stem = Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| {
+            [
+                OutputContainer::Mp4,
+                OutputContainer::Mkv,
+                OutputContainer::Mov,
+            ]
+            .iter()
+            .any(|c| ext.eq_ignore_ascii_case(c.extension()))
+        })
+        .map_or(filename, |ext| &filename[..filename.len() - ext.len() - 1]);
+    sanitize_filename(
+        stem,
+        extension,
+        MAX_FILENAME_BYTES - FILENAME_HEADROOM_BYTES,
+    )
 }
 
 /// Gets the Content-Length of a resource via HEAD request.
@@ -2188,7 +3458,7 @@ async fn build_output_path(app: &AppHandle, filename: &str) -> Result<PathBuf, S
 /// Returns `Some(content_length)` on success.
 /// Returns `None` on failure.
 async fn head_content_length(url: &str, cookie: Option<&str>) -> Option<u64> {
-    let client = build_client().ok()?;
+    let client = build_cdn_client().ok()?;
     let mut req = client.head(url);
     if let Some(c) = cookie {
         req = req.header(reqwest::header::COOKIE, c);
@@ -2414,39 +3684,66 @@ fn select_stream_url(
 
 /// Resolves the user's codec priority and filters video streams accordingly.
 ///
-/// Reads the codec priority setting once and returns:
-/// - The streams to use for quality selection: filtered by the preferred
-///   codec, or all streams when the preferred codec is unavailable for any
-///   quality (so the download never fails).
-/// - The codec selection result, used by callers to detect codec fallback.
-///   `None` means no priority codec was available at all (caller treats this
-///   as a codec fallback for warning purposes).
+/// Reads the codec priority setting once and delegates to
+/// [`filter_streams_by_codec_priority`].
 ///
 /// Shared by `download_video` and `refetch_dash_urls` to keep the codec
 /// selection logic in a single place.
 async fn select_streams_by_codec_priority(
     app: &AppHandle,
     video_streams: &[XPlayerApiResponseVideo],
+    requested_quality: i32,
 ) -> (Vec<XPlayerApiResponseVideo>, Option<VideoStreamSelection>) {
     let codec_priority = settings::get_settings(app)
         .await
         .ok()
         .and_then(|s| s.video_codec_priority)
         .unwrap_or_default();
+    filter_streams_by_codec_priority(&codec_priority, video_streams, requested_quality)
+}
 
-    let available_codecs: Vec<i16> = video_streams.iter().map(|v| v.codecid).collect();
-    let codec_selection = select_video_stream(&codec_priority, &available_codecs);
+/// Filters video streams by codec priority.
+///
+/// Returns:
+/// - The streams to use for quality selection. When `requested_quality` is
+///   offered, its streams are filtered by the codecs available at that
+///   quality, so HDR, Dolby Vision and 8K (often HEVC-only) are never
+///   hidden by the preference; all other qualities are filtered by the
+///   preferred codec across the whole list (the "best available" path). A
+///   quality without any priority codec keeps all of its streams, so the
+///   download never fails.
+/// - The codec selection result, used by callers to detect codec fallback.
+///   `None` means no priority codec was available at all (caller treats this
+///   as a codec fallback for warning purposes).
+fn filter_streams_by_codec_priority(
+    codec_priority: &VideoCodecPriority,
+    video_streams: &[XPlayerApiResponseVideo],
+    requested_quality: i32,
+) -> (Vec<XPlayerApiResponseVideo>, Option<VideoStreamSelection>) {
+    let available: Vec<(i32, i16)> = video_streams.iter().map(|v| (v.id, v.codecid)).collect();
+    let available_codecs: Vec<i16> = available.iter().map(|&(_, codecid)| codecid).collect();
+    let global_selection = select_video_stream(codec_priority, &available_codecs);
+    let requested_offered = available.iter().any(|&(id, _)| id == requested_quality);
+    let requested_selection =
+        select_video_stream_per_quality(codec_priority, &available).remove(&requested_quality);
 
     let filtered: Vec<_> = video_streams
         .iter()
         .filter(|v| {
-            codec_selection
-                .as_ref()
-                .map(|sel| v.codecid == sel.codecid)
-                .unwrap_or(true)
+            let selection = if v.id == requested_quality {
+                requested_selection.as_ref()
+            } else {
+                global_selection.as_ref()
+            };
+            selection.is_none_or(|sel| v.codecid == sel.codecid)
         })
         .cloned()
         .collect();
+    let codec_selection = if requested_offered {
+        requested_selection
+    } else {
+        global_selection
+    };
 
     if filtered.is_empty() {
         log::info!("[BE] no streams with preferred codec, using all streams");
@@ -2674,11 +3971,66 @@ pub async fn fetch_subtitles(
         }
     };
 
+    let data = match fetch_player_v2(client, Some(&cookie_header), &mixin_key, bvid, cid).await {
+        Ok(data) => data,
+        Err(e) => {
+            log::error!("[BE] fetch_subtitles: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let subtitles = data.subtitle.and_then(|s| s.subtitles).unwrap_or_default();
+
+    log::info!(
+        "[BE] fetch_subtitles: retrieved {} subtitles for \
+         bvid={}, cid={}",
+        subtitles.len(),
+        bvid,
+        cid
+    );
+
+    subtitles
+        .into_iter()
+        .map(|item| {
+            let is_ai = item.subtitle_url.contains("/ai_subtitle/");
+            SubtitleDto {
+                lan: item.lan,
+                lan_doc: item.lan_doc,
+                subtitle_url: item.subtitle_url,
+                is_ai,
+                ai_type: item.ai_type,
+            }
+        })
+        .collect()
+}
+
+/// Requests the WBI-signed Player v2 API for a video part.
+///
+/// # Arguments
+///
+/// * `client` - HTTP client
+/// * `cookie_header` - Optional Cookie header (subtitles need a login;
+///   chapters do not)
+/// * `mixin_key` - WBI mixin key from [`crate::utils::wbi::fetch_mixin_key`]
+/// * `bvid` - Bilibili video ID
+/// * `cid` - Content ID
+///
+/// # Errors
+///
+/// Returns an error if the request fails, the response cannot be parsed, or
+/// the API returns a non-zero code or no data.
+async fn fetch_player_v2(
+    client: &Client,
+    cookie_header: Option<&str>,
+    mixin_key: &str,
+    bvid: &str,
+    cid: i64,
+) -> Result<PlayerV2ApiData, String> {
     let mut params = BTreeMap::from([
         ("bvid".to_string(), bvid.to_string()),
         ("cid".to_string(), cid.to_string()),
     ]);
-    let signature = crate::utils::wbi::generate_wbi_signature(&mut params, &mixin_key);
+    let signature = crate::utils::wbi::generate_wbi_signature(&mut params, mixin_key);
 
     let mut query: Vec<(&str, String)> = params
         .iter()
@@ -2687,76 +4039,107 @@ pub async fn fetch_subtitles(
     query.push(("w_rid", signature.w_rid));
     query.push(("wts", signature.wts));
 
-    let response = match client
+    let mut request = client
         .get("https://api.bilibili.com/x/player/wbi/v2")
-        .header(header::COOKIE, &cookie_header)
         .header(header::REFERER, "https://www.bilibili.com")
-        .query(&query)
+        .query(&query);
+    if let Some(cookie) = cookie_header {
+        request = request.header(header::COOKIE, cookie);
+    }
+    let response = request
         .send()
         .await
-    {
-        Ok(resp) => resp,
-        Err(e) => {
-            log::error!("[BE] fetch_subtitles: HTTP request failed: {}", e);
-            return Vec::new();
-        }
-    };
+        .map_err(|e| format!("HTTP request failed: {}", e))?;
 
     let status = response.status();
     if !status.is_success() {
-        log::error!(
-            "[BE] fetch_subtitles: API returned \
-             non-success status: {}",
-            status
-        );
-        return Vec::new();
+        return Err(format!("API returned non-success status: {}", status));
     }
 
-    let body: PlayerV2ApiResponse = match response.json().await {
-        Ok(b) => b,
-        Err(e) => {
-            log::error!("[BE] fetch_subtitles: failed to parse JSON: {}", e);
-            return Vec::new();
-        }
-    };
+    let body: PlayerV2ApiResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("failed to parse JSON: {}", e))?;
 
     if body.code != 0 {
-        log::error!(
-            "[BE] fetch_subtitles: API error code={}, \
-             message={:?}",
-            body.code,
-            body.message
-        );
-        return Vec::new();
+        return Err(format!(
+            "API error code={}, message={:?}",
+            body.code, body.message
+        ));
     }
 
-    let subtitles = body
-        .data
-        .and_then(|d| d.subtitle)
-        .and_then(|s| s.subtitles)
-        .unwrap_or_default();
+    body.data.ok_or_else(|| "API returned no data".to_string())
+}
 
-    log::info!(
-        "[BE] fetch_subtitles: retrieved {} subtitles for \
-         bvid={}, cid={}",
-        subtitles.len(),
-        bvid,
-        cid
-    );
+/// Fetches the chapters (分段章节) of a video part from Player v2 API.
+///
+/// Chapters are public, so cookies are optional. Returns an empty vector
+/// on error or when the uploader set no chapters (does not propagate
+/// errors).
+///
+/// # Arguments
+///
+/// * `client` - HTTP client
+/// * `cookie_header` - Optional Cookie header
+/// * `mixin_key` - WBI mixin key, shared across the parts of a video
+/// * `bvid` - Bilibili video ID
+/// * `cid` - Content ID
+async fn fetch_chapters(
+    client: &Client,
+    cookie_header: Option<&str>,
+    mixin_key: &str,
+    bvid: &str,
+    cid: i64,
+) -> Vec<Chapter> {
+    match fetch_player_v2(client, cookie_header, mixin_key, bvid, cid).await {
+        Ok(data) => chapters_from_view_points(data.view_points.unwrap_or_default()),
+        Err(e) => {
+            log::warn!(
+                "[BE] fetch_chapters: failed for bvid={}, cid={}: {}",
+                bvid,
+                cid,
+                e
+            );
+            Vec::new()
+        }
+    }
+}
 
-    subtitles
+/// Converts Player v2 view points into chapters ordered by start time.
+///
+/// Zero-length and inverted ranges are dropped.
+fn chapters_from_view_points(view_points: Vec<PlayerV2ViewPoint>) -> Vec<Chapter> {
+    let mut chapters: Vec<Chapter> = view_points
         .into_iter()
-        .map(|item| {
-            let is_ai = item.subtitle_url.contains("/ai_subtitle/");
-            SubtitleDto {
-                lan: item.lan,
-                lan_doc: item.lan_doc,
-                subtitle_url: item.subtitle_url,
-                is_ai,
-                ai_type: item.ai_type,
-            }
+        .filter(|point| point.to > point.from && point.from >= 0)
+        .map(|point| Chapter {
+            title: point.content.trim().to_string(),
+            start: point.from,
+            end: point.to,
         })
-        .collect()
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Fetches the chapters of one video part, signing the Player v2 request
+/// with a freshly fetched WBI mixin key.
+///
+/// Returns an empty vector when the client or mixin key cannot be built.
+async fn fetch_part_chapters(cookies: &[CookieEntry], bvid: &str, cid: i64) -> Vec<Chapter> {
+    let cookie_header = Some(build_cookie_header(cookies)).filter(|h| !h.is_empty());
+    let Ok(client) = build_client() else {
+        return Vec::new();
+    };
+    match crate::utils::wbi::fetch_mixin_key(&client, cookie_header.as_deref()).await {
+        Ok(mixin_key) => {
+            fetch_chapters(&client, cookie_header.as_deref(), &mixin_key, bvid, cid).await
+        }
+        Err(e) => {
+            log::warn!("[BE] fetch_chapters: failed to fetch WBI mixin key: {}", e);
+            Vec::new()
+        }
+    }
 }
 
 /// Fetches available subtitles for a specific video part.
@@ -2836,7 +4219,7 @@ pub async fn fetch_part_qualities(
     // DASH format: separate video and audio streams
     if let Some(dash) = data.dash {
         let video_qualities = convert_qualities(&dash.video);
-        let audio_qualities = convert_qualities(&dash.audio);
+        let audio_qualities = convert_qualities(&dash.audio_streams());
         log::info!(
             "[BE] fetch_part_qualities: received {} video qualities, {} audio qualities",
             video_qualities.len(),
@@ -2855,6 +4238,7 @@ pub async fn fetch_part_qualities(
                 codecid: 0,
                 quality: first_non_empty(&[&f.new_description, &f.display_desc, &f.description])
                     .unwrap_or_else(|| quality_to_string(&f.quality)),
+                dynamic_range: DynamicRange::from_quality(f.quality),
             })
             .collect();
         // durl format has no separate audio stream
@@ -3081,6 +4465,8 @@ async fn prepare_subtitle_mode(
     };
 
     let client = build_client()?;
+    // Subtitle files are served from hdslb, outside the API scope.
+    let cdn_client = build_cdn_client()?;
 
     // Emit a "subtitle" progress stage so the frontend can surface that the
     // subtitle download is running. Without this, the UI stays frozen at
@@ -3089,7 +4475,7 @@ async fn prepare_subtitle_mode(
     // and fetched in parallel per language, so there is no meaningful
     // byte-level progress to stream — the frontend renders this as an
     // indeterminate "downloading..." state.
-    // Why after build_client: on client construction failure we return early
+    // Why after building the clients: on client construction failure we return early
     // without emitting, so no orphan "subtitle" entry is left in the
     // frontend's progress slice bound to this download_id.
     let _ = app.emit(
@@ -3172,7 +4558,7 @@ async fn prepare_subtitle_mode(
             .into_iter()
             .map(|sub| {
                 let srt_path = lib_path.join(format!("temp_sub_{download_id}_{}.srt", sub.lan));
-                let client = client.clone();
+                let client = cdn_client.clone();
                 async move {
                     let result = download_subtitle_with_retry(
                         &client,
@@ -3259,21 +4645,25 @@ async fn prepare_subtitle_mode(
 // Bangumi Handlers
 // ============================================================================
 
-/// Fetches bangumi (anime/series) episode metadata from Bilibili.
+/// Fetches bangumi (anime/series) metadata from Bilibili.
 ///
-/// Retrieves comprehensive information for a bangumi episode including title,
-/// all available episodes, quality options, and VIP/preview status.
+/// Retrieves comprehensive information for a bangumi season including title,
+/// all available episodes, extra sections (PVs, OVAs), quality options, and
+/// VIP/preview status.
 ///
 /// # Arguments
 ///
 /// * `app` - Tauri application handle for accessing cookie cache and settings
-/// * `ep_id` - Bangumi episode ID (e.g., 3051843)
+/// * `id` - Episode, season or media ID. For season and media IDs the first
+///   main episode becomes the target episode.
 ///
 /// # Returns
 ///
 /// Returns a `Video` struct containing:
-/// - Episode title and metadata
-/// - List of all episodes in the series
+/// - Season title and metadata
+/// - List of all main episodes in `parts`
+/// - Extra sections in `extra_sections`, with pages numbered after the
+///   main episodes
 /// - Quality options (may be limited for non-VIP users)
 /// - VIP and preview status flags
 ///
@@ -3286,50 +4676,29 @@ async fn prepare_subtitle_mode(
 /// - Episode is copyright restricted (`ERR::BANGUMI_COPYRIGHT_RESTRICTED`)
 /// - Access is denied (`ERR::BANGUMI_ACCESS_DENIED`)
 /// - API request fails (`ERR::API_ERROR`)
-pub async fn fetch_bangumi_info(app: &AppHandle, ep_id: i64) -> Result<Video, String> {
+pub async fn fetch_bangumi_info(app: &AppHandle, id: BangumiId) -> Result<Video, String> {
     use crate::utils::sanitize::{apply_title_replacements, resolve_duplicate_titles};
 
     log::info!(
-        "[BE] fetch_bangumi_info: requesting bangumi info for ep_id={}",
-        ep_id
+        "[BE] fetch_bangumi_info: requesting bangumi info for {:?}",
+        id
     );
 
     let cookies = read_cookie(app)?.unwrap_or_default();
     let cookie_header = build_cookie_header(&cookies);
     let is_limited_quality = cookie_header.is_empty();
 
-    let client = build_client()?;
-    let url = format!(
-        "https://api.bilibili.com/pgc/view/web/season?ep_id={}",
-        ep_id
-    );
-
-    let response = client
-        .get(&url)
-        .header(header::COOKIE, &cookie_header)
-        .header(header::REFERER, "https://www.bilibili.com")
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch bangumi info: {}", e))?;
-
-    check_http_status(response.status())?;
-    let body: BangumiSeasonApiResponse = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse bangumi response: {}", e))?;
-
-    validate_bangumi_response(body.code, &body.message)?;
-
-    let result = body
-        .result
-        .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())?;
+    let result = fetch_bangumi_season(&cookie_header, id).await?;
 
     // Find the target episode and use its AID as BVID placeholder
-    let target_ep = result
-        .episodes
-        .iter()
-        .find(|ep| ep.id == ep_id)
-        .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())?;
+    let target_ep = match id {
+        BangumiId::Episode(ep_id) => result.find_episode(ep_id),
+        BangumiId::Season(_) | BangumiId::Media(_) => result
+            .episodes
+            .first()
+            .or_else(|| result.section.iter().find_map(|s| s.episodes.first())),
+    }
+    .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())?;
 
     // Note: We don't block VIP-only episodes (status=13) here because
     // VIP members can still access them. The playurl API will return
@@ -3346,36 +4715,14 @@ pub async fn fetch_bangumi_info(app: &AppHandle, ep_id: i64) -> Result<Video, St
         .and_then(|s| s.auto_rename_duplicates)
         .unwrap_or(true);
 
-    // Convert episodes to VideoParts
+    // Convert main episodes and then extras to VideoParts with continuous
+    // page numbers; extras are split off again after duplicate resolution.
     let mut parts: Vec<VideoPart> = result
         .episodes
         .iter()
+        .chain(result.section.iter().flat_map(|s| s.episodes.iter()))
         .enumerate()
-        .map(|(idx, ep)| {
-            let original_part = if ep.long_title.is_empty() {
-                ep.title.clone()
-            } else {
-                format!("{} {}", ep.title, ep.long_title).trim().to_string()
-            };
-            let sanitized_part = apply_title_replacements(&original_part, replacements);
-            VideoPart {
-                cid: ep.cid,
-                page: (idx + 1) as i32,
-                part: original_part,
-                sanitized_part: Some(sanitized_part),
-                duration: ep.duration / 1000, // Convert ms to seconds
-                thumbnail: Thumbnail {
-                    url: ep.cover.clone(),
-                },
-                video_qualities: vec![],
-                audio_qualities: vec![],
-                subtitles: vec![],
-                ep_id: Some(ep.id),
-                status: Some(ep.status),
-                aid: Some(ep.aid),
-                is_preview: None, // Will be set when fetching qualities
-            }
-        })
+        .map(|(idx, ep)| bangumi_episode_to_part(ep, (idx + 1) as i32, replacements))
         .collect();
 
     // Apply duplicate title resolution if enabled
@@ -3395,6 +4742,17 @@ pub async fn fetch_bangumi_info(app: &AppHandle, ep_id: i64) -> Result<Video, St
         }
     }
 
+    let mut extras = parts.split_off(result.episodes.len()).into_iter();
+    let extra_sections = result
+        .section
+        .iter()
+        .filter(|s| !s.episodes.is_empty())
+        .map(|s| VideoSection {
+            title: s.title.clone(),
+            parts: extras.by_ref().take(s.episodes.len()).collect(),
+        })
+        .collect();
+
     // Apply title replacement to main title
     let sanitized_title = apply_title_replacements(&result.title, replacements);
 
@@ -3404,11 +4762,123 @@ pub async fn fetch_bangumi_info(app: &AppHandle, ep_id: i64) -> Result<Video, St
         parts,
         is_limited_quality,
         content_type: "bangumi".to_string(),
-        ep_id: Some(ep_id),
-        season_title: Some(result.title),
+        ep_id: Some(target_ep.id),
+        season_title: Some(result.title.clone()),
+        collection: None,
+        extra_sections,
+        metadata: Some(VideoMetadata {
+            title: result.title.clone(),
+            uploader: None,
+            description: Some(result.evaluate.clone()).filter(|d| !d.is_empty()),
+            publish_date: None,
+            tags: result.styles.clone(),
+            source_url: Some(format!(
+                "https://www.bilibili.com/bangumi/play/ss{}",
+                result.season_id
+            )),
+            cover_url: Some(result.cover.clone()),
+        }),
     })
 }
 
+/// Fetches the raw season data for a bangumi ID.
+///
+/// Media IDs are first resolved to their season through the review API,
+/// since the season API only accepts `ep_id` and `season_id`.
+///
+/// # Errors
+///
+/// Returns bangumi error codes from [`validate_bangumi_response`], or
+/// `ERR::BANGUMI_NOT_FOUND` when the response has no result.
+async fn fetch_bangumi_season(
+    cookie_header: &str,
+    id: BangumiId,
+) -> Result<BangumiSeasonResult, String> {
+    let client = build_client()?;
+    let query = match id {
+        BangumiId::Episode(ep_id) => format!("ep_id={ep_id}"),
+        BangumiId::Season(season_id) => format!("season_id={season_id}"),
+        BangumiId::Media(media_id) => {
+            let response = client
+                .get(format!(
+                    "https://api.bilibili.com/pgc/review/user?media_id={media_id}"
+                ))
+                .header(header::COOKIE, cookie_header)
+                .header(header::REFERER, "https://www.bilibili.com")
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch bangumi media info: {}", e))?;
+            check_http_status(response.status())?;
+            let body: BangumiMediaApiResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("Failed to parse bangumi media response: {}", e))?;
+            validate_bangumi_response(body.code, &body.message)?;
+            let media = body
+                .result
+                .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())?
+                .media;
+            format!("season_id={}", media.season_id)
+        }
+    };
+
+    let response = client
+        .get(format!(
+            "https://api.bilibili.com/pgc/view/web/season?{query}"
+        ))
+        .header(header::COOKIE, cookie_header)
+        .header(header::REFERER, "https://www.bilibili.com")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch bangumi info: {}", e))?;
+
+    check_http_status(response.status())?;
+    let body: BangumiSeasonApiResponse = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse bangumi response: {}", e))?;
+
+    validate_bangumi_response(body.code, &body.message)?;
+
+    body.result
+        .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())
+}
+
+/// Converts a season episode to a [`VideoPart`] with the given page number.
+///
+/// Qualities and preview status are left empty; they are loaded lazily by
+/// `fetch_bangumi_part_qualities`.
+fn bangumi_episode_to_part(
+    ep: &BangumiEpisode,
+    page: i32,
+    replacements: Option<&[TitleReplacement]>,
+) -> VideoPart {
+    let original_part = if ep.long_title.is_empty() {
+        ep.title.clone()
+    } else {
+        format!("{} {}", ep.title, ep.long_title).trim().to_string()
+    };
+    let sanitized_part =
+        crate::utils::sanitize::apply_title_replacements(&original_part, replacements);
+    VideoPart {
+        cid: ep.cid,
+        page,
+        part: original_part,
+        sanitized_part: Some(sanitized_part),
+        duration: ep.duration / 1000, // Convert ms to seconds
+        thumbnail: Thumbnail {
+            url: ep.cover.clone(),
+        },
+        video_qualities: vec![],
+        audio_qualities: vec![],
+        subtitles: vec![],
+        ep_id: Some(ep.id),
+        status: Some(ep.status),
+        aid: Some(ep.aid),
+        is_preview: None, // Will be set when fetching qualities
+    }
+}
+
 /// Fetches bangumi player result for quality selection.
 ///
 /// Returns raw player result containing either DASH or durl format.
@@ -3431,7 +4901,7 @@ pub async fn fetch_bangumi_info(app: &AppHandle, ep_id: i64) -> Result<Video, St
 /// - Non-success HTTP status
 /// - API errors (`ERR::BANGUMI_NOT_FOUND`, `ERR::BANGUMI_ACCESS_DENIED`, etc.)
 /// - Neither DASH nor durl available (`ERR::BANGUMI_NO_DASH`)
-async fn fetch_bangumi_player_result(
+pub(crate) async fn fetch_bangumi_player_result(
     cookies: &[CookieEntry],
     ep_id: i64,
     cid: i64,
@@ -3440,8 +4910,8 @@ async fn fetch_bangumi_player_result(
     let cookie_header = build_cookie_header(cookies);
 
     let url = format!(
-        "https://api.bilibili.com/pgc/player/web/playurl?ep_id={}&cid={}&qn=116&fnval=2064&fnver=0&fourk=1",
-        ep_id, cid
+        "https://api.bilibili.com/pgc/player/web/playurl?ep_id={}&cid={}&qn={}&fnval={}&fnver=0&fourk=1",
+        ep_id, cid, QUALITY_8K, PLAYURL_FNVAL
     );
 
     let response = client
@@ -3604,12 +5074,15 @@ async fn refetch_dash_urls(
 
     // Reuse the same codec-aware stream selection as the initial download so
     // a retry picks the same codec (keeps the merged output consistent).
-    let (streams_for_selection, _) = select_streams_by_codec_priority(app, &dash.video).await;
+    let (streams_for_selection, _) =
+        select_streams_by_codec_priority(app, &dash.video, video_quality).await;
     let (video_url, video_backup_urls, _) =
         select_stream_url(&streams_for_selection, video_quality)?;
+    let audio_streams = dash.audio_streams();
     let resolved_audio_quality =
-        audio_quality.unwrap_or_else(|| dash.audio.first().map(|a| a.id).unwrap_or(30280));
-    let (audio_url, audio_backup_urls, _) = select_stream_url(&dash.audio, resolved_audio_quality)?;
+        audio_quality.unwrap_or_else(|| audio_streams.first().map(|a| a.id).unwrap_or(30280));
+    let (audio_url, audio_backup_urls, _) =
+        select_stream_url(&audio_streams, resolved_audio_quality)?;
     Ok(FreshDashUrls {
         video_url,
         video_backup_urls,
@@ -3719,7 +5192,7 @@ pub async fn fetch_bangumi_part_qualities(
     // Try DASH format first
     if let Some(dash) = &result.dash {
         let video_qualities = convert_qualities(&dash.video);
-        let audio_qualities = convert_qualities(&dash.audio);
+        let audio_qualities = convert_qualities(&dash.audio_streams());
         log::info!(
             "[BE] fetch_bangumi_part_qualities: received {} video qualities, {} audio qualities",
             video_qualities.len(),
@@ -3738,6 +5211,7 @@ pub async fn fetch_bangumi_part_qualities(
                 id: entry.quality,
                 codecid: 7, // AVC for MP4 format
                 quality: quality_to_string(&entry.quality),
+                dynamic_range: DynamicRange::from_quality(entry.quality),
             })
             .collect();
 
@@ -3782,7 +5256,7 @@ pub async fn fetch_bangumi_part_qualities(
 /// ```
 pub async fn expand_short_url(url: String) -> Result<String, String> {
     // Build a client with redirect policy for short URL expansion
-    let client = Client::builder()
+    let client = proxy::apply(Client::builder(), ProxyTarget::Api)
         .user_agent(USER_AGENT)
         .redirect(reqwest::redirect::Policy::limited(5))
         .timeout(std::time::Duration::from_secs(10))
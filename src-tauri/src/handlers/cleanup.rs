@@ -8,6 +8,8 @@ use std::time::{Duration, SystemTime};
 
 use tauri::AppHandle;
 
+use crate::handlers::cover::COVER_EXTENSIONS;
+use crate::utils::download_manifest::{manifest_path, MANIFEST_SUFFIX};
 use crate::utils::paths::get_lib_path;
 
 /// Default age threshold in hours (24 hours = 1 day)
@@ -27,9 +29,15 @@ pub struct CleanupResult {
 /// Scans the lib directory for temp files matching:
 /// - `temp_video_*.m4s` - Temporary video segments
 /// - `temp_audio_*.m4s` - Temporary audio segments
-/// - `temp_sub_*.srt` - Temporary subtitle files
+/// - `temp_sub_*.srt` / `temp_sub_*.ass` - Temporary subtitle and danmaku files
+/// - `temp_cover_*.{jpg,png,webp,...}` - Temporary cover art
+/// - `temp_chapters_*.txt` - Temporary ffmetadata chapter files
+/// - `temp_*.m4s.manifest.json` - Resume manifests for partial streams
 ///
-/// Files older than 24 hours are deleted.
+/// Files older than 24 hours are deleted. A partial stream with a resume
+/// manifest is aged by whichever of the pair was written last, so a download
+/// that was interrupted recently is kept for resuming. Manifests whose
+/// stream file no longer exists are deleted regardless of age.
 pub fn cleanup_temp_files(app: &AppHandle, max_age_hours: Option<u64>) -> CleanupResult {
     let lib_path = get_lib_path(app);
     let max_age = max_age_hours.unwrap_or(DEFAULT_MAX_AGE_HOURS);
@@ -47,8 +55,8 @@ pub fn cleanup_temp_files(app: &AppHandle, max_age_hours: Option<u64>) -> Cleanu
                 let path = entry.path();
                 if is_temp_file(&path) {
                     if let Ok(metadata) = entry.metadata() {
-                        if let Ok(modified) = metadata.modified() {
-                            if modified < threshold {
+                        if let Ok(modified) = last_modified(&path, &metadata) {
+                            if modified < threshold || is_orphan_manifest(&path) {
                                 match fs::remove_file(&path) {
                                     Ok(()) => {
                                         log::info!("[BE] cleanup_temp_files: deleted {:?}", path);
@@ -80,6 +88,34 @@ pub fn cleanup_temp_files(app: &AppHandle, max_age_hours: Option<u64>) -> Cleanu
     result
 }
 
+/// Returns the modification time used to age a temp file.
+///
+/// For a stream with a resume manifest this is the newer of the two
+/// timestamps, so a pair is always deleted together. For a manifest it is
+/// the newer of itself and its stream.
+fn last_modified(path: &Path, metadata: &fs::Metadata) -> std::io::Result<SystemTime> {
+    let own = metadata.modified()?;
+    let sibling = match stream_path_for_manifest(path) {
+        Some(stream) => stream,
+        None => manifest_path(path),
+    };
+    let sibling_modified = fs::metadata(sibling).and_then(|m| m.modified()).ok();
+    Ok(sibling_modified.map_or(own, |s| s.max(own)))
+}
+
+/// Returns the stream path a manifest belongs to, or `None` when `path` is
+/// not a manifest.
+fn stream_path_for_manifest(path: &Path) -> Option<std::path::PathBuf> {
+    let name = path.file_name()?.to_str()?;
+    let stream_name = name.strip_suffix(MANIFEST_SUFFIX)?;
+    Some(path.with_file_name(stream_name))
+}
+
+/// Returns `true` for a resume manifest whose stream file is gone.
+fn is_orphan_manifest(path: &Path) -> bool {
+    stream_path_for_manifest(path).is_some_and(|stream| !stream.exists())
+}
+
 /// Checks if a file is a temp download file.
 ///
 /// # Temp File Pattern
@@ -87,7 +123,11 @@ pub fn cleanup_temp_files(app: &AppHandle, max_age_hours: Option<u64>) -> Cleanu
 /// Matches files with the following naming conventions:
 /// - `temp_video_*.m4s` - Temporary video segments
 /// - `temp_audio_*.m4s` - Temporary audio segments
-/// - `temp_sub_*.srt` - Temporary subtitle files
+/// - `temp_sub_*.srt` / `temp_sub_*.ass` - Temporary subtitle and danmaku files
+/// - `temp_cover_*.{jpg,png,webp,...}` - Temporary cover art
+/// - `temp_chapters_*.txt` - Temporary ffmetadata chapter files
+/// - `temp_video_*.m4s.manifest.json` / `temp_audio_*.m4s.manifest.json` -
+///   Resume manifests
 ///
 /// # Arguments
 ///
@@ -105,6 +145,10 @@ pub fn cleanup_temp_files(app: &AppHandle, max_age_hours: Option<u64>) -> Cleanu
 /// assert!(is_temp_file(Path::new("temp_video_123.m4s")));
 /// assert!(is_temp_file(Path::new("temp_audio_456.m4s")));
 /// assert!(is_temp_file(Path::new("temp_sub_789.srt")));
+/// assert!(is_temp_file(Path::new("temp_cover_789.jpg")));
+/// assert!(is_temp_file(Path::new("temp_cover_789.webp")));
+/// assert!(is_temp_file(Path::new("temp_chapters_789.txt")));
+/// assert!(is_temp_file(Path::new("temp_video_123.m4s.manifest.json")));
 /// assert!(!is_temp_file(Path::new("final_video.mp4")));
 /// ```
 fn is_temp_file(path: &Path) -> bool {
@@ -112,10 +156,19 @@ fn is_temp_file(path: &Path) -> bool {
         Some(name) => name,
         None => return false,
     };
+    // Manifests are matched by the stream name they belong to.
+    let file_name = file_name.strip_suffix(MANIFEST_SUFFIX).unwrap_or(file_name);
 
     let is_video = file_name.starts_with("temp_video_") && file_name.ends_with(".m4s");
     let is_audio = file_name.starts_with("temp_audio_") && file_name.ends_with(".m4s");
-    let is_subtitle = file_name.starts_with("temp_sub_") && file_name.ends_with(".srt");
+    let is_subtitle = file_name.starts_with("temp_sub_")
+        && (file_name.ends_with(".srt") || file_name.ends_with(".ass"));
+    let is_cover = file_name.starts_with("temp_cover_")
+        && Path::new(file_name)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| COVER_EXTENSIONS.contains(&ext));
+    let is_chapters = file_name.starts_with("temp_chapters_") && file_name.ends_with(".txt");
 
-    is_video || is_audio || is_subtitle
+    is_video || is_audio || is_subtitle || is_cover || is_chapters
 }
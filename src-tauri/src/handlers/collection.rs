@@ -0,0 +1,315 @@
+//! Bilibili Collection and Series Module
+//!
+//! This module handles ordered groups of videos that span several BV IDs:
+//!
+//! ## Main Features
+//!
+//! - **UGC Collections (合集)**: Built from the `ugc_season` block of the view
+//!   API, which `fetch_video_info` already requests
+//! - **Series (系列)**: Fetched from the series archive API by series ID
+//! - **Batch Enqueue**: Queues a whole collection (or a subset) with
+//!   filenames prefixed by the episode number so files sort in episode order
+//!
+//! ## API Endpoints
+//!
+//! - Series metadata: `GET https://api.bilibili.com/x/series/series`
+//! - Series videos: `GET https://api.bilibili.com/x/series/archives`
+
+use reqwest::header;
+use tauri::AppHandle;
+
+use crate::constants::REFERER;
+use crate::handlers::bilibili::{self, build_client, build_cookie_header_from_cache};
+use crate::handlers::queue::{self, QueueJob};
+use crate::handlers::settings;
+use crate::models::bilibili_api::{SeriesArchivesApiResponse, SeriesMetaApiResponse, UgcSeason};
+use crate::models::frontend_dto::{CollectionEpisode, CollectionKind, VideoCollection};
+use crate::utils::download_archive::ArchiveMode;
+use crate::utils::sanitize::apply_title_replacements;
+
+/// Page size used when walking a series (API maximum).
+const SERIES_PAGE_SIZE: i64 = 100;
+
+/// Converts the `ugc_season` block of a view response into a collection.
+///
+/// Episodes are numbered continuously across sections. Section titles are
+/// only kept when the collection has more than one section, since a single
+/// section is usually named after the collection itself.
+pub fn collection_from_ugc_season(season: &UgcSeason) -> VideoCollection {
+    let multi_section = season.sections.len() > 1;
+    let episodes = season
+        .sections
+        .iter()
+        .flat_map(|section| {
+            section
+                .episodes
+                .iter()
+                .map(move |ep| (multi_section.then(|| section.title.clone()), ep))
+        })
+        .enumerate()
+        .map(|(i, (section_title, ep))| CollectionEpisode {
+            index: i as i32 + 1,
+            section_title,
+            bvid: ep.bvid.clone(),
+            title: ep.title.clone(),
+            cover: ep.arc.as_ref().map(|a| a.pic.clone()).unwrap_or_default(),
+            duration: ep.arc.as_ref().map(|a| a.duration).unwrap_or(0),
+            part_count: ep.pages.as_ref().map(|p| p.len() as i32),
+        })
+        .collect();
+
+    VideoCollection {
+        kind: CollectionKind::Season,
+        id: season.id,
+        mid: season.mid,
+        title: season.title.clone(),
+        cover: season.cover.clone(),
+        episodes,
+    }
+}
+
+/// Fetches an uploader series with all of its videos.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for accessing cookie cache
+/// * `mid` - Series owner's member ID
+/// * `series_id` - Series ID (the `sid` of a `channel/seriesdetail` URL)
+///
+/// # Returns
+///
+/// The series as a [`VideoCollection`] with episodes in ascending publish
+/// order.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Network request fails or the response cannot be parsed
+/// - Bilibili API returns a non-zero code
+pub async fn fetch_series(
+    app: &AppHandle,
+    mid: i64,
+    series_id: i64,
+) -> Result<VideoCollection, String> {
+    log::info!(
+        "[BE] fetch_series: requesting mid={}, series_id={}",
+        mid,
+        series_id
+    );
+    let client = build_client()?;
+    let cookie_header = build_cookie_header_from_cache(app).unwrap_or_default();
+
+    let meta = client
+        .get(format!(
+            "https://api.bilibili.com/x/series/series?series_id={series_id}"
+        ))
+        .header(header::COOKIE, &cookie_header)
+        .header(header::REFERER, REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch series info: {e}"))?
+        .json::<SeriesMetaApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse series info response: {e}"))?;
+    if meta.code != 0 {
+        return Err(format!("API error (code {}): {}", meta.code, meta.message));
+    }
+    let meta = meta.data.ok_or("No data in response")?.meta;
+
+    let mut episodes: Vec<CollectionEpisode> = Vec::new();
+    let mut page_num = 1;
+    loop {
+        let url = format!(
+            "https://api.bilibili.com/x/series/archives?mid={}&series_id={}&only_normal=true&sort=asc&pn={}&ps={}",
+            mid, series_id, page_num, SERIES_PAGE_SIZE
+        );
+        let response = client
+            .get(&url)
+            .header(header::COOKIE, &cookie_header)
+            .header(header::REFERER, REFERER)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch series videos: {e}"))?
+            .json::<SeriesArchivesApiResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse series videos response: {e}"))?;
+        if response.code != 0 {
+            return Err(format!(
+                "API error (code {}): {}",
+                response.code, response.message
+            ));
+        }
+        let data = response.data.ok_or("No data in response")?;
+        let reached_end =
+            data.archives.is_empty() || data.page.num * data.page.size >= data.page.total;
+        for archive in data.archives {
+            episodes.push(CollectionEpisode {
+                index: episodes.len() as i32 + 1,
+                section_title: None,
+                bvid: archive.bvid,
+                title: archive.title,
+                cover: archive.pic,
+                duration: archive.duration,
+                part_count: None,
+            });
+        }
+        if reached_end {
+            break;
+        }
+        page_num += 1;
+    }
+
+    log::info!(
+        "[BE] fetch_series: series \"{}\" has {} video(s)",
+        meta.name,
+        episodes.len()
+    );
+    Ok(VideoCollection {
+        kind: CollectionKind::Series,
+        id: meta.series_id,
+        mid: meta.mid,
+        title: meta.name,
+        cover: meta.cover,
+        episodes,
+    })
+}
+
+/// Enqueues the episodes of a collection in episode order.
+///
+/// Each episode is resolved with `fetch_video_info` and all of its parts
+/// are queued at the best available quality. Filenames are prefixed with
+/// the collection title and the zero-padded episode number (e.g.
+/// `"My Course - 03 Lesson 3"`) so they sort in episode order.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `collection` - Collection as returned by `fetch_video_info` or
+///   [`fetch_series`]
+/// * `bvids` - Optional subset of episodes to enqueue (all when `None`)
+/// * `archive_mode` - How items already in the download archive are handled
+///
+/// # Returns
+///
+/// The queue jobs that were added. Episodes whose details cannot be fetched
+/// are skipped and logged.
+pub async fn enqueue_collection(
+    app: &AppHandle,
+    collection: &VideoCollection,
+    bvids: Option<Vec<String>>,
+    archive_mode: ArchiveMode,
+) -> Result<Vec<QueueJob>, String> {
+    log::info!(
+        "[BE] enqueue_collection: kind={:?}, id={}, episodes={}",
+        collection.kind,
+        collection.id,
+        collection.episodes.len()
+    );
+    let settings = settings::get_settings(app).await.ok();
+    let replacements = settings
+        .as_ref()
+        .and_then(|s| s.title_replacements.as_deref());
+    let collection_title = apply_title_replacements(&collection.title, replacements);
+    let width = collection.episodes.len().to_string().len().max(2);
+
+    let mut options = Vec::new();
+    for ep in collection
+        .episodes
+        .iter()
+        .filter(|ep| bvids.as_ref().is_none_or(|ids| ids.contains(&ep.bvid)))
+    {
+        match bilibili::fetch_video_info(app, &ep.bvid).await {
+            Ok(video) => {
+                options.extend(
+                    queue::part_download_options(&video)
+                        .into_iter()
+                        .map(|mut opts| {
+                            opts.filename = episode_filename(
+                                &collection_title,
+                                ep.index,
+                                width,
+                                &opts.filename,
+                            );
+                            opts
+                        }),
+                );
+            }
+            Err(e) => log::warn!("[BE] enqueue_collection: skipping bvid={}: {}", ep.bvid, e),
+        }
+    }
+    Ok(queue::enqueue_many(app, options, archive_mode).await)
+}
+
+/// Builds an episode-ordered filename: `"{collection} - {NN} {filename}"`.
+fn episode_filename(collection_title: &str, index: i32, width: usize, filename: &str) -> String {
+    format!("{collection_title} - {index:0width$} {filename}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn season(sections: serde_json::Value) -> UgcSeason {
+        serde_json::from_value(json!({
+            "id": 7,
+            "title": "Course",
+            "cover": "c.jpg",
+            "mid": 42,
+            "sections": sections
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn episodes_are_numbered_across_sections() {
+        let season = season(json!([
+            {"id": 1, "title": "Part A", "episodes": [
+                {"aid": 1, "bvid": "BV1", "cid": 11, "title": "Intro",
+                 "arc": {"pic": "1.jpg", "duration": 60}},
+                {"aid": 2, "bvid": "BV2", "cid": 12, "title": "Setup"}
+            ]},
+            {"id": 2, "title": "Part B", "episodes": [
+                {"aid": 3, "bvid": "BV3", "cid": 13, "title": "Deep dive",
+                 "pages": [{"cid": 13, "page": 1, "part": "a", "duration": 1},
+                           {"cid": 14, "page": 2, "part": "b", "duration": 1}]}
+            ]}
+        ]));
+
+        let collection = collection_from_ugc_season(&season);
+        assert_eq!(collection.kind, CollectionKind::Season);
+        assert_eq!(collection.episodes.len(), 3);
+        assert_eq!(collection.episodes[2].index, 3);
+        assert_eq!(
+            collection.episodes[2].section_title.as_deref(),
+            Some("Part B")
+        );
+        assert_eq!(collection.episodes[2].part_count, Some(2));
+        assert_eq!(collection.episodes[0].duration, 60);
+    }
+
+    #[test]
+    fn single_section_title_is_dropped() {
+        let season = season(json!([
+            {"id": 1, "title": "Course", "episodes": [
+                {"aid": 1, "bvid": "BV1", "cid": 11, "title": "Intro"}
+            ]}
+        ]));
+        assert_eq!(
+            collection_from_ugc_season(&season).episodes[0].section_title,
+            None
+        );
+    }
+
+    #[test]
+    fn episode_filename_is_zero_padded() {
+        assert_eq!(
+            episode_filename("Course", 3, 2, "Intro"),
+            "Course - 03 Intro"
+        );
+        assert_eq!(
+            episode_filename("Course", 7, 3, "Intro p2"),
+            "Course - 007 Intro p2"
+        );
+    }
+}
@@ -3,6 +3,8 @@
 //! This module manages:
 //! - Maximum concurrent video downloads (semaphore)
 //! - Download cancellation tokens for aborting in-progress downloads
+//! - Pause requests, which stop a download like a cancel but keep its
+//!   partial temp files for resuming
 
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
@@ -77,6 +79,9 @@ pub struct DownloadCancelRegistry {
     /// children that are not in `tokens` yet). `download_video` checks this
     /// on start and rejects immediately so cancelled pending parts never run.
     cancelled_ids: Mutex<HashSet<String>>,
+    /// IDs whose cancellation was requested as a pause. `download_video`
+    /// keeps resumable temp files for these instead of deleting them.
+    paused_ids: Mutex<HashSet<String>>,
 }
 
 impl DownloadCancelRegistry {
@@ -85,6 +90,7 @@ impl DownloadCancelRegistry {
         Self {
             tokens: Mutex::new(HashMap::new()),
             cancelled_ids: Mutex::new(HashSet::new()),
+            paused_ids: Mutex::new(HashSet::new()),
         }
     }
 
@@ -264,6 +270,46 @@ impl DownloadCancelRegistry {
         let mut ids = self.cancelled_ids.lock().await;
         ids.remove(download_id);
     }
+
+    /// Stops a download as a pause rather than a cancel.
+    ///
+    /// The id is flagged as paused *before* its token is cancelled so that
+    /// `download_video`, which observes the cancellation, already sees the
+    /// flag when it decides whether to keep its partial temp files. When no
+    /// token is registered yet (the download is still starting), the id is
+    /// pre-cancelled instead, mirroring `cancel_download`.
+    ///
+    /// # Arguments
+    ///
+    /// * `download_id` - Unique identifier for the download to pause
+    ///
+    /// # Returns
+    ///
+    /// `true` if a running download was signalled, `false` if it was only
+    /// pre-cancelled
+    pub async fn pause(&self, download_id: &str) -> bool {
+        {
+            let mut ids = self.paused_ids.lock().await;
+            ids.insert(download_id.to_string());
+        }
+        let was_running = self.cancel(download_id).await;
+        if !was_running {
+            self.mark_cancelled(download_id).await;
+        }
+        was_running
+    }
+
+    /// Returns true if the download's cancellation was requested as a pause.
+    pub async fn is_paused(&self, download_id: &str) -> bool {
+        let ids = self.paused_ids.lock().await;
+        ids.contains(download_id)
+    }
+
+    /// Clears the paused flag, returning whether it was set.
+    pub async fn take_paused(&self, download_id: &str) -> bool {
+        let mut ids = self.paused_ids.lock().await;
+        ids.remove(download_id)
+    }
 }
 
 #[cfg(test)]
@@ -322,6 +368,39 @@ mod tests {
         );
     }
 
+    /// pause() must cancel the token like cancel() while leaving the paused
+    /// flag set, so download_video keeps its resumable temp files.
+    #[tokio::test]
+    async fn pause_cancels_token_and_records_paused_flag() {
+        let registry = DownloadCancelRegistry::new();
+        let id = "test-id-pause";
+
+        let token = registry.register(id).await;
+        assert!(
+            registry.pause(id).await,
+            "running download should be signalled"
+        );
+        assert!(token.is_cancelled());
+        assert!(registry.is_paused(id).await);
+        assert!(registry.take_paused(id).await);
+        assert!(
+            !registry.is_paused(id).await,
+            "take_paused should clear the flag"
+        );
+    }
+
+    /// Pausing a download that has not registered its token yet must still
+    /// stop it on start via the pre-cancel flag.
+    #[tokio::test]
+    async fn pause_before_start_marks_cancelled() {
+        let registry = DownloadCancelRegistry::new();
+        let id = "test-id-pause-pending";
+
+        assert!(!registry.pause(id).await);
+        assert!(registry.is_cancelled(id).await);
+        assert!(registry.is_paused(id).await);
+    }
+
     /// Prerequisite for the get_token-None fallback path: cancel() must
     /// remove the token so get_token returns None for in-flight callers.
     #[tokio::test]
@@ -0,0 +1,194 @@
+//! Cover Art Module
+//!
+//! Downloads the cover (`pic`) of a video at its original resolution so it
+//! can be embedded into merged MP4 files as an `attached_pic` stream and,
+//! optionally, kept next to the video as a sidecar image.
+//!
+//! The temporary cover is `temp_cover_{download_id}.{ext}` in the lib
+//! directory, where the extension follows the image format (`Content-Type`,
+//! else the URL); leftovers are removed by `cleanup_temp_files`.
+
+use std::path::{Path, PathBuf};
+
+use reqwest::header;
+
+use crate::constants::REFERER;
+use crate::handlers::bilibili::build_cdn_client;
+
+/// Returns the URL of the original-resolution image.
+///
+/// Bilibili image URLs accept an `@{width}w_{height}h...` suffix that
+/// selects a resized variant; dropping it yields the original upload.
+/// Protocol-relative and plain `http` URLs are upgraded to `https`.
+pub fn original_cover_url(url: &str) -> String {
+    let url = url.split('@').next().unwrap_or(url);
+    if let Some(rest) = url.strip_prefix("//") {
+        format!("https://{rest}")
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("https://{rest}")
+    } else {
+        url.to_string()
+    }
+}
+
+/// Extensions of the image formats a cover is saved as.
+pub const COVER_EXTENSIONS: &[&str] = &["jpg", "png", "webp", "gif", "avif"];
+
+/// Returns the temporary cover path of a download.
+pub fn temp_cover_path(lib_path: &Path, download_id: &str, extension: &str) -> PathBuf {
+    lib_path.join(format!("temp_cover_{download_id}.{extension}"))
+}
+
+/// Removes the temporary cover of a download, whatever its format.
+pub async fn remove_temp_covers(lib_path: &Path, download_id: &str) {
+    for extension in COVER_EXTENSIONS {
+        let _ = tokio::fs::remove_file(temp_cover_path(lib_path, download_id, extension)).await;
+    }
+}
+
+/// Returns the sidecar cover path of a downloaded video
+/// (`{filename}.cover.{ext}`, with the extension of `cover_path`).
+pub fn sidecar_path_for(output_path: &Path, cover_path: &Path) -> PathBuf {
+    let extension = cover_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("jpg");
+    output_path.with_extension(format!("cover.{extension}"))
+}
+
+/// Returns the MIME type of a cover extension (see [`COVER_EXTENSIONS`]).
+pub fn mime_type_for(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "webp" => "image/webp",
+        "gif" => "image/gif",
+        "avif" => "image/avif",
+        _ => "image/jpeg",
+    }
+}
+
+/// Returns the file extension of a cover image.
+///
+/// The `Content-Type` of the response decides; the extension of the URL
+/// is the fallback, and JPEG (the format of most covers) the default.
+fn cover_extension(content_type: Option<&str>, url: &str) -> &'static str {
+    let from_mime = |mime: &str| match mime.trim().to_ascii_lowercase().as_str() {
+        "image/jpeg" | "image/jpg" | "image/pjpeg" => Some("jpg"),
+        "image/png" => Some("png"),
+        "image/webp" => Some("webp"),
+        "image/gif" => Some("gif"),
+        "image/avif" => Some("avif"),
+        _ => None,
+    };
+    let from_url = || {
+        let ext = Path::new(url.split(['?', '#']).next().unwrap_or(url))
+            .extension()?
+            .to_str()?
+            .to_ascii_lowercase();
+        match ext.as_str() {
+            "jpeg" => Some("jpg"),
+            ext => COVER_EXTENSIONS.iter().copied().find(|&known| known == ext),
+        }
+    };
+    content_type
+        .and_then(|v| from_mime(v.split(';').next().unwrap_or(v)))
+        .or_else(from_url)
+        .unwrap_or("jpg")
+}
+
+/// Downloads a cover image at its original resolution into the lib
+/// directory.
+///
+/// # Arguments
+///
+/// * `url` - Cover URL as returned by the API (`pic`)
+/// * `lib_path` - Lib directory for temporary files
+/// * `download_id` - Download ID, used for the temp file name
+///
+/// # Returns
+///
+/// The written file (see [`temp_cover_path`]).
+///
+/// # Errors
+///
+/// Returns an error if the request fails, the response is not an image, or
+/// the file cannot be written.
+pub async fn download_cover(
+    url: &str,
+    lib_path: &Path,
+    download_id: &str,
+) -> Result<PathBuf, String> {
+    let url = original_cover_url(url);
+    let client = build_cdn_client()?;
+    let response = client
+        .get(&url)
+        .header(header::REFERER, REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch cover: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Cover returned HTTP {}", response.status()));
+    }
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    if !content_type
+        .as_deref()
+        .is_none_or(|v| v.starts_with("image/"))
+    {
+        return Err(format!("Cover is not an image: {url}"));
+    }
+    let path = temp_cover_path(
+        lib_path,
+        download_id,
+        cover_extension(content_type.as_deref(), &url),
+    );
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read cover: {e}"))?;
+    tokio::fs::write(&path, &bytes)
+        .await
+        .map_err(|e| format!("Failed to write cover: {e}"))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn original_cover_url_drops_resize_suffix() {
+        assert_eq!(
+            original_cover_url("http://i0.hdslb.com/bfs/archive/abc.jpg@672w_378h_1c.webp"),
+            "https://i0.hdslb.com/bfs/archive/abc.jpg"
+        );
+        assert_eq!(
+            original_cover_url("//i1.hdslb.com/bfs/archive/abc.png"),
+            "https://i1.hdslb.com/bfs/archive/abc.png"
+        );
+        assert_eq!(
+            original_cover_url("https://i2.hdslb.com/bfs/archive/abc.jpg"),
+            "https://i2.hdslb.com/bfs/archive/abc.jpg"
+        );
+    }
+
+    #[test]
+    fn cover_extension_follows_content_type_then_url() {
+        let url = "https://i0.hdslb.com/bfs/archive/abc.png";
+        assert_eq!(cover_extension(Some("image/webp"), url), "webp");
+        assert_eq!(cover_extension(Some("image/JPEG; charset=x"), url), "jpg");
+        assert_eq!(cover_extension(Some("image/x-unknown"), url), "png");
+        assert_eq!(cover_extension(None, "https://a/b.jpeg?x=1"), "jpg");
+        assert_eq!(cover_extension(None, "https://a/b"), "jpg");
+        assert_eq!(
+            sidecar_path_for(
+                Path::new("/v/Title.mp4"),
+                Path::new("/lib/temp_cover_1.png")
+            ),
+            Path::new("/v/Title.cover.png")
+        );
+    }
+}
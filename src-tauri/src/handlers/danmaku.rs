@@ -0,0 +1,273 @@
+//! Danmaku (弾幕) Download Module
+//!
+//! This module fetches the bullet comments of a video part and turns them
+//! into an ASS subtitle track:
+//!
+//! ## Main Features
+//!
+//! - **Segmented Fetch**: Reads every 6-minute protobuf segment from
+//!   `seg.so`, falling back to the XML endpoint when a segment fails
+//! - **Raw Storage**: Writes the comments as a Bilibili XML file next to the
+//!   video, which external danmaku players can load
+//! - **ASS Conversion**: Lays comments out as scrolling/top/bottom events
+//!   (see [`crate::utils::danmaku`]). MKV downloads embed the track through
+//!   `MergeMode::SoftSub`; MP4/MOV downloads keep it as `{name}.danmaku.ass`
+//!   next to the video, since `mov_text` cannot express positioned or moving
+//!   text
+//!
+//! ## API Endpoints
+//!
+//! - Protobuf: `GET https://api.bilibili.com/x/v2/dm/web/seg.so`
+//! - XML: `GET https://comment.bilibili.com/{cid}.xml` (a static comment
+//!   host, fetched with the CDN proxy scope)
+
+use std::path::{Path, PathBuf};
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use reqwest::{header, Client};
+use tauri::AppHandle;
+
+use crate::constants::REFERER;
+use crate::handlers::bilibili::{
+    build_cdn_client, build_client, build_cookie_header_from_cache, build_output_path,
+    create_output_dir,
+};
+use crate::handlers::ffmpeg::SubtitleMergeOptions;
+use crate::models::frontend_dto::DanmakuFiles;
+use crate::utils::danmaku::{self, Danmaku, DanmakuOptions};
+
+const SEG_URL: &str = "https://api.bilibili.com/x/v2/dm/web/seg.so";
+const XML_URL: &str = "https://comment.bilibili.com";
+
+/// Length of one `seg.so` segment in seconds.
+const SEGMENT_SECONDS: i64 = 360;
+
+/// Parallel segment requests.
+const SEGMENT_CONCURRENCY: usize = 4;
+
+/// Danmaku track prepared for a merge.
+pub struct DanmakuTrack {
+    /// ASS file to embed as a soft subtitle (MKV) or to copy next to the
+    /// output (MP4/MOV)
+    pub subtitle: SubtitleMergeOptions,
+    /// Raw comments as Bilibili XML, written next to the output on success
+    pub raw_xml: String,
+    /// Number of comments fetched
+    pub count: usize,
+}
+
+/// Fetches all danmaku of a video part.
+///
+/// Reads the protobuf segments covering `duration_seconds`; if any segment
+/// fails, the whole list is fetched from the XML endpoint instead.
+///
+/// # Arguments
+///
+/// * `client` - API client for `seg.so`
+/// * `cookie_header` - Optional login cookies (anonymous requests work too)
+/// * `cid` - Content ID of the video part
+/// * `duration_seconds` - Part duration, used to compute the segment count
+///
+/// # Errors
+///
+/// Returns an error only if both endpoints fail.
+pub async fn fetch_danmaku(
+    client: &Client,
+    cookie_header: Option<&str>,
+    cid: i64,
+    duration_seconds: i64,
+) -> Result<Vec<Danmaku>, String> {
+    let segments = ((duration_seconds.max(1) + SEGMENT_SECONDS - 1) / SEGMENT_SECONDS).max(1);
+    log::info!("[BE] fetch_danmaku: cid={}, segments={}", cid, segments);
+
+    let result: Result<Vec<Vec<Danmaku>>, String> = stream::iter(1..=segments)
+        .map(|index| fetch_segment(client, cookie_header, cid, index))
+        .buffered(SEGMENT_CONCURRENCY)
+        .try_collect()
+        .await;
+
+    match result {
+        Ok(parts) => Ok(parts.into_iter().flatten().collect()),
+        Err(e) => {
+            log::warn!(
+                "[BE] fetch_danmaku: seg.so failed for cid={}, falling back to XML: {}",
+                cid,
+                e
+            );
+            fetch_xml(cid).await.map(|xml| danmaku::parse_xml(&xml))
+        }
+    }
+}
+
+/// Fetches one protobuf segment (1-indexed) from `seg.so`.
+async fn fetch_segment(
+    client: &Client,
+    cookie_header: Option<&str>,
+    cid: i64,
+    index: i64,
+) -> Result<Vec<Danmaku>, String> {
+    let mut request = client
+        .get(SEG_URL)
+        .query(&[("type", 1), ("oid", cid), ("segment_index", index)])
+        .header(header::REFERER, REFERER);
+    if let Some(cookie) = cookie_header {
+        request = request.header(header::COOKIE, cookie);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch danmaku segment {index}: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Danmaku segment {index} returned HTTP {}",
+            response.status()
+        ));
+    }
+    // Why: errors come back as a JSON body with HTTP 200.
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.contains("json"));
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to read danmaku segment {index}: {e}"))?;
+    if is_json {
+        return Err(format!(
+            "Danmaku segment {index} API error: {}",
+            String::from_utf8_lossy(&body)
+        ));
+    }
+    danmaku::parse_segment(&body)
+}
+
+/// Fetches the XML danmaku document of a video part.
+async fn fetch_xml(cid: i64) -> Result<String, String> {
+    let response = build_cdn_client()?
+        .get(format!("{XML_URL}/{cid}.xml"))
+        .header(header::REFERER, REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch danmaku XML: {e}"))?;
+    if !response.status().is_success() {
+        return Err(format!("Danmaku XML returned HTTP {}", response.status()));
+    }
+    response
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read danmaku XML: {e}"))
+}
+
+/// Fetches danmaku and writes the ASS track used by a merge.
+///
+/// The ASS file is a temporary `temp_sub_{download_id}_danmaku.ass` in the
+/// lib directory, so it is removed together with the other subtitle files.
+///
+/// # Arguments
+///
+/// * `cookie_header` - Login cookies of the download
+/// * `cid` - Content ID of the video part
+/// * `duration_seconds` - Part duration (comments after the end are dropped)
+/// * `download_id` - Download ID, used for the temp file name
+/// * `lib_path` - Lib directory for temporary files
+/// * `options` - ASS rendering options
+///
+/// # Errors
+///
+/// Returns an error if the comments cannot be fetched or the ASS file
+/// cannot be written.
+pub async fn prepare_danmaku_track(
+    cookie_header: Option<&str>,
+    cid: i64,
+    duration_seconds: i64,
+    download_id: &str,
+    lib_path: &Path,
+    options: &DanmakuOptions,
+) -> Result<DanmakuTrack, String> {
+    let client = build_client()?;
+    let comments = fetch_danmaku(&client, cookie_header, cid, duration_seconds).await?;
+    let ass_path = lib_path.join(format!("temp_sub_{download_id}_danmaku.ass"));
+    let ass = danmaku::danmaku_to_ass(&comments, options, Some(duration_seconds as f64));
+    tokio::fs::write(&ass_path, ass)
+        .await
+        .map_err(|e| format!("Failed to write danmaku ASS: {e}"))?;
+
+    Ok(DanmakuTrack {
+        subtitle: SubtitleMergeOptions {
+            path: ass_path,
+            language: "und".to_string(),
+            title: "Danmaku".to_string(),
+        },
+        raw_xml: danmaku::to_xml(cid, &comments),
+        count: comments.len(),
+    })
+}
+
+/// Returns the raw danmaku path stored next to a downloaded video.
+pub fn raw_path_for(output_path: &Path) -> PathBuf {
+    output_path.with_extension("danmaku.xml")
+}
+
+/// Returns the danmaku ASS path stored next to a downloaded video.
+pub fn ass_path_for(output_path: &Path) -> PathBuf {
+    output_path.with_extension("danmaku.ass")
+}
+
+/// Downloads the danmaku of a video part as standalone files.
+///
+/// Writes `{filename}.danmaku.xml` (raw comments) and
+/// `{filename}.danmaku.ass` into the download directory.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for settings and cookie access
+/// * `cid` - Content ID of the video part
+/// * `duration_seconds` - Part duration
+/// * `filename` - Base filename (an `.mp4` extension is ignored)
+/// * `options` - ASS rendering options (defaults when `None`)
+///
+/// # Returns
+///
+/// Paths of the written files and the number of comments.
+///
+/// # Errors
+///
+/// Returns an error if the download directory is not configured, the
+/// comments cannot be fetched, or a file cannot be written.
+pub async fn download_danmaku(
+    app: &AppHandle,
+    cid: i64,
+    duration_seconds: i64,
+    filename: &str,
+    options: Option<DanmakuOptions>,
+) -> Result<DanmakuFiles, String> {
+    log::info!("[BE] download_danmaku: cid={}, filename={}", cid, filename);
+    let options = options.unwrap_or_default();
+    let output_path = build_output_path(app, filename, "mp4").await?;
+    let cookie_header = build_cookie_header_from_cache(app).ok();
+    let client = build_client()?;
+    let comments = fetch_danmaku(&client, cookie_header.as_deref(), cid, duration_seconds).await?;
+    create_output_dir(&output_path).await?;
+
+    let xml_path = raw_path_for(&output_path);
+    let ass_path = ass_path_for(&output_path);
+    tokio::fs::write(&xml_path, danmaku::to_xml(cid, &comments))
+        .await
+        .map_err(|e| format!("Failed to write danmaku XML: {e}"))?;
+    let ass = danmaku::danmaku_to_ass(&comments, &options, Some(duration_seconds as f64));
+    tokio::fs::write(&ass_path, ass)
+        .await
+        .map_err(|e| format!("Failed to write danmaku ASS: {e}"))?;
+
+    log::info!(
+        "[BE] download_danmaku: wrote {} comment(s) for cid={}",
+        comments.len(),
+        cid
+    );
+    Ok(DanmakuFiles {
+        xml_path: xml_path.to_string_lossy().to_string(),
+        ass_path: ass_path.to_string_lossy().to_string(),
+        count: comments.len(),
+    })
+}
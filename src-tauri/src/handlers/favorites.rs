@@ -6,23 +6,53 @@
 //!
 //! - **Folder List Retrieval**: Fetches all favorite folders for a user
 //! - **Folder Contents Retrieval**: Fetches videos within a specific folder
+//! - **Incremental Sync**: Walks a whole folder, skips parts already in the
+//!   download history or queue, enqueues the rest and records a per-folder
+//!   sync cursor in `favorites_sync.json`
 //!
 //! ## API Endpoints
 //!
 //! - Folder list: `GET https://api.bilibili.com/x/v3/fav/folder/created/list-all`
 //! - Folder contents: `GET https://api.bilibili.com/x/v3/fav/resource/list`
 
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use chrono::Utc;
 use reqwest::header;
-use serde_json;
+use reqwest::Client;
+use serde::Serialize;
+use serde_json::{self, json};
 use tauri::AppHandle;
+use tauri_plugin_store::StoreExt;
 
 use crate::constants::REFERER;
-use crate::handlers::bilibili::{build_client, build_cookie_header_from_cache};
-use crate::models::bilibili_api::{FavoriteFolderListApiResponse, FavoriteResourceListApiResponse};
+use crate::handlers::bilibili::{self, build_client, build_cookie_header_from_cache};
+use crate::handlers::queue::{self, QueueJob, QueueJobStatus};
+use crate::models::bilibili_api::{
+    FavoriteFolderListApiResponse, FavoriteResourceListApiResponse,
+    FavoriteResourceListApiResponseData, FavoriteResourceMedia,
+};
 use crate::models::frontend_dto::{
-    FavoriteFolder, FavoriteFolderUpperDto, FavoriteVideo, FavoriteVideoListResponse,
-    FavoriteVideoUpperDto,
+    FavoriteFolder, FavoriteFolderUpperDto, FavoriteSyncCursor, FavoriteVideo,
+    FavoriteVideoListResponse, FavoriteVideoUpperDto,
 };
+use crate::models::history::HistoryEntry;
+use crate::store::HistoryStore;
+use crate::utils::download_archive::ArchiveMode;
+
+const SYNC_STORE_FILE: &str = "favorites_sync.json";
+const CURSORS_KEY: &str = "cursors";
+
+/// Largest page size accepted by the resource list API.
+const SYNC_PAGE_SIZE: i32 = 20;
+
+/// Delay between page requests while walking a whole folder.
+const PAGE_DELAY: Duration = Duration::from_millis(300);
+
+/// Resource type of a regular video in a favorite folder (12 is audio,
+/// 21 is a collection).
+const MEDIA_TYPE_VIDEO: i32 = 2;
 
 /// Fetches all favorite folders for the logged-in user.
 ///
@@ -187,33 +217,7 @@ pub async fn fetch_favorite_videos(
     let cookie_header = build_cookie_header_from_cache(app)?;
 
     let client = build_client()?;
-    let url = format!(
-        "https://api.bilibili.com/x/v3/fav/resource/list?media_id={}&pn={}&ps={}&order=mtime&type=0&platform=web",
-        media_id, page_num, page_size
-    );
-
-    let response = client
-        .get(&url)
-        .header(header::COOKIE, &cookie_header)
-        .header(header::REFERER, REFERER)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to fetch favorite videos: {e}"))?
-        .json::<FavoriteResourceListApiResponse>()
-        .await
-        .map_err(|e| format!("Failed to parse favorite videos response: {e}"))?;
-
-    if response.code == -101 {
-        return Err("ERR::UNAUTHORIZED".into());
-    }
-    if response.code != 0 {
-        return Err(format!(
-            "API error (code {}): {}",
-            response.code, response.message
-        ));
-    }
-
-    let data = response.data.ok_or("No data in response")?;
+    let data = fetch_resource_page(&client, &cookie_header, media_id, page_num, page_size).await?;
     let total_count = data.info.media_count;
 
     // Convert API response to frontend DTO
@@ -249,3 +253,324 @@ pub async fn fetch_favorite_videos(
         total_count,
     })
 }
+
+/// Outcome of a favorites folder sync.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteSyncReport {
+    /// Jobs added to the download queue (one per new video part)
+    pub jobs: Vec<QueueJob>,
+    /// Number of videos listed in the folder
+    pub scanned: usize,
+    /// Videos skipped because every part is already downloaded or queued
+    pub already_downloaded: usize,
+    /// Videos skipped because they were deleted or are not regular videos
+    pub unavailable: usize,
+    /// Cursor recorded for this run
+    pub cursor: FavoriteSyncCursor,
+}
+
+/// Syncs a favorite folder into the download queue.
+///
+/// Walks every page of the folder and compares it against the download
+/// history and the unfinished queue jobs by bvid/cid. Only parts that are
+/// in neither are enqueued, so running the sync repeatedly (e.g. nightly)
+/// downloads each new favorite once. History entries recorded before cids
+/// were tracked cover every part of their video.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `media_id` - Favorite folder ID
+///
+/// # Returns
+///
+/// A [`FavoriteSyncReport`] with the queued jobs and skip counts. The cursor
+/// is also persisted and can be read back with [`get_sync_cursors`].
+///
+/// # Errors
+///
+/// Returns an error if the folder listing fails (see
+/// [`fetch_favorite_videos`]). Per-video failures do not abort the sync.
+pub async fn sync_favorite_folder(
+    app: &AppHandle,
+    media_id: i64,
+) -> Result<FavoriteSyncReport, String> {
+    log::info!("[BE] sync_favorite_folder: media_id={}", media_id);
+    let cookie_header = build_cookie_header_from_cache(app)?;
+    let client = build_client()?;
+
+    let mut medias: Vec<FavoriteResourceMedia> = Vec::new();
+    let mut total_count = 0i64;
+    let mut page_num = 1;
+    loop {
+        let data = fetch_resource_page(&client, &cookie_header, media_id, page_num, SYNC_PAGE_SIZE)
+            .await?;
+        total_count = total_count.max(data.info.media_count);
+        let page = data.medias.unwrap_or_default();
+        if page.is_empty() {
+            break;
+        }
+        medias.extend(page);
+        if !data.has_more {
+            break;
+        }
+        page_num += 1;
+        tokio::time::sleep(PAGE_DELAY).await;
+    }
+
+    let history = HistoryStore::run_blocking(app, |store| Ok(store.get_all())).await?;
+    let mut index = DownloadedIndex::from_history(&history);
+    index.add_queue_jobs(&queue::get_jobs().await);
+
+    let mut already_downloaded = 0;
+    let mut unavailable = 0;
+    let mut options = Vec::new();
+    for media in &medias {
+        if !is_downloadable(media) {
+            unavailable += 1;
+            continue;
+        }
+        if index.covers_video(&media.bvid, media.page) {
+            already_downloaded += 1;
+            continue;
+        }
+        match bilibili::fetch_video_info(app, &media.bvid).await {
+            Ok(video) => {
+                let new_parts: Vec<_> = queue::part_download_options(&video)
+                    .into_iter()
+                    .filter(|opts| !index.covers_part(&opts.bvid, opts.cid))
+                    .collect();
+                if new_parts.is_empty() {
+                    already_downloaded += 1;
+                }
+                options.extend(new_parts);
+            }
+            Err(e) => log::warn!(
+                "[BE] sync_favorite_folder: skipping bvid={}: {}",
+                media.bvid,
+                e
+            ),
+        }
+    }
+    let jobs = queue::enqueue_many(app, options, ArchiveMode::Skip).await;
+
+    let cursor = FavoriteSyncCursor {
+        media_id,
+        last_synced_at: Utc::now().to_rfc3339(),
+        last_fav_time: medias.iter().map(|m| m.fav_time).max().unwrap_or(0),
+        total_count,
+        last_enqueued: jobs.len(),
+    };
+    if let Err(e) = save_sync_cursor(app, &cursor) {
+        log::warn!("[BE] sync_favorite_folder: failed to save cursor: {}", e);
+    }
+    log::info!(
+        "[BE] sync_favorite_folder: scanned={}, enqueued={}, already_downloaded={}, unavailable={}",
+        medias.len(),
+        jobs.len(),
+        already_downloaded,
+        unavailable
+    );
+
+    Ok(FavoriteSyncReport {
+        jobs,
+        scanned: medias.len(),
+        already_downloaded,
+        unavailable,
+        cursor,
+    })
+}
+
+/// Returns the recorded sync cursor of every synced folder.
+pub fn get_sync_cursors(app: &AppHandle) -> Vec<FavoriteSyncCursor> {
+    let mut cursors: Vec<_> = load_sync_cursors(app).into_values().collect();
+    cursors.sort_by_key(|c| c.media_id);
+    cursors
+}
+
+/// Requests one page of a favorite folder.
+async fn fetch_resource_page(
+    client: &Client,
+    cookie_header: &str,
+    media_id: i64,
+    page_num: i32,
+    page_size: i32,
+) -> Result<FavoriteResourceListApiResponseData, String> {
+    let url = format!(
+        "https://api.bilibili.com/x/v3/fav/resource/list?media_id={}&pn={}&ps={}&order=mtime&type=0&platform=web",
+        media_id, page_num, page_size
+    );
+
+    let response = client
+        .get(&url)
+        .header(header::COOKIE, cookie_header)
+        .header(header::REFERER, REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch favorite videos: {e}"))?
+        .json::<FavoriteResourceListApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse favorite videos response: {e}"))?;
+
+    if response.code == -101 {
+        return Err("ERR::UNAUTHORIZED".into());
+    }
+    if response.code != 0 {
+        return Err(format!(
+            "API error (code {}): {}",
+            response.code, response.message
+        ));
+    }
+
+    response
+        .data
+        .ok_or_else(|| "No data in response".to_string())
+}
+
+/// Returns `true` for regular videos that still exist.
+///
+/// Bit 0 of `attr` marks a favorite whose video was deleted (`attr` 1 or 9).
+fn is_downloadable(media: &FavoriteResourceMedia) -> bool {
+    media.media_type == MEDIA_TYPE_VIDEO && media.attr & 1 == 0
+}
+
+/// Parts that are already downloaded or waiting in the queue.
+#[derive(Debug, Default)]
+struct DownloadedIndex {
+    /// Videos recorded without a cid, treated as fully downloaded
+    whole_videos: HashSet<String>,
+    /// Known parts grouped by bvid
+    parts: HashMap<String, HashSet<i64>>,
+}
+
+impl DownloadedIndex {
+    /// Indexes the completed entries of the download history.
+    fn from_history(entries: &[HistoryEntry]) -> Self {
+        let mut index = Self::default();
+        for entry in entries.iter().filter(|e| e.status == "completed") {
+            let Some(bvid) = entry.bvid.as_deref() else {
+                continue;
+            };
+            match entry.cid {
+                Some(cid) => index.add_part(bvid, cid),
+                None => {
+                    index.whole_videos.insert(bvid.to_string());
+                }
+            }
+        }
+        index
+    }
+
+    /// Adds queue jobs that are still going to produce a file.
+    fn add_queue_jobs(&mut self, jobs: &[QueueJob]) {
+        for job in jobs
+            .iter()
+            .filter(|j| !matches!(j.status, QueueJobStatus::Failed | QueueJobStatus::Cancelled))
+        {
+            self.add_part(&job.options.bvid, job.options.cid);
+        }
+    }
+
+    fn add_part(&mut self, bvid: &str, cid: i64) {
+        self.parts.entry(bvid.to_string()).or_default().insert(cid);
+    }
+
+    /// Returns `true` if all `part_count` parts of a video are known, so
+    /// the view lookup can be skipped.
+    fn covers_video(&self, bvid: &str, part_count: i32) -> bool {
+        self.whole_videos.contains(bvid)
+            || self
+                .parts
+                .get(bvid)
+                .is_some_and(|cids| cids.len() >= part_count.max(1) as usize)
+    }
+
+    fn covers_part(&self, bvid: &str, cid: i64) -> bool {
+        self.whole_videos.contains(bvid)
+            || self.parts.get(bvid).is_some_and(|cids| cids.contains(&cid))
+    }
+}
+
+/// Records the cursor of one folder in the sync store.
+fn save_sync_cursor(app: &AppHandle, cursor: &FavoriteSyncCursor) -> Result<(), String> {
+    let mut cursors = load_sync_cursors(app);
+    cursors.insert(cursor.media_id.to_string(), cursor.clone());
+    let store = app.store(SYNC_STORE_FILE).map_err(|e| e.to_string())?;
+    let value = serde_json::to_value(&cursors).map_err(|e| e.to_string())?;
+    store.set(CURSORS_KEY, value);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Reads all cursors keyed by folder ID, returning an empty map on any
+/// error.
+fn load_sync_cursors(app: &AppHandle) -> HashMap<String, FavoriteSyncCursor> {
+    let Ok(store) = app.store(SYNC_STORE_FILE) else {
+        return HashMap::new();
+    };
+    let value = store.get(CURSORS_KEY).unwrap_or(json!({}));
+    serde_json::from_value(value).unwrap_or_else(|e| {
+        log::warn!("[BE] favorites: discarding unreadable sync store: {}", e);
+        HashMap::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn history(bvid: &str, cid: Option<i64>, status: &str) -> HistoryEntry {
+        serde_json::from_value(json!({
+            "id": format!("{bvid}_1"),
+            "title": "t",
+            "bvid": bvid,
+            "cid": cid,
+            "url": format!("https://www.bilibili.com/video/{bvid}"),
+            "downloadedAt": "2026-01-01T00:00:00Z",
+            "status": status,
+            "fileSize": null,
+            "quality": null,
+            "thumbnailUrl": null
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn index_tracks_parts_by_cid() {
+        let index = DownloadedIndex::from_history(&[
+            history("BV1", Some(11), "completed"),
+            history("BV1", Some(12), "failed"),
+        ]);
+        assert!(index.covers_part("BV1", 11));
+        assert!(!index.covers_part("BV1", 12));
+        assert!(!index.covers_part("BV2", 11));
+        assert!(index.covers_video("BV1", 1));
+        assert!(!index.covers_video("BV1", 2));
+    }
+
+    #[test]
+    fn legacy_entry_without_cid_covers_whole_video() {
+        let index = DownloadedIndex::from_history(&[history("BV1", None, "completed")]);
+        assert!(index.covers_video("BV1", 3));
+        assert!(index.covers_part("BV1", 99));
+    }
+
+    #[test]
+    fn deleted_and_non_video_favorites_are_skipped() {
+        let media = |media_type: i32, attr: i32| -> FavoriteResourceMedia {
+            serde_json::from_value(json!({
+                "id": 1, "type": media_type, "title": "t", "cover": "", "intro": "",
+                "page": 1, "duration": 1,
+                "upper": {"mid": 1, "name": "u", "face": ""},
+                "attr": attr,
+                "cnt_info": {"collect": 0, "play": 0, "danmaku": 0},
+                "link": "", "ctime": 0, "pubtime": 0, "fav_time": 0,
+                "bv_id": "BV1", "bvid": "BV1"
+            }))
+            .unwrap()
+        };
+        assert!(is_downloadable(&media(2, 0)));
+        assert!(!is_downloadable(&media(2, 9)));
+        assert!(!is_downloadable(&media(12, 0)));
+    }
+}
@@ -6,7 +6,12 @@
 
 use crate::constants::FFMPEG_VALIDATION_TIMEOUT_SECS;
 use crate::emits::Emits;
+use crate::handlers::audio::{build_stream_args, AudioOnlyOptions};
+use crate::models::frontend_dto::VideoMetadata;
+use crate::models::settings::AudioFormat;
+use crate::utils::codec::{AudioStreamKind, DynamicRange, OutputContainer};
 use crate::utils::downloads::download_url;
+use crate::utils::metadata::ffmpeg_metadata_args;
 use crate::utils::paths::{get_ffmpeg_path, get_ffmpeg_root_path};
 use anyhow::Result;
 use std::fs::File;
@@ -56,6 +61,26 @@ pub enum MergeMode {
     HardSub(SubtitleMergeOptions),
 }
 
+/// Optional inputs and stream properties of a merge.
+///
+/// Passed to [`merge_avs`]; the default merges plain AAC audio and SDR
+/// video into MP4 without tags, cover or chapters.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MergeOptions<'a> {
+    /// Descriptive metadata written as container tags
+    pub metadata: Option<&'a VideoMetadata>,
+    /// Cover image embedded as an `attached_pic` stream
+    pub cover_path: Option<&'a Path>,
+    /// ffmetadata file whose chapters are embedded
+    pub chapters_path: Option<&'a Path>,
+    /// Output container; the output path must use its extension
+    pub container: OutputContainer,
+    /// Codec family of the audio stream; FLAC requires an `.mkv` output
+    pub audio_kind: AudioStreamKind,
+    /// Dynamic range of the video stream
+    pub dynamic_range: DynamicRange,
+}
+
 /// Validates whether ffmpeg is properly installed and functional.
 ///
 /// This function checks if:
@@ -538,11 +563,12 @@ pub async fn merge_av(
     merge_avs(
         app,
         video_path,
-        audio_path,
+        Some(audio_path),
         output_path,
         download_id,
         duration_ms,
         MergeMode::None,
+        MergeOptions::default(),
         None,
     )
     .await
@@ -574,92 +600,84 @@ impl AudioCodec {
 /// The audio codec is parameterized so a single builder serves both the
 /// stream-copy attempt and the AAC re-encode fallback — only the `-c:a` value
 /// differs; everything else (inputs, mappings, metadata, video codec) is
-/// identical for a given [`MergeMode`].
+/// identical for a given [`MergeMode`]. From `options`, container tags are
+/// written in every mode, the cover is embedded (copied, never re-encoded),
+/// and the chapters of the ffmetadata file are copied with `-map_chapters`.
+///
+/// The output container (forced to Matroska for FLAC audio) selects the
+/// container-specific arguments: see [`mp4_output_args`] for MP4/MOV and
+/// [`matroska_output_args`] for MKV.
 fn build_merge_args(
     video_path: &str,
-    audio_path: &str,
+    audio_path: Option<&str>,
     output_path: &str,
     subtitle_mode: &MergeMode,
     audio_codec: AudioCodec,
+    options: &MergeOptions,
 ) -> Result<Vec<String>, String> {
     let to_str_err = || "Invalid path".to_string();
+    let cover_path = options
+        .cover_path
+        .map(|p| p.to_str().ok_or_else(to_str_err))
+        .transpose()?;
+    let chapters_path = options
+        .chapters_path
+        .map(|p| p.to_str().ok_or_else(to_str_err))
+        .transpose()?;
+    let container = options.container.for_audio(options.audio_kind);
     let audio = audio_codec.as_str();
+    let to_strings = |args: &[&str]| args.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+    // Matroska stores the cover as an attachment, the others as a stream.
+    let cover_stream = cover_path.filter(|_| !container.is_matroska());
 
-    let mut args = Vec::new();
+    let mut input_args = to_strings(&["-i", video_path]);
+    if let Some(audio) = audio_path {
+        input_args.extend(to_strings(&["-i", audio]));
+    }
+    // Without an audio input (video-only downloads) no audio is mapped or
+    // encoded.
+    let audio_args = if audio_path.is_some() {
+        to_strings(&["-c:a", audio])
+    } else {
+        Vec::new()
+    };
+    let main_maps = if audio_path.is_some() {
+        to_strings(&["-map", "0:v", "-map", "1:a"])
+    } else {
+        to_strings(&["-map", "0:v"])
+    };
+    let mut map_args: Vec<String> = Vec::new();
+    let mut output_args: Vec<String> = Vec::new();
 
     match subtitle_mode {
         MergeMode::None => {
-            args.extend(
-                [
-                    "-i",
-                    video_path,
-                    "-i",
-                    audio_path,
-                    "-c:v",
-                    "copy",
-                    "-c:a",
-                    audio,
-                    "-progress",
-                    "pipe:1",
-                    "-y",
-                    output_path,
-                ]
-                .iter()
-                .map(|s| s.to_string()),
-            );
+            output_args.extend(to_strings(&["-c:v", "copy"]));
+            output_args.extend(audio_args);
         }
         MergeMode::SoftSub(subtitles) => {
-            let mut input_args: Vec<String> = vec![
-                "-i".to_string(),
-                video_path.to_string(),
-                "-i".to_string(),
-                audio_path.to_string(),
-            ];
-
+            let first_subtitle_input = input_args.len() / 2;
             for sub in subtitles {
                 let sub_str = sub.path.to_str().ok_or_else(to_str_err)?;
                 input_args.push("-i".to_string());
                 input_args.push(sub_str.to_string());
             }
 
-            let mut map_args: Vec<String> = vec![
-                "-map".to_string(),
-                "0:v".to_string(),
-                "-map".to_string(),
-                "1:a".to_string(),
-            ];
-
+            map_args.extend(main_maps.clone());
             for i in 0..subtitles.len() {
                 map_args.push("-map".to_string());
-                map_args.push(format!("{}:0", i + 2));
+                map_args.push(format!("{}:0", i + first_subtitle_input));
             }
 
-            let mut metadata_args: Vec<String> = Vec::new();
             for (i, sub) in subtitles.iter().enumerate() {
-                metadata_args.push(format!("-metadata:s:s:{}", i));
-                metadata_args.push(format!("language={}", sub.language));
-                metadata_args.push(format!("-metadata:s:s:{}", i));
-                metadata_args.push(format!("title={}", sub.title));
+                output_args.push(format!("-metadata:s:s:{}", i));
+                output_args.push(format!("language={}", sub.language));
+                output_args.push(format!("-metadata:s:s:{}", i));
+                output_args.push(format!("title={}", sub.title));
             }
 
-            let codec_args: Vec<String> = vec![
-                "-c:v".to_string(),
-                "copy".to_string(),
-                "-c:a".to_string(),
-                audio.to_string(),
-                "-c:s".to_string(),
-                "mov_text".to_string(),
-            ];
-
-            args.extend(input_args);
-            args.extend(map_args);
-            args.extend(metadata_args);
-            args.extend(codec_args);
-            args.extend(
-                ["-progress", "pipe:1", "-y", output_path]
-                    .iter()
-                    .map(|s| s.to_string()),
-            );
+            output_args.extend(to_strings(&["-c:v", "copy"]));
+            output_args.extend(audio_args);
+            output_args.extend(to_strings(&["-c:s", subtitle_codec(container)]));
         }
         MergeMode::HardSub(subtitle) => {
             let sub_str = subtitle.path.to_str().ok_or_else(to_str_err)?;
@@ -670,44 +688,152 @@ fn build_merge_args(
                 .replace('\'', "'\\''");
 
             let filter = format!("subtitles='{}'", escaped_sub);
+            // Why: `-vf` would also apply to the stream-copied cover, which
+            //   ffmpeg rejects; restrict the filter to the main video.
+            let filter_flag = if cover_stream.is_some() {
+                "-filter:v:0"
+            } else {
+                "-vf"
+            };
 
-            args.extend(
-                [
-                    "-i",
-                    video_path,
-                    "-i",
-                    audio_path,
-                    "-vf",
-                    &filter,
-                    "-c:v",
-                    "libx264",
-                    "-preset",
-                    "fast",
-                    "-c:a",
-                    audio,
-                    "-progress",
-                    "pipe:1",
-                    "-y",
-                    output_path,
-                ]
-                .iter()
-                .map(|s| s.to_string()),
-            );
+            output_args.extend(to_strings(&[
+                filter_flag,
+                &filter,
+                "-c:v",
+                "libx264",
+                "-preset",
+                "fast",
+            ]));
+            output_args.extend(audio_args);
         }
     }
 
+    // The video is stream-copied unless subtitles are burned in.
+    let video_copied = !matches!(subtitle_mode, MergeMode::HardSub(_));
+    let audio_copied = audio_path.is_some() && matches!(audio_codec, AudioCodec::Copy);
+    output_args.extend(match container {
+        OutputContainer::Mp4 | OutputContainer::Mov => {
+            mp4_output_args(options, audio_copied, video_copied)
+        }
+        OutputContainer::Mkv => matroska_output_args(options, video_copied, cover_path),
+    });
+
+    if let Some(cover) = cover_stream {
+        // Explicit maps keep ffmpeg from picking the cover as main video.
+        if map_args.is_empty() {
+            map_args.extend(main_maps);
+        }
+        map_args.push("-map".to_string());
+        map_args.push(format!("{}:v", input_args.len() / 2));
+        input_args.extend(to_strings(&["-i", cover]));
+        output_args.extend(to_strings(&[
+            "-c:v:1",
+            "copy",
+            "-disposition:v:1",
+            "attached_pic",
+        ]));
+    }
+
+    if let Some(chapters) = chapters_path {
+        // The ffmetadata input has no streams, so it needs no `-map`.
+        output_args.push("-map_chapters".to_string());
+        output_args.push((input_args.len() / 2).to_string());
+        input_args.extend(to_strings(&["-i", chapters]));
+    }
+
+    let mut args = input_args;
+    args.extend(map_args);
+    args.extend(output_args);
+    if let Some(metadata) = options.metadata {
+        args.extend(ffmpeg_metadata_args(metadata));
+    }
+    args.extend(to_strings(&["-progress", "pipe:1", "-y", output_path]));
+
     Ok(args)
 }
 
-/// Merges video, audio, and optional subtitles into a single MP4 file.
+/// Returns the codec of soft subtitle tracks in a container.
 ///
-/// This is an extended version of [`merge_av`] that supports subtitle handling
+/// Matroska copies SRT and ASS tracks as-is, so danmaku and styled ASS
+/// subtitles keep their positioning and fonts; MP4 and MOV only support
+/// `mov_text`.
+fn subtitle_codec(container: OutputContainer) -> &'static str {
+    match container {
+        OutputContainer::Mkv => "copy",
+        OutputContainer::Mp4 | OutputContainer::Mov => "mov_text",
+    }
+}
+
+/// Builds the MP4/MOV specific merge arguments.
+///
+/// Copied Dolby audio is tagged `ec-3`. For stream-copied HDR video the
+/// colour description is written (`+write_colr`), and Dolby Vision keeps
+/// its configuration record (`-strict unofficial`) and the `dvh1` sample
+/// entry. The cover is added by the caller as an `attached_pic` stream.
+fn mp4_output_args(options: &MergeOptions, audio_copied: bool, video_copied: bool) -> Vec<String> {
+    let mut args: Vec<&str> = Vec::new();
+    if audio_copied && options.audio_kind == AudioStreamKind::Dolby {
+        args.extend(["-tag:a", "ec-3"]);
+    }
+    if video_copied {
+        if options.dynamic_range.is_hdr() {
+            args.extend(["-movflags", "+write_colr"]);
+        }
+        if options.dynamic_range == DynamicRange::DolbyVision {
+            // Why: ffmpeg only writes the Dolby Vision configuration record
+            //   (`dvcC`/`dvvC`) in unofficial mode; without it players
+            //   decode the base layer with the wrong colours.
+            args.extend(["-strict", "unofficial", "-tag:v:0", "dvh1"]);
+        }
+    }
+    args.into_iter().map(String::from).collect()
+}
+
+/// Builds the Matroska specific merge arguments.
+///
+/// Dolby Vision keeps its configuration record (block addition mapping,
+/// written only with `-strict unofficial`), and the cover is stored as a
+/// `cover.{ext}` attachment, which is how Matroska players look up artwork.
+fn matroska_output_args(
+    options: &MergeOptions,
+    video_copied: bool,
+    cover_path: Option<&str>,
+) -> Vec<String> {
+    let mut args: Vec<String> = Vec::new();
+    if video_copied && options.dynamic_range == DynamicRange::DolbyVision {
+        args.extend(["-strict".to_string(), "unofficial".to_string()]);
+    }
+    if let Some(cover) = cover_path {
+        let extension = Path::new(cover)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("jpg");
+        args.extend([
+            "-attach".to_string(),
+            cover.to_string(),
+            "-metadata:s:t".to_string(),
+            format!(
+                "mimetype={}",
+                crate::handlers::cover::mime_type_for(extension)
+            ),
+            "-metadata:s:t".to_string(),
+            format!("filename=cover.{extension}"),
+        ]);
+    }
+    args
+}
+
+/// Merges video, audio, and optional subtitles into a single file.
+///
+/// The container (MP4 by default, MKV or MOV) is set by
+/// [`MergeOptions::container`]. This is an extended version of [`merge_av`] that supports subtitle handling
 /// based on the specified [`MergeMode`]:
 ///
 /// - **None**: Merges video and audio only (identical to [`merge_av`]).
 ///   Video stream is copied without re-encoding; audio is first tried with
 ///   stream copy, falling back to AAC re-encoding if needed.
-/// - **SoftSub**: Embeds subtitle tracks into the container (`mov_text` codec).
+/// - **SoftSub**: Embeds subtitle tracks into the container (`mov_text` codec;
+///   copied as-is into MKV).
 ///   Viewers can toggle subtitles on/off during playback. Supports multiple tracks.
 ///   Audio uses copy-first with AAC fallback.
 /// - **HardSub**: Burns subtitles into the video frame using the `subtitles` filter.
@@ -720,11 +846,16 @@ fn build_merge_args(
 ///
 /// * `app` - Tauri application handle for event emission
 /// * `video_path` - Path to the video file (.m4s)
-/// * `audio_path` - Path to the audio file (.m4s)
-/// * `output_path` - Path for the merged output file (.mp4)
+/// * `audio_path` - Path to the audio file (.m4s); `None` writes a silent
+///   video-only file
+/// * `output_path` - Path for the merged output file (.mp4, .mkv or .mov)
 /// * `download_id` - Optional download ID for progress tracking
 /// * `duration_ms` - Optional video duration in milliseconds for accurate progress
-/// * `subtitle_mode` - Controls how subtitles are handled during the merge
+/// * `subtitle_mode` - Controls how subtitles are handled during the merge.
+///   Burned-in subtitles are embedded as a soft track instead for HDR
+///   video, since re-encoding to H.264 would wash the picture out.
+/// * `options` - Metadata, cover, chapters and stream properties
+/// * `cancel_token` - Optional token that aborts ffmpeg when cancelled
 ///
 /// # Returns
 ///
@@ -741,13 +872,21 @@ fn build_merge_args(
 pub async fn merge_avs(
     app: &AppHandle,
     video_path: &std::path::Path,
-    audio_path: &std::path::Path,
+    audio_path: Option<&std::path::Path>,
     output_path: &std::path::Path,
     download_id: Option<String>,
     duration_ms: Option<u64>,
     subtitle_mode: MergeMode,
+    options: MergeOptions<'_>,
     cancel_token: Option<CancellationToken>,
 ) -> Result<(), String> {
+    let subtitle_mode = match subtitle_mode {
+        MergeMode::HardSub(subtitle) if options.dynamic_range.is_hdr() => {
+            log::warn!("[BE] merge_avs: HDR video, embedding the subtitle as a soft track");
+            MergeMode::SoftSub(vec![subtitle])
+        }
+        mode => mode,
+    };
     log::info!(
         "[BE] merge_avs: starting merge download_id={:?}, output={:?}, subtitle_mode={:?}",
         download_id,
@@ -770,7 +909,9 @@ pub async fn merge_avs(
 
     let to_str_err = || "Invalid path".to_string();
     let video_str = video_path.to_str().ok_or_else(to_str_err)?;
-    let audio_str = audio_path.to_str().ok_or_else(to_str_err)?;
+    let audio_str = audio_path
+        .map(|p| p.to_str().ok_or_else(to_str_err))
+        .transpose()?;
     let output_str = output_path.to_str().ok_or_else(to_str_err)?;
 
     // Try audio stream copy first; fall back to AAC re-encoding on failure.
@@ -780,6 +921,7 @@ pub async fn merge_avs(
         output_str,
         &subtitle_mode,
         AudioCodec::Copy,
+        &options,
     )?;
     let copy_result = run_merge_ffmpeg(
         &ffmpeg_path,
@@ -800,10 +942,13 @@ pub async fn merge_avs(
 
     // Cancellation must propagate immediately — it is a user action, not a
     // transcode failure, and must NOT trigger the AAC re-encode fallback.
-    if matches!(
-        copy_result.as_ref().err().map(String::as_str),
-        Some("ERR::CANCELLED")
-    ) {
+    // Without audio there is nothing to re-encode either.
+    if audio_path.is_none()
+        || matches!(
+            copy_result.as_ref().err().map(String::as_str),
+            Some("ERR::CANCELLED")
+        )
+    {
         return Err(copy_result.unwrap_err());
     }
 
@@ -820,6 +965,7 @@ pub async fn merge_avs(
         output_str,
         &subtitle_mode,
         AudioCodec::Aac,
+        &options,
     )?;
     match run_merge_ffmpeg(
         &ffmpeg_path,
@@ -841,6 +987,111 @@ pub async fn merge_avs(
     }
 }
 
+/// Converts a downloaded DASH audio stream to the audio-only output format.
+///
+/// AAC streams going to `.m4a` are remuxed first and re-encoded only if the
+/// copy fails; MP3 output and Dolby/Hi-Res streams are always transcoded.
+/// Progress is reported through the merge stage, like [`merge_avs`].
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for event emission
+/// * `audio_path` - Path to the audio stream (.m4s)
+/// * `output_path` - Path of the `.mp3`/`.m4a` output file
+/// * `download_id` - Download ID for progress tracking
+/// * `duration_ms` - Optional duration in milliseconds for accurate progress
+/// * `audio_kind` - Codec family of the downloaded stream
+/// * `options` - Target format and bitrate
+/// * `metadata` - Descriptive metadata written as tags
+/// * `cover_path` - Cover image embedded as the attached picture
+/// * `cancel_token` - Optional token that aborts ffmpeg when cancelled
+///
+/// # Errors
+///
+/// Returns an error if a path is not valid UTF-8, ffmpeg fails, or the
+/// conversion is cancelled (`ERR::CANCELLED`).
+#[allow(clippy::too_many_arguments)]
+pub async fn convert_audio_stream(
+    app: &AppHandle,
+    audio_path: &Path,
+    output_path: &Path,
+    download_id: &str,
+    duration_ms: Option<u64>,
+    audio_kind: AudioStreamKind,
+    options: &AudioOnlyOptions,
+    metadata: Option<&VideoMetadata>,
+    cover_path: Option<&Path>,
+    cancel_token: Option<CancellationToken>,
+) -> Result<(), String> {
+    log::info!(
+        "[BE] convert_audio_stream: download_id={}, output={:?}, format={:?}, kind={:?}",
+        download_id,
+        output_path,
+        options.format,
+        audio_kind
+    );
+    let emits = Emits::new(
+        app.clone(),
+        download_id.to_string(),
+        Some(100 * 1024 * 1024),
+    );
+    let _ = emits.set_stage("merge").await;
+
+    let ffmpeg_path = get_ffmpeg_path(app);
+    let to_str_err = || "Invalid path".to_string();
+    let audio_str = audio_path.to_str().ok_or_else(to_str_err)?;
+    let output_str = output_path.to_str().ok_or_else(to_str_err)?;
+    let cover_str = cover_path
+        .map(|p| p.to_str().ok_or_else(to_str_err))
+        .transpose()?;
+
+    let can_copy = options.format == AudioFormat::M4a && audio_kind == AudioStreamKind::Aac;
+    if can_copy {
+        let copy_args =
+            build_stream_args(audio_str, output_str, options, true, metadata, cover_str);
+        match run_merge_ffmpeg(
+            &ffmpeg_path,
+            &copy_args,
+            output_path,
+            duration_ms,
+            &emits,
+            &cancel_token,
+        )
+        .await
+        {
+            Ok(()) => {
+                log::info!("[BE] convert_audio_stream: audio copy succeeded");
+                let _ = emits.set_stage("complete").await;
+                emits.complete().await;
+                return Ok(());
+            }
+            Err(e) if e == "ERR::CANCELLED" => return Err(e),
+            Err(e) => {
+                log::info!(
+                    "[BE] convert_audio_stream: audio copy failed, re-encoding: {}",
+                    e
+                );
+                emits.update_progress(0);
+                let _ = emits.set_stage("merge-fallback").await;
+            }
+        }
+    }
+
+    let args = build_stream_args(audio_str, output_str, options, false, metadata, cover_str);
+    run_merge_ffmpeg(
+        &ffmpeg_path,
+        &args,
+        output_path,
+        duration_ms,
+        &emits,
+        &cancel_token,
+    )
+    .await?;
+    let _ = emits.set_stage("complete").await;
+    emits.complete().await;
+    Ok(())
+}
+
 /// Runs ffmpeg with the given args for merge operations.
 ///
 /// Handles progress parsing, cancellation, and stderr collection.
@@ -950,6 +1201,74 @@ async fn run_merge_ffmpeg(
     Ok(())
 }
 
+/// Remuxes a single media file into MP4 without re-encoding.
+///
+/// Used for live recording chunks (FLV, MPEG-TS or fragmented MP4).
+/// `-bsf:a aac_adtstoasc` converts ADTS audio from TS input; other inputs
+/// pass through unchanged. `+faststart` moves the index to the front so the
+/// file can be previewed before it is fully read.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for locating the ffmpeg binary
+/// * `input_path` - Source media file
+/// * `output_path` - Destination `.mp4` file (overwritten if present)
+///
+/// # Errors
+///
+/// Returns an error if ffmpeg cannot be spawned or exits with a failure.
+pub async fn remux_to_mp4(
+    app: &AppHandle,
+    input_path: &Path,
+    output_path: &Path,
+) -> Result<(), String> {
+    log::info!(
+        "[BE] remux_to_mp4: input={:?}, output={:?}",
+        input_path,
+        output_path
+    );
+    let to_str_err = || "Invalid path".to_string();
+    let input_str = input_path.to_str().ok_or_else(to_str_err)?;
+    let output_str = output_path.to_str().ok_or_else(to_str_err)?;
+
+    let mut cmd = AsyncCommand::new(get_ffmpeg_path(app));
+    cmd.args([
+        "-i",
+        input_str,
+        "-c",
+        "copy",
+        "-bsf:a",
+        "aac_adtstoasc",
+        "-movflags",
+        "+faststart",
+        "-y",
+        output_str,
+    ]);
+
+    #[cfg(target_os = "windows")]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .output()
+        .await
+        .map_err(|e| format!("Failed to spawn ffmpeg: {e}"))?;
+
+    if !output.status.success() {
+        let _ = tokio::fs::remove_file(output_path).await;
+        return Err(format!(
+            "ffmpeg failed to remux.\nExit code: {:?}\nstderr: {}",
+            output.status.code(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -970,14 +1289,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn video_only_merge_maps_no_audio() {
+        let subtitle = SubtitleMergeOptions {
+            path: PathBuf::from("sub.srt"),
+            language: "en".to_string(),
+            title: "English".to_string(),
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            None,
+            "out.mp4",
+            &MergeMode::SoftSub(vec![subtitle]),
+            AudioCodec::Copy,
+            &MergeOptions::default(),
+        )
+        .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-i v.m4s -i sub.srt -map 0:v -map 1:0"));
+        assert!(!args.contains(&"-c:a".to_string()));
+    }
+
     #[test]
     fn none_mode_copy_uses_audio_copy() {
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::None,
             AudioCodec::Copy,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "copy");
@@ -987,10 +1328,11 @@ mod tests {
     fn none_mode_aac_uses_audio_aac() {
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::None,
             AudioCodec::Aac,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "aac");
@@ -1005,10 +1347,11 @@ mod tests {
         }];
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::SoftSub(subtitles),
             AudioCodec::Copy,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "copy");
@@ -1026,10 +1369,11 @@ mod tests {
         }];
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::SoftSub(subtitles),
             AudioCodec::Aac,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "aac");
@@ -1044,10 +1388,11 @@ mod tests {
         };
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::HardSub(subtitle),
             AudioCodec::Copy,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "copy");
@@ -1065,12 +1410,261 @@ mod tests {
         };
         let args = build_merge_args(
             "v.m4s",
-            "a.m4s",
+            Some("a.m4s"),
             "out.mp4",
             &MergeMode::HardSub(subtitle),
             AudioCodec::Aac,
+            &MergeOptions::default(),
         )
         .unwrap();
         assert_audio_codec_pair(&args, "aac");
     }
+
+    #[test]
+    fn metadata_tags_precede_the_output() {
+        let metadata = VideoMetadata {
+            title: "Title".to_string(),
+            ..Default::default()
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mp4",
+            &MergeMode::None,
+            AudioCodec::Copy,
+            &MergeOptions {
+                metadata: Some(&metadata),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let tag = args.iter().position(|a| a == "title=Title").unwrap();
+        assert_eq!(args[tag - 1], "-metadata");
+        assert!(tag < args.iter().position(|a| a == "out.mp4").unwrap());
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+    }
+
+    #[test]
+    fn cover_is_mapped_as_attached_pic() {
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mp4",
+            &MergeMode::None,
+            AudioCodec::Copy,
+            &MergeOptions {
+                cover_path: Some(Path::new("cover.jpg")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-i v.m4s -i a.m4s -i cover.jpg -map 0:v -map 1:a -map 2:v"));
+        assert!(joined.contains("-c:v:1 copy -disposition:v:1 attached_pic"));
+
+        let subtitle = SubtitleMergeOptions {
+            path: std::path::PathBuf::from("subtitle.srt"),
+            language: "eng".to_string(),
+            title: "English".to_string(),
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mp4",
+            &MergeMode::HardSub(subtitle),
+            AudioCodec::Copy,
+            &MergeOptions {
+                cover_path: Some(Path::new("cover.jpg")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(args.contains(&"-filter:v:0".to_string()));
+        assert!(!args.contains(&"-vf".to_string()));
+    }
+
+    #[test]
+    fn chapters_are_mapped_from_the_ffmetadata_input() {
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mp4",
+            &MergeMode::None,
+            AudioCodec::Copy,
+            &MergeOptions {
+                cover_path: Some(Path::new("cover.jpg")),
+                chapters_path: Some(Path::new("chapters.txt")),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.starts_with("-i v.m4s -i a.m4s -i cover.jpg -i chapters.txt"));
+        assert!(joined.contains("-map_chapters 3"));
+        assert!(!joined.contains("-map 3"));
+        assert_eq!(args.last().map(String::as_str), Some("out.mp4"));
+    }
+
+    #[test]
+    fn audio_kind_selects_container_specific_args() {
+        let subtitle = SubtitleMergeOptions {
+            path: std::path::PathBuf::from("subtitle.srt"),
+            language: "eng".to_string(),
+            title: "English".to_string(),
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mkv",
+            &MergeMode::SoftSub(vec![subtitle]),
+            AudioCodec::Copy,
+            &MergeOptions {
+                audio_kind: AudioStreamKind::Flac,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(args.join(" ").contains("-c:s copy"));
+
+        let dolby = |codec| {
+            build_merge_args(
+                "v.m4s",
+                Some("a.m4s"),
+                "out.mp4",
+                &MergeMode::None,
+                codec,
+                &MergeOptions {
+                    audio_kind: AudioStreamKind::Dolby,
+                    ..Default::default()
+                },
+            )
+            .unwrap()
+            .join(" ")
+        };
+        assert!(dolby(AudioCodec::Copy).contains("-c:a copy -tag:a ec-3"));
+        assert!(!dolby(AudioCodec::Aac).contains("-tag:a"));
+    }
+
+    #[test]
+    fn mp4_and_mov_embed_mov_text_and_attached_pic() {
+        let subtitle = SubtitleMergeOptions {
+            path: PathBuf::from("danmaku.ass"),
+            language: "und".to_string(),
+            title: "Danmaku".to_string(),
+        };
+        for container in [OutputContainer::Mp4, OutputContainer::Mov] {
+            let args = build_merge_args(
+                "v.m4s",
+                Some("a.m4s"),
+                &format!("out.{}", container.extension()),
+                &MergeMode::SoftSub(vec![subtitle.clone()]),
+                AudioCodec::Copy,
+                &MergeOptions {
+                    container,
+                    cover_path: Some(Path::new("cover.jpg")),
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+            let joined = args.join(" ");
+            assert!(joined.contains("-c:s mov_text"));
+            assert!(joined.contains("-map 3:v"));
+            assert!(joined.contains("-disposition:v:1 attached_pic"));
+            assert!(!joined.contains("-attach"));
+        }
+    }
+
+    #[test]
+    fn matroska_keeps_ass_and_attaches_cover() {
+        let subtitles = vec![
+            SubtitleMergeOptions {
+                path: PathBuf::from("sub.srt"),
+                language: "eng".to_string(),
+                title: "English".to_string(),
+            },
+            SubtitleMergeOptions {
+                path: PathBuf::from("danmaku.ass"),
+                language: "und".to_string(),
+                title: "Danmaku".to_string(),
+            },
+        ];
+        let options = MergeOptions {
+            container: OutputContainer::Mkv,
+            cover_path: Some(Path::new("cover.jpg")),
+            audio_kind: AudioStreamKind::Dolby,
+            ..Default::default()
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mkv",
+            &MergeMode::SoftSub(subtitles),
+            AudioCodec::Copy,
+            &options,
+        )
+        .unwrap();
+        let joined = args.join(" ");
+        assert!(joined.starts_with(
+            "-i v.m4s -i a.m4s -i sub.srt -i danmaku.ass -map 0:v -map 1:a -map 2:0 -map 3:0"
+        ));
+        assert!(joined.contains("-c:s copy"));
+        assert!(joined.contains(
+            "-attach cover.jpg -metadata:s:t mimetype=image/jpeg -metadata:s:t filename=cover.jpg"
+        ));
+        assert!(!joined.contains("attached_pic") && !joined.contains("-tag:a"));
+
+        // The cover is no video stream, so a burned-in subtitle uses -vf.
+        let subtitle = SubtitleMergeOptions {
+            path: PathBuf::from("sub.srt"),
+            language: "eng".to_string(),
+            title: "English".to_string(),
+        };
+        let args = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mkv",
+            &MergeMode::HardSub(subtitle),
+            AudioCodec::Copy,
+            &options,
+        )
+        .unwrap();
+        assert!(args.contains(&"-vf".to_string()));
+    }
+
+    #[test]
+    fn dolby_vision_keeps_its_configuration() {
+        let options = MergeOptions {
+            dynamic_range: DynamicRange::DolbyVision,
+            ..Default::default()
+        };
+        let joined = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mp4",
+            &MergeMode::None,
+            AudioCodec::Copy,
+            &options,
+        )
+        .unwrap()
+        .join(" ");
+        assert!(joined.contains("-movflags +write_colr"));
+        assert!(joined.contains("-strict unofficial -tag:v:0 dvh1"));
+
+        let mkv = MergeOptions {
+            audio_kind: AudioStreamKind::Flac,
+            ..options
+        };
+        let joined = build_merge_args(
+            "v.m4s",
+            Some("a.m4s"),
+            "out.mkv",
+            &MergeMode::None,
+            AudioCodec::Copy,
+            &mkv,
+        )
+        .unwrap()
+        .join(" ");
+        assert!(joined.contains("-strict unofficial"));
+        assert!(!joined.contains("-movflags") && !joined.contains("-tag:v"));
+    }
 }
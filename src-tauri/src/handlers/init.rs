@@ -11,7 +11,7 @@ use std::sync::Mutex;
 
 use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::handlers::{bilibili, cleanup, cookie, ffmpeg, qr_login};
+use crate::handlers::{bilibili, cleanup, cookie, ffmpeg, qr_login, queue};
 use crate::models::frontend_dto::User;
 use crate::models::qr_login::{CookieRefreshInfo, LoginMethod};
 use crate::models::settings::Settings;
@@ -57,8 +57,9 @@ pub async fn initialize(app: AppHandle) -> Result<(), String> {
     // Idempotency guard: `initialize` may be invoked from both the splash
     // window (useSplashLifecycle) and the main window (useInit.initApp, used
     // in E2E mode where there is no splash). The AtomicBool guarantees the
-    // heavy init (cleanup, ffmpeg, session restore, user fetch) runs at most
-    // once per process; the second caller returns immediately.
+    // heavy init (cleanup, ffmpeg, session restore, user fetch, queue
+    // restore) runs at most once per process; the second caller returns
+    // immediately.
     let init_guard = app.state::<std::sync::atomic::AtomicBool>();
     if init_guard.swap(true, std::sync::atomic::Ordering::SeqCst) {
         return Ok(());
@@ -68,6 +69,12 @@ pub async fn initialize(app: AppHandle) -> Result<(), String> {
     emit_step(&app, "init.cleanup_in_progress");
     let _ = cleanup::cleanup_temp_files(&app, None);
 
+    // Open the history database once so a legacy history.json is migrated
+    // before the frontend first lists history.
+    if let Err(e) = crate::store::HistoryStore::run_blocking(&app, |_| Ok(())).await {
+        log::warn!("[BE] initialize: failed to open history database: {}", e);
+    }
+
     // 2. ffmpeg validate / install (heaviest step; downloads on first run).
     //    Settings are already loaded in setup and stored in InitResult, so
     //    they are not reloaded here.
@@ -124,7 +131,11 @@ pub async fn initialize(app: AppHandle) -> Result<(), String> {
         Err(e) => (None, Some(e)),
     };
 
-    // 5. Store the result for the main window to read on startup.
+    // 5. Restore the persisted download queue. Runs after session restore so
+    //    resumed jobs fetch playurls with the user's cookies.
+    queue::restore(&app).await;
+
+    // 6. Store the result for the main window to read on startup.
     if let Some(state) = app.try_state::<Mutex<InitResult>>() {
         if let Ok(mut guard) = state.lock() {
             // guard.settings is set in setup (before the splash is created);
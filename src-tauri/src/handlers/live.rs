@@ -0,0 +1,1008 @@
+//! Bilibili Live Recording Module
+//!
+//! This module records live rooms to disk:
+//!
+//! ## Main Features
+//!
+//! - **Room Resolution**: Resolves short room IDs and reports whether the
+//!   room is broadcasting
+//! - **Stream Selection**: Picks an FLV (`http_stream`) or HLS (`http_hls`,
+//!   fMP4 preferred over TS) stream from the live playurl API
+//! - **Continuous Recording**: Writes the stream as it arrives (drawing from
+//!   the global bandwidth limit), reconnecting automatically when the
+//!   connection drops or stalls and stopping once the room goes offline
+//! - **Chunk Splitting**: Starts a new file after a configured duration or
+//!   size
+//! - **Remux on Stop**: Optionally converts every chunk to MP4 with ffmpeg
+//!   once the recording stops
+//!
+//! Progress is reported through [`Emits::new_unbounded`] (bytes, rate and
+//! elapsed time, no percentage) under the recording ID. Recording state
+//! changes are emitted as `live:updated` events.
+//!
+//! ## API Endpoints
+//!
+//! - Room ID: `GET https://api.live.bilibili.com/room/v1/Room/room_init`
+//! - Room info: `GET https://api.live.bilibili.com/room/v1/Room/get_info`
+//! - Playurl: `GET https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo`
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, Utc};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use reqwest::header;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use crate::constants::USER_AGENT;
+use crate::emits::Emits;
+use crate::handlers::bilibili::{
+    build_client, build_cookie_header_from_cache, build_output_path, create_output_dir,
+};
+use crate::handlers::ffmpeg;
+use crate::handlers::settings;
+use crate::models::bilibili_api::{
+    LivePlayInfoApiResponse, LivePlayurl, LiveRoomInfoApiResponse, LiveRoomInitApiResponse,
+};
+use crate::models::frontend_dto::LiveRoomInfo;
+use crate::utils::bandwidth::BANDWIDTH_LIMITER;
+use crate::utils::proxy::{self, ProxyTarget};
+use crate::utils::sanitize::{
+    apply_title_replacements, sanitize_filename, FILENAME_HEADROOM_BYTES, MAX_FILENAME_BYTES,
+};
+
+const LIVE_REFERER: &str = "https://live.bilibili.com";
+
+/// Quality requested when the caller does not pick one (10000 = 原画).
+const DEFAULT_LIVE_QN: i32 = 10000;
+
+/// Consecutive failed connection attempts before a recording gives up.
+const MAX_RECONNECT_ATTEMPTS: u32 = 10;
+
+/// Delay between reconnect attempts.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// Longest gap without stream data before the connection is treated as
+/// dead and reconnected.
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Interval between HLS playlist refreshes (segments are ~1 s long).
+const HLS_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How many segments a playlist's newest segment may lag behind the last
+/// written one (CDN edges are not perfectly in step) before its media
+/// sequence is taken to have restarted.
+const HLS_SEQUENCE_RESTART_GAP: u64 = 10;
+
+/// Active recordings keyed by recording ID.
+static LIVE_RECORDINGS: Lazy<Arc<Mutex<HashMap<String, ActiveRecording>>>> =
+    Lazy::new(|| Arc::new(Mutex::new(HashMap::new())));
+
+/// Stream protocol to record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiveStreamFormat {
+    /// HTTP-FLV (`http_stream`), one long-lived response per connection
+    #[default]
+    Flv,
+    /// HLS (`http_hls`), fMP4 segments when offered, MPEG-TS otherwise
+    Hls,
+}
+
+/// Options for a live recording.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveRecordOptions {
+    /// Room ID (short or long)
+    pub room_id: i64,
+    /// Stream protocol. Defaults to FLV.
+    #[serde(default)]
+    pub format: LiveStreamFormat,
+    /// Live quality (`qn`). `None` requests the original stream.
+    #[serde(default)]
+    pub quality: Option<i32>,
+    /// Start a new file after this many minutes (0 or `None` = never).
+    #[serde(default)]
+    pub split_minutes: Option<u64>,
+    /// Start a new file after this many megabytes (0 or `None` = never).
+    #[serde(default)]
+    pub split_size_mb: Option<u64>,
+    /// Remux every chunk to MP4 when the recording stops.
+    #[serde(default)]
+    pub remux_to_mp4: bool,
+}
+
+impl LiveRecordOptions {
+    /// Returns `true` once a chunk has reached the split duration or size.
+    fn should_split(&self, elapsed: Duration, bytes: u64) -> bool {
+        let by_time = self
+            .split_minutes
+            .filter(|&m| m > 0)
+            .is_some_and(|m| elapsed >= Duration::from_secs(m * 60));
+        let by_size = self
+            .split_size_mb
+            .filter(|&mb| mb > 0)
+            .is_some_and(|mb| bytes >= mb * 1024 * 1024);
+        by_time || by_size
+    }
+}
+
+/// State of a live recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LiveRecordingStatus {
+    /// Receiving stream data
+    Recording,
+    /// Connection lost; waiting to reconnect
+    Reconnecting,
+    /// Stopped; chunks are being remuxed to MP4
+    Remuxing,
+    /// Stopped by the user or because the broadcast ended
+    Stopped,
+    /// Gave up after repeated connection failures
+    Failed,
+}
+
+/// A live recording sent to the frontend.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveRecording {
+    /// Recording ID (also the `downloadId` of its progress events)
+    pub id: String,
+    /// Long room ID
+    pub room_id: i64,
+    /// Room title when the recording started
+    pub title: String,
+    pub format: LiveStreamFormat,
+    pub status: LiveRecordingStatus,
+    /// Start timestamp (ISO 8601 format)
+    pub started_at: String,
+    /// Chunk files written so far, in order
+    pub files: Vec<String>,
+    /// Total bytes received
+    pub bytes: u64,
+    /// Last connection error
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Registry entry for a running recording.
+struct ActiveRecording {
+    recording: LiveRecording,
+    token: CancellationToken,
+}
+
+/// How a single connection ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SessionEnd {
+    /// The chunk reached its split limit; reconnect into a new chunk
+    Split,
+    /// The server closed the stream
+    Ended,
+    /// The user stopped the recording
+    Stopped,
+}
+
+/// A stream URL chosen from the playurl response.
+#[derive(Debug, Clone, PartialEq)]
+struct SelectedStream {
+    url: String,
+    /// Container of the stream (`flv`, `ts` or `fmp4`)
+    format_name: String,
+    qn: i32,
+}
+
+/// Resolves a live room and reports whether it is broadcasting.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for accessing cookie cache
+/// * `room_id` - Short or long room ID
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `ERR::LIVE_ROOM_NOT_FOUND`: The room does not exist
+/// - Network request fails or the response cannot be parsed
+pub async fn fetch_live_room(app: &AppHandle, room_id: i64) -> Result<LiveRoomInfo, String> {
+    log::info!("[BE] fetch_live_room: room_id={}", room_id);
+    let client = build_client()?;
+    let cookie_header = build_cookie_header_from_cache(app).unwrap_or_default();
+
+    let init = client
+        .get(format!(
+            "https://api.live.bilibili.com/room/v1/Room/room_init?id={room_id}"
+        ))
+        .header(header::COOKIE, &cookie_header)
+        .header(header::REFERER, LIVE_REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch live room: {e}"))?
+        .json::<LiveRoomInitApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse live room response: {e}"))?;
+    match init.code {
+        0 => {}
+        60004 => return Err("ERR::LIVE_ROOM_NOT_FOUND".into()),
+        code => return Err(format!("API error (code {}): {}", code, init.message)),
+    }
+    let init = init.data.ok_or("No data in response")?;
+
+    let info = client
+        .get(format!(
+            "https://api.live.bilibili.com/room/v1/Room/get_info?room_id={}",
+            init.room_id
+        ))
+        .header(header::COOKIE, &cookie_header)
+        .header(header::REFERER, LIVE_REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch live room info: {e}"))?
+        .json::<LiveRoomInfoApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse live room info response: {e}"))?;
+    if info.code != 0 {
+        return Err(format!("API error (code {}): {}", info.code, info.message));
+    }
+    let info = info.data.ok_or("No data in response")?;
+
+    Ok(LiveRoomInfo {
+        room_id: init.room_id,
+        short_id: init.short_id,
+        uid: init.uid,
+        title: info.title,
+        cover: info.user_cover,
+        is_live: init.live_status == 1,
+    })
+}
+
+/// Starts recording a live room in the background.
+///
+/// Chunks are written to the download folder as
+/// `"{title}_{YYYYMMDD_HHMMSS}_{NNN}.{flv|m4s|ts}"`. The recording runs
+/// until [`stop_live_recording`] is called, the room reports it is offline, or
+/// [`MAX_RECONNECT_ATTEMPTS`] connections in a row fail.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `options` - Room, protocol, quality, split and remux options
+///
+/// # Returns
+///
+/// The new recording in `recording` state.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `ERR::LIVE_OFFLINE`: The room is not broadcasting
+/// - `ERR::LIVE_ALREADY_RECORDING`: The room is already being recorded
+/// - The room cannot be resolved (see [`fetch_live_room`])
+/// - The download output path is not configured
+pub async fn start_live_recording(
+    app: &AppHandle,
+    options: LiveRecordOptions,
+) -> Result<LiveRecording, String> {
+    log::info!("[BE] start_live_recording: options={:?}", options);
+    let room = fetch_live_room(app, options.room_id).await?;
+    if !room.is_live {
+        return Err("ERR::LIVE_OFFLINE".into());
+    }
+
+    let settings = settings::get_settings(app).await.ok();
+    let replacements = settings
+        .as_ref()
+        .and_then(|s| s.title_replacements.as_deref());
+    let timestamp = format!("_{}", Local::now().format("%Y%m%d_%H%M%S"));
+    let title = sanitize_filename(
+        &apply_title_replacements(&room.title, replacements),
+        "",
+        MAX_FILENAME_BYTES - FILENAME_HEADROOM_BYTES - timestamp.len(),
+    );
+    let base_name = format!("{title}{timestamp}");
+    let output_stem = build_output_path(app, &base_name, "").await?;
+    create_output_dir(&output_stem).await?;
+
+    let recording = LiveRecording {
+        id: format!("live-{}-{:08x}", room.room_id, rand::random::<u32>()),
+        room_id: room.room_id,
+        title: room.title,
+        format: options.format,
+        status: LiveRecordingStatus::Recording,
+        started_at: Utc::now().to_rfc3339(),
+        files: Vec::new(),
+        bytes: 0,
+        error: None,
+    };
+    let token = CancellationToken::new();
+    {
+        let mut recordings = LIVE_RECORDINGS.lock().await;
+        if recordings
+            .values()
+            .any(|r| r.recording.room_id == room.room_id)
+        {
+            return Err("ERR::LIVE_ALREADY_RECORDING".into());
+        }
+        recordings.insert(
+            recording.id.clone(),
+            ActiveRecording {
+                recording: recording.clone(),
+                token: token.clone(),
+            },
+        );
+    }
+    let _ = app.emit("live:updated", &recording);
+
+    let recorder = Recorder {
+        app: app.clone(),
+        client: build_live_client()?,
+        id: recording.id.clone(),
+        room_id: room.room_id,
+        output_stem,
+        options,
+        token,
+        emits: Emits::new_unbounded(app.clone(), recording.id.clone()),
+        total_bytes: 0,
+        files: Vec::new(),
+        last_hls_sequence: None,
+    };
+    tauri::async_runtime::spawn(recorder.run());
+    Ok(recording)
+}
+
+/// Stops a live recording.
+///
+/// The current chunk is closed and, if requested, all chunks are remuxed
+/// to MP4 before the recording reports `stopped`.
+///
+/// # Returns
+///
+/// `true` if the recording was running.
+pub async fn stop_live_recording(id: &str) -> bool {
+    log::info!("[BE] stop_live_recording: id={}", id);
+    match LIVE_RECORDINGS.lock().await.get(id) {
+        Some(active) => {
+            active.token.cancel();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Returns all recordings that have not finished yet.
+pub async fn get_live_recordings() -> Vec<LiveRecording> {
+    let mut recordings: Vec<_> = LIVE_RECORDINGS
+        .lock()
+        .await
+        .values()
+        .map(|r| r.recording.clone())
+        .collect();
+    recordings.sort_by(|a, b| a.started_at.cmp(&b.started_at));
+    recordings
+}
+
+/// Builds the client for stream transfers.
+///
+/// Unlike the VOD download client there is no overall request timeout: an
+/// FLV response lasts as long as the broadcast. Stalls are detected per
+/// read with [`STALL_TIMEOUT`] instead.
+fn build_live_client() -> Result<Client, String> {
+    proxy::apply(Client::builder(), ProxyTarget::Cdn)
+        .user_agent(USER_AGENT)
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| format!("failed to build live client: {e}"))
+}
+
+/// Picks a stream URL for the requested protocol.
+///
+/// HLS prefers fMP4 over TS. AVC is preferred over HEVC for player
+/// compatibility; HEVC is used when it is the only codec offered.
+fn select_stream(playurl: &LivePlayurl, format: LiveStreamFormat) -> Option<SelectedStream> {
+    let (protocol, containers): (&str, &[&str]) = match format {
+        LiveStreamFormat::Flv => ("http_stream", &["flv"]),
+        LiveStreamFormat::Hls => ("http_hls", &["fmp4", "ts"]),
+    };
+    let stream = playurl
+        .stream
+        .iter()
+        .find(|s| s.protocol_name == protocol)?;
+    containers.iter().find_map(|container| {
+        let entry = stream.format.iter().find(|f| f.format_name == *container)?;
+        let codec = entry
+            .codec
+            .iter()
+            .find(|c| c.codec_name == "avc")
+            .or_else(|| entry.codec.first())?;
+        let url_info = codec.url_info.first()?;
+        Some(SelectedStream {
+            url: format!("{}{}{}", url_info.host, codec.base_url, url_info.extra),
+            format_name: entry.format_name.clone(),
+            qn: codec.current_qn,
+        })
+    })
+}
+
+/// Parsed HLS media playlist.
+#[derive(Debug, Default, PartialEq)]
+struct HlsPlaylist {
+    /// `EXT-X-MAP` initialization segment (fMP4 only)
+    init_uri: Option<String>,
+    /// Segments with their media sequence numbers
+    segments: Vec<(u64, String)>,
+    /// `EXT-X-ENDLIST` was present
+    ended: bool,
+}
+
+/// Parses the subset of an HLS media playlist needed for recording.
+fn parse_hls_playlist(text: &str) -> HlsPlaylist {
+    let mut playlist = HlsPlaylist::default();
+    let mut sequence = 0u64;
+    for line in text.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        if let Some(value) = line.strip_prefix("#EXT-X-MEDIA-SEQUENCE:") {
+            sequence = value.trim().parse().unwrap_or(0);
+        } else if let Some(attrs) = line.strip_prefix("#EXT-X-MAP:") {
+            playlist.init_uri = attrs
+                .split(',')
+                .find_map(|attr| attr.trim().strip_prefix("URI="))
+                .map(|uri| uri.trim_matches('"').to_string());
+        } else if line == "#EXT-X-ENDLIST" {
+            playlist.ended = true;
+        } else if !line.starts_with('#') {
+            playlist.segments.push((sequence, line.to_string()));
+            sequence += 1;
+        }
+    }
+    playlist
+}
+
+/// Returns the sequence after which segments of `playlist` are new.
+///
+/// Keeps `last` (the last segment written, possibly in an earlier
+/// connection) unless the playlist's newest segment lies clearly behind it,
+/// which means the media sequence restarted with a new stream and every
+/// segment is new.
+fn continue_hls_sequence(last: Option<u64>, playlist: &HlsPlaylist) -> Option<u64> {
+    let Some((newest, _)) = playlist.segments.last() else {
+        return last;
+    };
+    last.filter(|&last| newest + HLS_SEQUENCE_RESTART_GAP >= last)
+}
+
+/// Chunk file extension for a stream container.
+fn chunk_extension(format_name: &str) -> &'static str {
+    match format_name {
+        "fmp4" => "m4s",
+        "ts" => "ts",
+        _ => "flv",
+    }
+}
+
+/// State owned by a running recording task.
+struct Recorder {
+    app: AppHandle,
+    client: Client,
+    id: String,
+    room_id: i64,
+    /// Output path without the chunk index and extension
+    output_stem: PathBuf,
+    options: LiveRecordOptions,
+    token: CancellationToken,
+    emits: Emits,
+    total_bytes: u64,
+    files: Vec<PathBuf>,
+    /// Media sequence of the last HLS segment written, kept across
+    /// reconnects so the playlist window is not written twice
+    last_hls_sequence: Option<u64>,
+}
+
+impl Recorder {
+    /// Records until stopped, reconnecting after failures.
+    async fn run(mut self) {
+        self.emits.set_stage("recording").await;
+        let mut failures = 0u32;
+        let mut last_error: Option<String> = None;
+        let mut broadcast_ended = false;
+
+        while !self.token.is_cancelled() {
+            let bytes_before = self.total_bytes;
+            let result = self.record_session().await;
+            let received = self.total_bytes > bytes_before;
+            if received {
+                failures = 0;
+            }
+            let e = match result {
+                Ok(SessionEnd::Stopped) => break,
+                // The server also closes the stream when the broadcast ends
+                // or on CDN hiccups; reconnect at once, and let the next
+                // playurl lookup tell the two apart.
+                Ok(_) if received => continue,
+                Ok(_) => "ERR::LIVE_NO_DATA".to_string(),
+                Err(e) => e,
+            };
+            if e == "ERR::LIVE_OFFLINE" {
+                // Retrying cannot help until the streamer goes live again.
+                log::info!("[BE] live {}: room went offline", self.id);
+                broadcast_ended = true;
+                break;
+            }
+            failures += 1;
+            log::warn!(
+                "[BE] live {}: connection failed ({}/{}): {}",
+                self.id,
+                failures,
+                MAX_RECONNECT_ATTEMPTS,
+                e
+            );
+            last_error = Some(e);
+            if failures >= MAX_RECONNECT_ATTEMPTS {
+                break;
+            }
+            self.update(LiveRecordingStatus::Reconnecting, last_error.clone())
+                .await;
+            self.emits.set_stage("reconnecting").await;
+            tokio::select! {
+                _ = self.token.cancelled() => break,
+                _ = tokio::time::sleep(RECONNECT_DELAY) => {}
+            }
+        }
+
+        self.emits.stop().await;
+        if self.options.remux_to_mp4 && !self.files.is_empty() {
+            self.update(LiveRecordingStatus::Remuxing, None).await;
+            self.emits.set_stage("remux").await;
+            self.remux_chunks().await;
+        }
+        self.emits.complete().await;
+
+        let status = if self.token.is_cancelled() || broadcast_ended || last_error.is_none() {
+            LiveRecordingStatus::Stopped
+        } else {
+            LiveRecordingStatus::Failed
+        };
+        log::info!(
+            "[BE] live {}: finished status={:?}, files={}, bytes={}",
+            self.id,
+            status,
+            self.files.len(),
+            self.total_bytes
+        );
+        let error = (status == LiveRecordingStatus::Failed)
+            .then_some(last_error)
+            .flatten();
+        self.update(status, error).await;
+        LIVE_RECORDINGS.lock().await.remove(&self.id);
+    }
+
+    /// Resolves a fresh stream URL and records one connection.
+    async fn record_session(&mut self) -> Result<SessionEnd, String> {
+        let stream = self.resolve_stream().await?;
+        log::info!(
+            "[BE] live {}: connecting format={}, qn={}",
+            self.id,
+            stream.format_name,
+            stream.qn
+        );
+        self.update(LiveRecordingStatus::Recording, None).await;
+        self.emits.set_stage("recording").await;
+        match self.options.format {
+            LiveStreamFormat::Flv => self.record_flv(&stream).await,
+            LiveStreamFormat::Hls => self.record_hls(&stream).await,
+        }
+    }
+
+    /// Requests the playurl and selects a stream.
+    ///
+    /// Signed stream URLs expire, so this runs before every connection.
+    async fn resolve_stream(&self) -> Result<SelectedStream, String> {
+        let client = build_client()?;
+        let cookie_header = build_cookie_header_from_cache(&self.app).unwrap_or_default();
+        let url = format!(
+            "https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo?room_id={}&protocol=0,1&format=0,1,2&codec=0,1&qn={}&platform=web&ptype=8",
+            self.room_id,
+            self.options.quality.unwrap_or(DEFAULT_LIVE_QN)
+        );
+        let response = client
+            .get(&url)
+            .header(header::COOKIE, &cookie_header)
+            .header(header::REFERER, LIVE_REFERER)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch live playurl: {e}"))?
+            .json::<LivePlayInfoApiResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse live playurl response: {e}"))?;
+        if response.code != 0 {
+            return Err(format!(
+                "API error (code {}): {}",
+                response.code, response.message
+            ));
+        }
+        let data = response.data.ok_or("No data in response")?;
+        if data.live_status != 1 {
+            return Err("ERR::LIVE_OFFLINE".into());
+        }
+        let playurl = data.playurl_info.ok_or("ERR::LIVE_OFFLINE")?.playurl;
+        select_stream(&playurl, self.options.format).ok_or_else(|| "ERR::LIVE_NO_STREAM".into())
+    }
+
+    /// Records an FLV stream into a single chunk.
+    ///
+    /// A new FLV file needs the stream header and codec configuration that
+    /// the server only sends at the start of a response, so splitting is
+    /// done by reconnecting rather than by cutting the byte stream.
+    async fn record_flv(&mut self, stream: &SelectedStream) -> Result<SessionEnd, String> {
+        let response = self
+            .client
+            .get(&stream.url)
+            .header(header::REFERER, LIVE_REFERER)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to live stream: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Live stream returned HTTP {}", response.status()));
+        }
+
+        let mut file = self
+            .open_chunk(chunk_extension(&stream.format_name))
+            .await?;
+        let started = Instant::now();
+        let mut chunk_bytes = 0u64;
+        let mut body = response.bytes_stream();
+        let result = loop {
+            let next = tokio::select! {
+                _ = self.token.cancelled() => break Ok(SessionEnd::Stopped),
+                next = tokio::time::timeout(STALL_TIMEOUT, body.next()) => next,
+            };
+            let data = match next {
+                Err(_) => break Err("ERR::LIVE_STALLED".to_string()),
+                Ok(None) => break Ok(SessionEnd::Ended),
+                Ok(Some(Err(e))) => break Err(format!("Live stream read failed: {e}")),
+                Ok(Some(Ok(data))) => data,
+            };
+            BANDWIDTH_LIMITER.acquire(data.len() as u64).await;
+            if let Err(e) = file.write_all(&data).await {
+                break Err(format!("Failed to write live chunk: {e}"));
+            }
+            chunk_bytes += data.len() as u64;
+            self.add_bytes(data.len() as u64);
+            if self.options.should_split(started.elapsed(), chunk_bytes) {
+                break Ok(SessionEnd::Split);
+            }
+        };
+        let _ = file.flush().await;
+        self.discard_empty_chunk(chunk_bytes).await;
+        result
+    }
+
+    /// Records an HLS stream by polling its playlist.
+    ///
+    /// Chunks are split at segment boundaries. Each fMP4 chunk starts with
+    /// the initialization segment, so every chunk is playable on its own.
+    async fn record_hls(&mut self, stream: &SelectedStream) -> Result<SessionEnd, String> {
+        let playlist_url =
+            Url::parse(&stream.url).map_err(|e| format!("Invalid live playlist URL: {e}"))?;
+        let extension = chunk_extension(&stream.format_name);
+        let mut init_segment: Option<Vec<u8>> = None;
+        let mut chunk: Option<(File, Instant, u64)> = None;
+
+        let result = loop {
+            if self.token.is_cancelled() {
+                break Ok(SessionEnd::Stopped);
+            }
+            let playlist = match self.fetch_text(&playlist_url).await {
+                Ok(text) => parse_hls_playlist(&text),
+                Err(e) => break Err(e),
+            };
+            let continued = continue_hls_sequence(self.last_hls_sequence, &playlist);
+            if continued.is_none() && self.last_hls_sequence.is_some() {
+                log::info!(
+                    "[BE] live {}: media sequence restarted, recording the whole playlist",
+                    self.id
+                );
+            }
+            self.last_hls_sequence = continued;
+            if init_segment.is_none() {
+                if let Some(uri) = &playlist.init_uri {
+                    match self.fetch_segment(&playlist_url, uri).await {
+                        Ok(data) => init_segment = Some(data),
+                        Err(e) => break Err(e),
+                    }
+                }
+            }
+
+            let mut failed = None;
+            for (sequence, uri) in &playlist.segments {
+                if self.last_hls_sequence.is_some_and(|last| *sequence <= last) {
+                    continue;
+                }
+                let data = match self.fetch_segment(&playlist_url, uri).await {
+                    Ok(data) => data,
+                    Err(e) => {
+                        failed = Some(e);
+                        break;
+                    }
+                };
+                let rotate = chunk.as_ref().is_none_or(|(_, started, bytes)| {
+                    self.options.should_split(started.elapsed(), *bytes)
+                });
+                if rotate {
+                    if let Some((mut file, _, _)) = chunk.take() {
+                        let _ = file.flush().await;
+                    }
+                    let mut file = match self.open_chunk(extension).await {
+                        Ok(opened) => opened,
+                        Err(e) => {
+                            failed = Some(e);
+                            break;
+                        }
+                    };
+                    let mut bytes = 0u64;
+                    if let Some(init) = &init_segment {
+                        if let Err(e) = file.write_all(init).await {
+                            failed = Some(format!("Failed to write live chunk: {e}"));
+                            break;
+                        }
+                        bytes += init.len() as u64;
+                    }
+                    chunk = Some((file, Instant::now(), bytes));
+                }
+                let Some((file, _, bytes)) = chunk.as_mut() else {
+                    continue;
+                };
+                BANDWIDTH_LIMITER.acquire(data.len() as u64).await;
+                if let Err(e) = file.write_all(&data).await {
+                    failed = Some(format!("Failed to write live chunk: {e}"));
+                    break;
+                }
+                *bytes += data.len() as u64;
+                self.add_bytes(data.len() as u64);
+                self.last_hls_sequence = Some(*sequence);
+            }
+            if let Some(e) = failed {
+                break Err(e);
+            }
+            if playlist.ended {
+                break Ok(SessionEnd::Ended);
+            }
+
+            tokio::select! {
+                _ = self.token.cancelled() => break Ok(SessionEnd::Stopped),
+                _ = tokio::time::sleep(HLS_POLL_INTERVAL) => {}
+            }
+        };
+
+        if let Some((mut file, _, _)) = chunk.take() {
+            let _ = file.flush().await;
+        }
+        result
+    }
+
+    /// Fetches a playlist as text.
+    async fn fetch_text(&self, url: &Url) -> Result<String, String> {
+        let request = self
+            .client
+            .get(url.as_str())
+            .header(header::REFERER, LIVE_REFERER)
+            .send();
+        let response = tokio::time::timeout(STALL_TIMEOUT, request)
+            .await
+            .map_err(|_| "ERR::LIVE_STALLED".to_string())?
+            .map_err(|e| format!("Failed to fetch live playlist: {e}"))?;
+        if !response.status().is_success() {
+            return Err(format!("Live playlist returned HTTP {}", response.status()));
+        }
+        response
+            .text()
+            .await
+            .map_err(|e| format!("Failed to read live playlist: {e}"))
+    }
+
+    /// Downloads one HLS segment, resolving `uri` against the playlist URL.
+    async fn fetch_segment(&self, playlist_url: &Url, uri: &str) -> Result<Vec<u8>, String> {
+        let url = playlist_url
+            .join(uri)
+            .map_err(|e| format!("Invalid live segment URL: {e}"))?;
+        let request = async {
+            let response = self
+                .client
+                .get(url.as_str())
+                .header(header::REFERER, LIVE_REFERER)
+                .send()
+                .await
+                .map_err(|e| format!("Failed to fetch live segment: {e}"))?;
+            if !response.status().is_success() {
+                return Err(format!("Live segment returned HTTP {}", response.status()));
+            }
+            response
+                .bytes()
+                .await
+                .map(|b| b.to_vec())
+                .map_err(|e| format!("Failed to read live segment: {e}"))
+        };
+        tokio::time::timeout(STALL_TIMEOUT, request)
+            .await
+            .map_err(|_| "ERR::LIVE_STALLED".to_string())?
+    }
+
+    /// Creates the next chunk file and records it in the recording state.
+    async fn open_chunk(&mut self, extension: &str) -> Result<File, String> {
+        let path = chunk_path(&self.output_stem, self.files.len() + 1, extension);
+        let file = File::create(&path)
+            .await
+            .map_err(|e| format!("Failed to create live chunk {:?}: {e}", path))?;
+        log::info!("[BE] live {}: writing chunk {:?}", self.id, path);
+        self.files.push(path);
+        self.update(LiveRecordingStatus::Recording, None).await;
+        Ok(file)
+    }
+
+    /// Removes the last chunk if the connection delivered no data.
+    async fn discard_empty_chunk(&mut self, chunk_bytes: u64) {
+        if chunk_bytes > 0 {
+            return;
+        }
+        if let Some(path) = self.files.pop() {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+    }
+
+    /// Counts received bytes and forwards them to the progress emitter.
+    fn add_bytes(&mut self, bytes: u64) {
+        self.total_bytes += bytes;
+        self.emits.update_progress(self.total_bytes);
+    }
+
+    /// Remuxes every chunk to MP4, replacing the originals on success.
+    ///
+    /// A chunk whose remux fails is kept as recorded.
+    async fn remux_chunks(&mut self) {
+        for path in self.files.iter_mut() {
+            let output = path.with_extension("mp4");
+            match ffmpeg::remux_to_mp4(&self.app, path, &output).await {
+                Ok(()) => {
+                    let _ = tokio::fs::remove_file(&*path).await;
+                    *path = output;
+                }
+                Err(e) => log::warn!("[BE] live {}: remux of {:?} failed: {}", self.id, path, e),
+            }
+        }
+    }
+
+    /// Updates the registry entry and emits `live:updated`.
+    async fn update(&self, status: LiveRecordingStatus, error: Option<String>) {
+        let mut recordings = LIVE_RECORDINGS.lock().await;
+        let Some(active) = recordings.get_mut(&self.id) else {
+            return;
+        };
+        active.recording.status = status;
+        active.recording.error = error;
+        active.recording.bytes = self.total_bytes;
+        active.recording.files = self
+            .files
+            .iter()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+        let _ = self.app.emit("live:updated", &active.recording);
+    }
+}
+
+/// Builds the path of chunk `index` (1-based): `"{stem}_{NNN}.{extension}"`.
+fn chunk_path(stem: &Path, index: usize, extension: &str) -> PathBuf {
+    let mut name = stem.as_os_str().to_os_string();
+    name.push(format!("_{index:03}.{extension}"));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn options(split_minutes: Option<u64>, split_size_mb: Option<u64>) -> LiveRecordOptions {
+        LiveRecordOptions {
+            room_id: 1,
+            format: LiveStreamFormat::Flv,
+            quality: None,
+            split_minutes,
+            split_size_mb,
+            remux_to_mp4: false,
+        }
+    }
+
+    #[test]
+    fn split_by_time_or_size() {
+        let opts = options(Some(30), Some(100));
+        assert!(!opts.should_split(Duration::from_secs(60), 1024));
+        assert!(opts.should_split(Duration::from_secs(30 * 60), 0));
+        assert!(opts.should_split(Duration::from_secs(1), 100 * 1024 * 1024));
+        assert!(!options(Some(0), None).should_split(Duration::from_secs(1 << 20), u64::MAX));
+    }
+
+    #[test]
+    fn parses_fmp4_playlist() {
+        let playlist = parse_hls_playlist(
+            "#EXTM3U\n#EXT-X-VERSION:7\n#EXT-X-MEDIA-SEQUENCE:41\n#EXT-X-TARGETDURATION:1\n\
+             #EXT-X-MAP:URI=\"h1700000000.m4s\"\n#EXTINF:1.00,\n41.m4s\n#EXTINF:1.00,\n42.m4s\n",
+        );
+        assert_eq!(playlist.init_uri.as_deref(), Some("h1700000000.m4s"));
+        assert_eq!(
+            playlist.segments,
+            vec![(41, "41.m4s".to_string()), (42, "42.m4s".to_string())]
+        );
+        assert!(!playlist.ended);
+    }
+
+    #[test]
+    fn hls_sequence_continues_across_reconnects() {
+        let playlist = |first: u64| HlsPlaylist {
+            segments: (first..first + 3)
+                .map(|s| (s, format!("{s}.m4s")))
+                .collect(),
+            ..Default::default()
+        };
+        // A reconnect sees an overlapping window: keep skipping up to 42.
+        assert_eq!(continue_hls_sequence(Some(42), &playlist(40)), Some(42));
+        assert_eq!(continue_hls_sequence(Some(42), &playlist(45)), Some(42));
+        // A slightly lagging edge is still the same stream.
+        assert_eq!(continue_hls_sequence(Some(42), &playlist(35)), Some(42));
+        // A restarted stream numbers from the start again.
+        assert_eq!(continue_hls_sequence(Some(5000), &playlist(1)), None);
+        assert_eq!(continue_hls_sequence(None, &playlist(40)), None);
+        assert_eq!(
+            continue_hls_sequence(Some(42), &HlsPlaylist::default()),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn prefers_fmp4_and_avc() {
+        let playurl: LivePlayurl = serde_json::from_value(json!({
+            "stream": [
+                {"protocol_name": "http_stream", "format": [
+                    {"format_name": "flv", "codec": [
+                        {"codec_name": "avc", "current_qn": 10000, "base_url": "/live/a.flv?",
+                         "url_info": [{"host": "https://cn-flv.example", "extra": "sig=1"}]}
+                    ]}
+                ]},
+                {"protocol_name": "http_hls", "format": [
+                    {"format_name": "ts", "codec": [
+                        {"codec_name": "avc", "current_qn": 10000, "base_url": "/live/a.m3u8?",
+                         "url_info": [{"host": "https://cn-ts.example", "extra": ""}]}
+                    ]},
+                    {"format_name": "fmp4", "codec": [
+                        {"codec_name": "hevc", "current_qn": 10000, "base_url": "/live/h.m3u8?",
+                         "url_info": [{"host": "https://cn-hls.example", "extra": ""}]},
+                        {"codec_name": "avc", "current_qn": 10000, "base_url": "/live/b.m3u8?",
+                         "url_info": [{"host": "https://cn-hls.example", "extra": "sig=2"}]}
+                    ]}
+                ]}
+            ]
+        }))
+        .unwrap();
+
+        let flv = select_stream(&playurl, LiveStreamFormat::Flv).unwrap();
+        assert_eq!(flv.url, "https://cn-flv.example/live/a.flv?sig=1");
+        let hls = select_stream(&playurl, LiveStreamFormat::Hls).unwrap();
+        assert_eq!(hls.format_name, "fmp4");
+        assert_eq!(hls.url, "https://cn-hls.example/live/b.m3u8?sig=2");
+    }
+
+    #[test]
+    fn chunk_paths_are_numbered() {
+        assert_eq!(
+            chunk_path(Path::new("/tmp/Room_20260101_120000"), 2, "flv"),
+            PathBuf::from("/tmp/Room_20260101_120000_002.flv")
+        );
+    }
+}
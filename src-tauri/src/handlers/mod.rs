@@ -1,31 +1,49 @@
 //! Tauri Command Handlers
 //!
 //! Organized by functionality:
+//! - **bangumi**: Whole-season bangumi download with per-episode availability checks
 //! - **bilibili**: Video info retrieval and download operations
 //! - **cleanup**: Orphaned temp file cleanup on app init
+//! - **collection**: UGC collection (合集) and series listing and batch download
 //! - **concurrency**: Semaphore management for parallel downloads
 //! - **concat**: Local MP4 file concatenation via ffmpeg concat demuxer
 //! - **cookie**: Firefox cookie extraction and caching
+//! - **cover**: Original-resolution cover download for artwork embedding
+//! - **danmaku**: Danmaku download, raw XML storage and ASS conversion
 //! - **favorites**: Bilibili favorite folder and video retrieval
 //! - **ffmpeg**: Binary validation and installation, A/V merging
 //! - **github**: GitHub API integration (repository info)
+//! - **live**: Live room recording with reconnect, splitting and MP4 remux
+//! - **queue**: Persistent backend download queue with pause/resume
+//! - **reconcile**: Download history reconciliation with the files on disk
 //! - **settings**: Application settings persistence
+//! - **space**: Uploader space listing and batch download
 //! - **trim**: Local MP4 file trimming via ffmpeg stream copy
 //! - **updater**: GitHub release notes fetching
+//! - **watch_later**: Watch Later (稍后再看) listing and batch download
 
 pub mod audio;
+pub mod bangumi;
 pub mod bilibili;
 pub mod cleanup;
+pub mod collection;
 pub mod concat;
 pub mod concurrency;
 pub mod cookie;
+pub mod cover;
+pub mod danmaku;
 pub mod favorites;
 pub mod ffmpeg;
 pub mod github;
 pub mod init;
+pub mod live;
 pub mod qr_login;
+pub mod queue;
+pub mod reconcile;
 pub mod resolution;
 pub mod rotation;
 pub mod settings;
+pub mod space;
 pub mod trim;
 pub mod updater;
+pub mod watch_later;
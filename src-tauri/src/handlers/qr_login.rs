@@ -21,7 +21,6 @@ use std::sync::RwLock;
 use base64::{engine::general_purpose::STANDARD, Engine};
 use image::Luma;
 use qrcode::QrCode;
-use reqwest::Client;
 use reqwest::Url;
 use tauri::AppHandle;
 use tauri::Manager;
@@ -36,6 +35,7 @@ use crate::models::qr_login::{
     CookieRefreshResponse, LoginMethod, LoginState, QrCodeGenerateResponse, QrCodePollResponse,
     QrCodeResult, QrCodeStatus, QrPollResult, Session,
 };
+use crate::utils::proxy::{self, ProxyTarget};
 use crate::utils::secure_storage::{EncryptedFileStorage, SecureStorage};
 
 /// Bilibili QR code generation API endpoint.
@@ -208,7 +208,7 @@ fn delete_session_from_store(app: &AppHandle) -> Result<(), String> {
 /// - QR code generation fails
 pub async fn generate_qr_code(_app: &AppHandle) -> Result<QrCodeResult, String> {
     log::info!("[BE] generate_qr_code: generating QR code");
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
 
     // Call Bilibili QR generate API
     let response = client
@@ -279,7 +279,7 @@ pub async fn poll_qr_status(app: &AppHandle, qrcode_key: &str) -> Result<QrPollR
         "[BE] poll_qr_status: polling with qrcode_key={}",
         qrcode_key
     );
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
 
     // Call Bilibili QR poll API
     let url = format!("{}?qrcode_key={}", QR_POLL_URL, qrcode_key);
@@ -362,7 +362,7 @@ pub async fn poll_qr_status(app: &AppHandle, qrcode_key: &str) -> Result<QrPollR
 /// Returns an error if the API request fails or returns invalid data.
 async fn fetch_buvid() -> Result<(String, String), String> {
     log::info!("[BE] fetch_buvid: fetching buvid3/buvid4 from API");
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
 
     let response = client
         .get("https://api.bilibili.com/x/frontend/finger/spi")
@@ -720,7 +720,7 @@ pub async fn check_cookie_refresh(app: &AppHandle) -> Result<CookieRefreshInfo,
     let cookies = get_cookie_header(app);
     log::debug!("[BE] Checking with cookies: {} bytes", cookies.len());
 
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
     let response = client
         .get(COOKIE_INFO_URL)
         .header("Cookie", &cookies)
@@ -828,7 +828,7 @@ async fn fetch_refresh_csrf(app: &AppHandle, correspond_path: &str) -> Result<St
     let url = format!("{}{}", CORRESPOND_URL_PREFIX, correspond_path);
     log::debug!("[BE] fetch_refresh_csrf: URL: {}", url);
 
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
     let response = client
         .get(&url)
         .header("Cookie", &cookies)
@@ -932,7 +932,7 @@ pub async fn refresh_cookie(app: &AppHandle) -> Result<Session, String> {
 
     // Step 3: Call cookie refresh API
     let cookies = get_cookie_header(app);
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
 
     let params = [
         ("csrf", session.bili_jct.clone()),
@@ -0,0 +1,752 @@
+//! Persistent Download Queue
+//!
+//! This module owns the download queue on the backend so it survives app
+//! restarts. Jobs are persisted to `download_queue.json` via
+//! tauri-plugin-store on every state change and restored by
+//! [`crate::handlers::init::initialize`].
+//!
+//! ## Job Lifecycle
+//!
+//! ```text
+//! pending ──▶ running ──▶ completed | failed | cancelled
+//!    ▲  │        │
+//!    │  ▼        ▼
+//!    └─ paused ◀─┘
+//! ```
+//!
+//! Pausing a running job stops its transfer through
+//! [`DownloadCancelRegistry::pause`](crate::handlers::concurrency::DownloadCancelRegistry::pause),
+//! which keeps the partial temp streams and their resume manifests. Resuming
+//! re-runs `download_video` with the same options, so only the missing
+//! segments are fetched.
+
+use crate::handlers::bilibili::{self, DownloadOptions, StreamMode};
+use crate::handlers::concurrency::DOWNLOAD_CANCEL_REGISTRY;
+use crate::handlers::watch_later;
+use crate::models::frontend_dto::Video;
+use crate::utils::download_archive::{self, ArchiveMode};
+use crate::utils::{filename_template, metadata};
+use chrono::Utc;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_store::StoreExt;
+use tokio::sync::Mutex;
+
+const QUEUE_STORE_FILE: &str = "download_queue.json";
+const VERSION_KEY: &str = "__version__";
+const JOBS_KEY: &str = "jobs";
+const CURRENT_VERSION: &str = "1.0";
+
+/// Maximum number of queue jobs running `download_video` at once.
+///
+/// Kept below `VIDEO_SEMAPHORE`'s limit so queued jobs do not fetch
+/// playurls long before they can start; their signed URLs would expire
+/// while waiting for a merge permit.
+const MAX_RUNNING_JOBS: usize = 3;
+
+/// State of a queued download job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueueJobStatus {
+    /// Waiting for a free slot
+    Pending,
+    /// `download_video` is running for this job
+    Running,
+    /// Stopped by the user; partial temp files are kept
+    Paused,
+    /// Finished successfully
+    Completed,
+    /// Not downloaded because the item is already in the download archive
+    Skipped,
+    /// Finished with an error (can be resumed to retry)
+    Failed,
+    /// Cancelled by the user; temp files were removed
+    Cancelled,
+}
+
+impl QueueJobStatus {
+    /// Returns `true` for terminal states that no longer need a slot.
+    fn is_finished(self) -> bool {
+        matches!(
+            self,
+            QueueJobStatus::Completed
+                | QueueJobStatus::Skipped
+                | QueueJobStatus::Failed
+                | QueueJobStatus::Cancelled
+        )
+    }
+}
+
+/// A single download job in the backend queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueJob {
+    /// Job ID (same as `options.download_id`)
+    pub id: String,
+    /// Options passed to `download_video` when the job runs
+    pub options: DownloadOptions,
+    /// Current job state
+    pub status: QueueJobStatus,
+    /// Error code of the last failed run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Output file path once completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
+    /// Enqueue timestamp (ISO 8601 format)
+    pub added_at: String,
+}
+
+/// Global backend download queue.
+pub static DOWNLOAD_QUEUE: Lazy<Arc<DownloadQueue>> = Lazy::new(|| Arc::new(DownloadQueue::new()));
+
+/// In-memory job list with state transitions.
+///
+/// Persistence and job execution live in the free functions of this module;
+/// the methods here only mutate the list, which keeps them testable without
+/// an `AppHandle`.
+#[derive(Debug, Default)]
+pub struct DownloadQueue {
+    jobs: Mutex<Vec<QueueJob>>,
+}
+
+impl DownloadQueue {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self {
+            jobs: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Appends a job. Rejects an ID that is already queued and unfinished.
+    async fn push(&self, job: QueueJob) -> Result<(), String> {
+        let mut jobs = self.jobs.lock().await;
+        if jobs
+            .iter()
+            .any(|j| j.id == job.id && !j.status.is_finished())
+        {
+            return Err("ERR::QUEUE_DUPLICATE".to_string());
+        }
+        jobs.retain(|j| j.id != job.id);
+        jobs.push(job);
+        Ok(())
+    }
+
+    /// Returns a copy of all jobs in queue order.
+    pub async fn snapshot(&self) -> Vec<QueueJob> {
+        self.jobs.lock().await.clone()
+    }
+
+    /// Replaces the job list with restored jobs.
+    ///
+    /// Jobs that were running when the app exited are reset to pending so
+    /// they restart (and resume from their manifests).
+    async fn restore_jobs(&self, restored: Vec<QueueJob>) {
+        let mut jobs = self.jobs.lock().await;
+        *jobs = restored
+            .into_iter()
+            .map(|mut j| {
+                if j.status == QueueJobStatus::Running {
+                    j.status = QueueJobStatus::Pending;
+                }
+                j
+            })
+            .collect();
+    }
+
+    /// Marks pending jobs as running up to [`MAX_RUNNING_JOBS`] and returns
+    /// the jobs that should be started.
+    async fn claim_startable(&self) -> Vec<QueueJob> {
+        let mut jobs = self.jobs.lock().await;
+        let running = jobs
+            .iter()
+            .filter(|j| j.status == QueueJobStatus::Running)
+            .count();
+        let mut started = Vec::new();
+        for job in jobs.iter_mut() {
+            if running + started.len() >= MAX_RUNNING_JOBS {
+                break;
+            }
+            if job.status == QueueJobStatus::Pending {
+                job.status = QueueJobStatus::Running;
+                job.error = None;
+                started.push(job.clone());
+            }
+        }
+        started
+    }
+
+    /// Records the outcome of a run. Returns `false` if the job was removed
+    /// while running.
+    async fn finish(
+        &self,
+        id: &str,
+        status: QueueJobStatus,
+        error: Option<String>,
+        output_path: Option<String>,
+    ) -> bool {
+        let mut jobs = self.jobs.lock().await;
+        match jobs.iter_mut().find(|j| j.id == id) {
+            Some(job) => {
+                job.status = status;
+                job.error = error;
+                job.output_path = output_path;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `true` if every job sharing `job`'s parent ID has completed
+    /// (or was skipped as already downloaded).
+    ///
+    /// Jobs without a parent ID form a group of their own.
+    async fn group_completed(&self, job: &QueueJob) -> bool {
+        let group = job.options.parent_id.as_deref().unwrap_or(&job.id);
+        self.jobs
+            .lock()
+            .await
+            .iter()
+            .filter(|j| j.options.parent_id.as_deref().unwrap_or(&j.id) == group)
+            .all(|j| {
+                matches!(
+                    j.status,
+                    QueueJobStatus::Completed | QueueJobStatus::Skipped
+                )
+            })
+    }
+
+    /// Pauses a job and returns its previous status.
+    ///
+    /// Pending jobs become paused immediately. Running jobs keep their
+    /// status until `download_video` returns; the caller signals them.
+    async fn pause_job(&self, id: &str) -> Option<QueueJobStatus> {
+        let mut jobs = self.jobs.lock().await;
+        let job = jobs.iter_mut().find(|j| j.id == id)?;
+        let previous = job.status;
+        if previous == QueueJobStatus::Pending {
+            job.status = QueueJobStatus::Paused;
+        }
+        Some(previous)
+    }
+
+    /// Moves a paused or failed job back to pending. Returns `true` if the
+    /// job changed state.
+    async fn resume_job(&self, id: &str) -> bool {
+        let mut jobs = self.jobs.lock().await;
+        match jobs.iter_mut().find(|j| j.id == id) {
+            Some(job) if matches!(job.status, QueueJobStatus::Paused | QueueJobStatus::Failed) => {
+                job.status = QueueJobStatus::Pending;
+                job.error = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a job and returns it.
+    async fn remove_job(&self, id: &str) -> Option<QueueJob> {
+        let mut jobs = self.jobs.lock().await;
+        let idx = jobs.iter().position(|j| j.id == id)?;
+        Some(jobs.remove(idx))
+    }
+
+    /// Removes all finished jobs and returns how many were removed.
+    async fn remove_finished(&self) -> usize {
+        let mut jobs = self.jobs.lock().await;
+        let before = jobs.len();
+        jobs.retain(|j| !j.status.is_finished());
+        before - jobs.len()
+    }
+
+    /// Returns the IDs of jobs in the given state.
+    async fn ids_with_status(&self, status: QueueJobStatus) -> Vec<String> {
+        let jobs = self.jobs.lock().await;
+        jobs.iter()
+            .filter(|j| j.status == status)
+            .map(|j| j.id.clone())
+            .collect()
+    }
+}
+
+/// Adds a download to the queue and starts it when a slot is free.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `options` - Download options; `download_id` becomes the job ID
+///
+/// # Returns
+///
+/// Returns the queued job.
+///
+/// # Errors
+///
+/// Returns `ERR::QUEUE_DUPLICATE` if an unfinished job with the same ID is
+/// already queued.
+pub async fn enqueue(app: &AppHandle, options: DownloadOptions) -> Result<QueueJob, String> {
+    log::info!("[BE] queue enqueue: id={}", options.download_id);
+    let job = QueueJob {
+        id: options.download_id.clone(),
+        options,
+        status: QueueJobStatus::Pending,
+        error: None,
+        output_path: None,
+        added_at: Utc::now().to_rfc3339(),
+    };
+    DOWNLOAD_QUEUE.push(job.clone()).await?;
+    on_queue_changed(app).await;
+    pump(app);
+    Ok(job)
+}
+
+/// Adds several downloads in one batch.
+///
+/// Used by the batch sources (uploader spaces, collections, favorites) that
+/// enqueue many parts at once. The queue is persisted and `queue:updated`
+/// emitted once for the whole batch instead of once per job.
+///
+/// `archive_mode` is applied to every job, deciding whether items already
+/// in the download archive are skipped, downloaded again or overwritten.
+///
+/// # Returns
+///
+/// The jobs that were added. Options whose download ID is already queued
+/// and unfinished are skipped instead of failing the batch.
+pub async fn enqueue_many(
+    app: &AppHandle,
+    options: Vec<DownloadOptions>,
+    archive_mode: ArchiveMode,
+) -> Vec<QueueJob> {
+    let mut added = Vec::with_capacity(options.len());
+    for mut opts in options {
+        opts.archive_mode = Some(archive_mode);
+        let job = QueueJob {
+            id: opts.download_id.clone(),
+            options: opts,
+            status: QueueJobStatus::Pending,
+            error: None,
+            output_path: None,
+            added_at: Utc::now().to_rfc3339(),
+        };
+        match DOWNLOAD_QUEUE.push(job.clone()).await {
+            Ok(()) => added.push(job),
+            Err(e) => log::warn!("[BE] queue enqueue_many: skipping id={}: {}", job.id, e),
+        }
+    }
+    log::info!("[BE] queue enqueue_many: added {} job(s)", added.len());
+    if !added.is_empty() {
+        on_queue_changed(app).await;
+        pump(app);
+    }
+    added
+}
+
+/// Builds download options for every part of a video.
+///
+/// Mirrors what the video page submits for a manual download: best
+/// available quality, no subtitles, and part filenames of the form
+/// `"{title} {part}"` (just `"{title}"` when the part is named after the
+/// video). All parts share a fresh parent ID so the frontend groups them.
+pub fn part_download_options(video: &Video) -> Vec<DownloadOptions> {
+    let parent_id = format!("{}-{:016x}", video.bvid, rand::random::<u64>());
+    video
+        .parts
+        .iter()
+        .map(|part| {
+            let filename = if video.title == part.part {
+                video.title.clone()
+            } else {
+                format!(
+                    "{} {}",
+                    video.title,
+                    part.sanitized_part.as_deref().unwrap_or(&part.part)
+                )
+            };
+            DownloadOptions {
+                bvid: video.bvid.clone(),
+                cid: part.cid,
+                filename: filename.trim().to_string(),
+                quality: None,
+                audio_quality: None,
+                download_id: format!("{}-p{}", parent_id, part.page),
+                parent_id: Some(parent_id.clone()),
+                duration_seconds: part.duration,
+                thumbnail_url: Some(part.thumbnail.url.clone()),
+                page: Some(part.page),
+                subtitle: None,
+                ep_id: part.ep_id.or(video.ep_id),
+                watch_later_aid: None,
+                danmaku: None,
+                metadata: metadata::part_metadata(video, part),
+                chapters: None,
+                audio_only: None,
+                stream_mode: StreamMode::Merged,
+                template_values: Some(filename_template::values_for_part(video, part)),
+                archive_mode: None,
+            }
+        })
+        .collect()
+}
+
+/// Returns all queued jobs in queue order.
+pub async fn get_jobs() -> Vec<QueueJob> {
+    DOWNLOAD_QUEUE.snapshot().await
+}
+
+/// Pauses a job, keeping completed segments of a running transfer.
+///
+/// # Returns
+///
+/// `true` if the job was pending or running, `false` if it was not found or
+/// already finished/paused.
+pub async fn pause(app: &AppHandle, id: &str) -> bool {
+    let paused = match DOWNLOAD_QUEUE.pause_job(id).await {
+        Some(QueueJobStatus::Pending) => true,
+        Some(QueueJobStatus::Running) => {
+            // The job flips to paused when download_video returns.
+            DOWNLOAD_CANCEL_REGISTRY.pause(id).await;
+            true
+        }
+        _ => false,
+    };
+    if paused {
+        log::info!("[BE] queue pause: id={}", id);
+        on_queue_changed(app).await;
+    }
+    paused
+}
+
+/// Resumes a paused or failed job.
+///
+/// # Returns
+///
+/// `true` if the job was moved back to pending.
+pub async fn resume(app: &AppHandle, id: &str) -> bool {
+    // Clear flags a pause may have left behind if it raced with the end of
+    // the previous run, so the next download_video start is not rejected.
+    DOWNLOAD_CANCEL_REGISTRY.clear_cancelled(id).await;
+    DOWNLOAD_CANCEL_REGISTRY.take_paused(id).await;
+    let resumed = DOWNLOAD_QUEUE.resume_job(id).await;
+    if resumed {
+        log::info!("[BE] queue resume: id={}", id);
+        on_queue_changed(app).await;
+        pump(app);
+    }
+    resumed
+}
+
+/// Pauses every pending and running job.
+///
+/// # Returns
+///
+/// Number of jobs paused.
+pub async fn pause_all(app: &AppHandle) -> usize {
+    let mut ids = DOWNLOAD_QUEUE
+        .ids_with_status(QueueJobStatus::Pending)
+        .await;
+    ids.extend(
+        DOWNLOAD_QUEUE
+            .ids_with_status(QueueJobStatus::Running)
+            .await,
+    );
+    let mut count = 0;
+    for id in ids {
+        if pause(app, &id).await {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Resumes every paused job.
+///
+/// # Returns
+///
+/// Number of jobs resumed.
+pub async fn resume_all(app: &AppHandle) -> usize {
+    let ids = DOWNLOAD_QUEUE.ids_with_status(QueueJobStatus::Paused).await;
+    let mut count = 0;
+    for id in ids {
+        if resume(app, &id).await {
+            count += 1;
+        }
+    }
+    count
+}
+
+/// Removes a job from the queue, cancelling it if it is running.
+///
+/// # Returns
+///
+/// `true` if the job existed.
+pub async fn remove(app: &AppHandle, id: &str) -> bool {
+    let Some(job) = DOWNLOAD_QUEUE.remove_job(id).await else {
+        return false;
+    };
+    if job.status == QueueJobStatus::Running && !DOWNLOAD_CANCEL_REGISTRY.cancel(id).await {
+        DOWNLOAD_CANCEL_REGISTRY.mark_cancelled(id).await;
+    }
+    log::info!("[BE] queue remove: id={}", id);
+    on_queue_changed(app).await;
+    pump(app);
+    true
+}
+
+/// Removes completed, failed, and cancelled jobs.
+///
+/// # Returns
+///
+/// Number of jobs removed.
+pub async fn clear_finished(app: &AppHandle) -> usize {
+    let removed = DOWNLOAD_QUEUE.remove_finished().await;
+    if removed > 0 {
+        on_queue_changed(app).await;
+    }
+    removed
+}
+
+/// Restores the persisted queue and starts pending jobs.
+///
+/// Called once from [`crate::handlers::init::initialize`]. A missing or
+/// unreadable store leaves the queue empty.
+pub async fn restore(app: &AppHandle) {
+    let jobs = load_persisted(app);
+    log::info!("[BE] queue restore: {} job(s)", jobs.len());
+    DOWNLOAD_QUEUE.restore_jobs(jobs).await;
+    on_queue_changed(app).await;
+    pump(app);
+}
+
+/// Starts pending jobs while running slots are free.
+///
+/// Synchronous on purpose: it only spawns, so `run_job` can call it without
+/// making the two futures recursive.
+fn pump(app: &AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let started = DOWNLOAD_QUEUE.claim_startable().await;
+        if started.is_empty() {
+            return;
+        }
+        on_queue_changed(&app).await;
+        for job in started {
+            tauri::async_runtime::spawn(run_job(app.clone(), job));
+        }
+    });
+}
+
+/// Runs `download_video` for a job and records the outcome.
+async fn run_job(app: AppHandle, job: QueueJob) {
+    log::info!("[BE] queue run_job: starting id={}", job.id);
+    let result = bilibili::download_video(&app, &job.options).await;
+    let paused = DOWNLOAD_CANCEL_REGISTRY.take_paused(&job.id).await;
+
+    let (status, error, output_path) = match result {
+        Ok(path) => (QueueJobStatus::Completed, None, Some(path)),
+        Err(e) => match download_archive::parse_archived_error(&e) {
+            Some(path) => (QueueJobStatus::Skipped, None, path),
+            None if e.contains("ERR::CANCELLED") && paused => (QueueJobStatus::Paused, None, None),
+            None if e.contains("ERR::CANCELLED") => (QueueJobStatus::Cancelled, None, None),
+            None => (QueueJobStatus::Failed, Some(e), None),
+        },
+    };
+    log::info!(
+        "[BE] queue run_job: finished id={} status={:?}",
+        job.id,
+        status
+    );
+
+    if DOWNLOAD_QUEUE
+        .finish(&job.id, status, error, output_path)
+        .await
+    {
+        on_queue_changed(&app).await;
+    }
+    pump(&app);
+
+    if let Some(aid) = job.options.watch_later_aid {
+        if matches!(status, QueueJobStatus::Completed | QueueJobStatus::Skipped)
+            && DOWNLOAD_QUEUE.group_completed(&job).await
+        {
+            if let Err(e) = watch_later::remove_from_watch_later(&app, aid).await {
+                log::warn!(
+                    "[BE] queue run_job: failed to remove aid={} from watch later: {}",
+                    aid,
+                    e
+                );
+            }
+        }
+    }
+}
+
+/// Persists the queue and notifies the frontend with a `queue:updated` event.
+async fn on_queue_changed(app: &AppHandle) {
+    let jobs = DOWNLOAD_QUEUE.snapshot().await;
+    if let Err(e) = persist(app, &jobs) {
+        log::warn!("[BE] queue: failed to persist queue: {}", e);
+    }
+    let _ = app.emit("queue:updated", &jobs);
+}
+
+/// Writes the job list to the queue store.
+fn persist(app: &AppHandle, jobs: &[QueueJob]) -> Result<(), String> {
+    let store = app.store(QUEUE_STORE_FILE).map_err(|e| e.to_string())?;
+    let jobs_value = serde_json::to_value(jobs).map_err(|e| e.to_string())?;
+    store.set(VERSION_KEY, CURRENT_VERSION);
+    store.set(JOBS_KEY, jobs_value);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Reads the job list from the queue store, returning an empty list on any
+/// error.
+fn load_persisted(app: &AppHandle) -> Vec<QueueJob> {
+    let Ok(store) = app.store(QUEUE_STORE_FILE) else {
+        return Vec::new();
+    };
+    let jobs_value = store.get(JOBS_KEY).unwrap_or(json!([]));
+    serde_json::from_value(jobs_value).unwrap_or_else(|e| {
+        log::warn!("[BE] queue: discarding unreadable queue store: {}", e);
+        Vec::new()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, status: QueueJobStatus) -> QueueJob {
+        let options: DownloadOptions = serde_json::from_value(json!({
+            "bvid": "BV1xx411c7XD",
+            "cid": 1,
+            "filename": "video",
+            "quality": null,
+            "audioQuality": null,
+            "downloadId": id,
+            "parentId": null,
+            "durationSeconds": 60
+        }))
+        .unwrap();
+        QueueJob {
+            id: id.to_string(),
+            options,
+            status,
+            error: None,
+            output_path: None,
+            added_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn push_rejects_unfinished_duplicate() {
+        let queue = DownloadQueue::new();
+        queue.push(job("a", QueueJobStatus::Pending)).await.unwrap();
+        assert_eq!(
+            queue.push(job("a", QueueJobStatus::Pending)).await,
+            Err("ERR::QUEUE_DUPLICATE".to_string())
+        );
+
+        queue
+            .finish("a", QueueJobStatus::Completed, None, None)
+            .await;
+        assert!(queue.push(job("a", QueueJobStatus::Pending)).await.is_ok());
+        assert_eq!(queue.snapshot().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn claim_startable_respects_running_limit() {
+        let queue = DownloadQueue::new();
+        for i in 0..5 {
+            queue
+                .push(job(&format!("j{i}"), QueueJobStatus::Pending))
+                .await
+                .unwrap();
+        }
+
+        let started = queue.claim_startable().await;
+        assert_eq!(started.len(), MAX_RUNNING_JOBS);
+        assert!(queue.claim_startable().await.is_empty());
+
+        queue
+            .finish("j0", QueueJobStatus::Completed, None, None)
+            .await;
+        let next = queue.claim_startable().await;
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0].id, format!("j{MAX_RUNNING_JOBS}"));
+    }
+
+    #[tokio::test]
+    async fn restore_resets_running_jobs_to_pending() {
+        let queue = DownloadQueue::new();
+        queue
+            .restore_jobs(vec![
+                job("run", QueueJobStatus::Running),
+                job("pause", QueueJobStatus::Paused),
+            ])
+            .await;
+
+        let jobs = queue.snapshot().await;
+        assert_eq!(jobs[0].status, QueueJobStatus::Pending);
+        assert_eq!(jobs[1].status, QueueJobStatus::Paused);
+    }
+
+    #[tokio::test]
+    async fn pause_and_resume_pending_job() {
+        let queue = DownloadQueue::new();
+        queue.push(job("a", QueueJobStatus::Pending)).await.unwrap();
+
+        assert_eq!(queue.pause_job("a").await, Some(QueueJobStatus::Pending));
+        assert_eq!(queue.snapshot().await[0].status, QueueJobStatus::Paused);
+        assert!(queue.claim_startable().await.is_empty());
+
+        assert!(queue.resume_job("a").await);
+        assert_eq!(queue.snapshot().await[0].status, QueueJobStatus::Pending);
+        assert!(!queue.resume_job("a").await, "pending job is not resumable");
+    }
+
+    #[tokio::test]
+    async fn running_job_stays_running_until_finished() {
+        let queue = DownloadQueue::new();
+        queue.push(job("a", QueueJobStatus::Pending)).await.unwrap();
+        queue.claim_startable().await;
+
+        assert_eq!(queue.pause_job("a").await, Some(QueueJobStatus::Running));
+        assert_eq!(queue.snapshot().await[0].status, QueueJobStatus::Running);
+    }
+
+    #[test]
+    fn status_serializes_lowercase() {
+        assert_eq!(
+            serde_json::to_value(QueueJobStatus::Paused).unwrap(),
+            json!("paused")
+        );
+    }
+
+    #[test]
+    fn part_download_options_names_parts_like_the_video_page() {
+        let video: Video = serde_json::from_value(json!({
+            "title": "Trip",
+            "bvid": "BV1xx411c7XD",
+            "parts": [
+                {"cid": 11, "page": 1, "part": "Trip", "duration": 30,
+                 "thumbnail": {"url": "a.jpg"}, "videoQualities": [], "audioQualities": []},
+                {"cid": 12, "page": 2, "part": "Day 2", "sanitizedPart": "Day 2 (1)",
+                 "duration": 40, "thumbnail": {"url": "b.jpg"},
+                 "videoQualities": [], "audioQualities": []}
+            ]
+        }))
+        .unwrap();
+
+        let options = part_download_options(&video);
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].filename, "Trip");
+        assert_eq!(options[1].filename, "Trip Day 2 (1)");
+        assert_eq!(options[1].cid, 12);
+        assert_eq!(options[0].parent_id, options[1].parent_id);
+        assert!(options[1].download_id.ends_with("-p2"));
+        assert_eq!(options[1].quality, None);
+    }
+}
@@ -0,0 +1,332 @@
+//! History Reconciliation Handler
+//!
+//! Compares the download history with the files on disk. Completed entries
+//! whose output file is gone are marked `missing`; files that were moved
+//! within the download output directory are found again by file name and
+//! size, and media files in the output directory that belong to no history
+//! entry are reported as orphans.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use tauri::AppHandle;
+
+use crate::handlers::settings;
+use crate::models::history::HistoryEntry;
+use crate::store::HistoryStore;
+
+/// Status of entries whose output file no longer exists.
+pub const STATUS_MISSING: &str = "missing";
+
+/// Extensions of the files the output directory is scanned for.
+const MEDIA_EXTENSIONS: &[&str] = &["mp4", "mkv", "mov", "flv", "m4v", "m4a", "mp3", "flac"];
+
+/// Depth limit of the output directory scan (folders from templates are
+/// rarely more than a few levels deep).
+const MAX_SCAN_DEPTH: usize = 8;
+
+/// An entry whose output file was found at a new location.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocatedEntry {
+    /// History entry ID
+    pub id: String,
+    /// Previously recorded path (`None` for entries that predate it)
+    pub previous_path: Option<String>,
+    /// Path the file was found at
+    pub output_path: String,
+}
+
+/// Result of a reconciliation run.
+#[derive(Debug, Default, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconcileReport {
+    /// Number of completed (or missing) entries that were checked
+    pub checked_count: u32,
+    /// Entries whose file was found at a new location
+    pub relocated: Vec<RelocatedEntry>,
+    /// IDs of entries that were missing and are back at their recorded path
+    pub restored: Vec<String>,
+    /// IDs of entries whose file could not be found
+    pub missing: Vec<String>,
+    /// Media files in the output directory without a history entry
+    pub orphans: Vec<String>,
+}
+
+/// A media file found in the output directory.
+#[derive(Debug)]
+struct MediaFile {
+    path: PathBuf,
+    size: u64,
+}
+
+/// Reconciles the download history with the download output directory.
+///
+/// Changed entries (relocated, missing or restored) are saved back to the
+/// history store. Downloads still in progress may show up as orphans, so
+/// the check is best run while the queue is idle.
+///
+/// # Returns
+///
+/// A [`ReconcileReport`] describing what was found.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Settings cannot be read or the output path is not configured
+/// - The history store cannot be read or written
+pub async fn reconcile_history(app: &AppHandle) -> Result<ReconcileReport, String> {
+    let settings = settings::get_settings(app)
+        .await
+        .map_err(|e| format!("Failed to get settings: {e}"))?;
+    let output_dir = settings
+        .dl_output_path
+        .ok_or_else(|| "Download output path is not configured".to_string())?;
+
+    HistoryStore::run_blocking(app, move |store| {
+        let mut media = Vec::new();
+        scan_media(Path::new(&output_dir), 0, &mut media);
+        let (changed, report) = reconcile(store.load()?, &media);
+        store.update_locations(&changed)?;
+        log::info!(
+            "[BE] reconcile_history: checked={}, relocated={}, restored={}, missing={}, orphans={}",
+            report.checked_count,
+            report.relocated.len(),
+            report.restored.len(),
+            report.missing.len(),
+            report.orphans.len()
+        );
+        Ok(report)
+    })
+    .await
+}
+
+/// Collects media files under `dir`. Symlinks are not followed and
+/// unreadable folders are skipped.
+fn scan_media(dir: &Path, depth: usize, media: &mut Vec<MediaFile>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let path = entry.path();
+        if file_type.is_dir() {
+            if depth < MAX_SCAN_DEPTH {
+                scan_media(&path, depth + 1, media);
+            }
+        } else if file_type.is_file() && is_media_file(&path) {
+            if let Ok(metadata) = entry.metadata() {
+                media.push(MediaFile {
+                    path,
+                    size: metadata.len(),
+                });
+            }
+        }
+    }
+}
+
+/// Checks if a path has one of the [`MEDIA_EXTENSIONS`].
+fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// Matches history entries against the scanned media files.
+///
+/// An entry whose recorded file is gone is relocated when exactly one
+/// unclaimed file has the same name (and the recorded size, if known);
+/// otherwise it is marked missing. Entries recorded before output paths
+/// were tracked are matched by title instead and are left alone when no
+/// file matches.
+///
+/// # Returns
+///
+/// The changed entries and the report.
+fn reconcile(
+    entries: Vec<HistoryEntry>,
+    media: &[MediaFile],
+) -> (Vec<HistoryEntry>, ReconcileReport) {
+    let mut report = ReconcileReport::default();
+    let mut changed = Vec::new();
+
+    // Files at their recorded path belong to their entry whatever its status.
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    for entry in &entries {
+        if let Some(path) = entry.output_path.as_deref().map(Path::new) {
+            if path.is_file() {
+                claim(&mut claimed, path, &entry.stream_files);
+            }
+        }
+    }
+
+    for mut entry in entries {
+        if !matches!(
+            entry.status.as_str(),
+            "completed" | "success" | STATUS_MISSING
+        ) {
+            continue;
+        }
+        report.checked_count += 1;
+
+        let recorded = entry.output_path.clone();
+        if recorded.as_deref().is_some_and(|p| Path::new(p).is_file()) {
+            if entry.status == STATUS_MISSING {
+                entry.status = "completed".to_string();
+                report.restored.push(entry.id.clone());
+                changed.push(entry);
+            }
+            continue;
+        }
+
+        let candidates: Vec<&MediaFile> = media
+            .iter()
+            .filter(|file| !claimed.contains(&file.path))
+            .filter(|file| entry.file_size.is_none_or(|size| size == file.size))
+            .filter(|file| match recorded.as_deref() {
+                Some(p) => file.path.file_name() == Path::new(p).file_name(),
+                None => file.path.file_stem().and_then(|s| s.to_str()) == Some(&entry.title),
+            })
+            .collect();
+
+        if let [file] = candidates[..] {
+            claim(&mut claimed, &file.path, &entry.stream_files);
+            let output_path = file.path.to_string_lossy().to_string();
+            report.relocated.push(RelocatedEntry {
+                id: entry.id.clone(),
+                previous_path: recorded,
+                output_path: output_path.clone(),
+            });
+            entry.output_path = Some(output_path);
+            if entry.status == STATUS_MISSING {
+                entry.status = "completed".to_string();
+            }
+            changed.push(entry);
+        } else if recorded.is_some() {
+            report.missing.push(entry.id.clone());
+            if entry.status != STATUS_MISSING {
+                entry.status = STATUS_MISSING.to_string();
+                changed.push(entry);
+            }
+        }
+    }
+
+    report.orphans = media
+        .iter()
+        .filter(|file| !claimed.contains(&file.path))
+        .map(|file| file.path.to_string_lossy().to_string())
+        .collect();
+    (changed, report)
+}
+
+/// Marks an output file and the raw streams kept next to it as known.
+fn claim(claimed: &mut HashSet<PathBuf>, output_path: &Path, stream_files: &[String]) {
+    claimed.insert(output_path.to_path_buf());
+    if let Some(dir) = output_path.parent() {
+        claimed.extend(stream_files.iter().map(|name| dir.join(name)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn entry(id: &str, title: &str, output_path: Option<&Path>, size: u64) -> HistoryEntry {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": title,
+            "url": "https://www.bilibili.com/video/BV1xx411c7XD",
+            "downloadedAt": "2026-01-01T00:00:00Z",
+            "status": "completed",
+            "fileSize": size,
+            "quality": "1080P",
+            "thumbnailUrl": null,
+            "outputPath": output_path,
+            "version": "2.0"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn reconcile_relocates_marks_and_reports() {
+        let dir = std::env::temp_dir().join(format!("reconcile_test_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("moved")).unwrap();
+        let write = |name: &str, size: usize| {
+            let path = dir.join(name);
+            fs::write(&path, vec![0u8; size]).unwrap();
+            path
+        };
+        let kept = write("kept.mp4", 10);
+        let moved = write("moved/moved.mp4", 20);
+        write("moved/same-name.mp4", 31);
+        let legacy = write("Legacy.mkv", 40);
+        let orphan = write("orphan.m4a", 50);
+        write("kept.jpg", 60);
+
+        let mut was_missing = entry("restored", "kept", Some(&kept), 10);
+        was_missing.status = STATUS_MISSING.to_string();
+        let mut failed = entry("failed", "Failed", Some(&dir.join("failed.mp4")), 1);
+        failed.status = "failed".to_string();
+        let entries = vec![
+            was_missing,
+            entry("moved", "moved", Some(&dir.join("moved.mp4")), 20),
+            entry("gone", "same-name", Some(&dir.join("same-name.mp4")), 30),
+            entry("legacy", "Legacy", None, 40),
+            entry("unknown", "Unknown", None, 70),
+            failed,
+        ];
+
+        let mut media = Vec::new();
+        scan_media(&dir, 0, &mut media);
+        let (changed, report) = reconcile(entries, &media);
+
+        assert_eq!(report.checked_count, 5);
+        assert_eq!(report.restored, ["restored"]);
+        assert_eq!(
+            report.relocated,
+            [
+                RelocatedEntry {
+                    id: "moved".to_string(),
+                    previous_path: Some(dir.join("moved.mp4").to_string_lossy().to_string()),
+                    output_path: moved.to_string_lossy().to_string(),
+                },
+                RelocatedEntry {
+                    id: "legacy".to_string(),
+                    previous_path: None,
+                    output_path: legacy.to_string_lossy().to_string(),
+                },
+            ]
+        );
+        assert_eq!(report.missing, ["gone"]);
+        let mut orphans = report.orphans.clone();
+        orphans.sort();
+        assert_eq!(
+            orphans,
+            [
+                dir.join("moved/same-name.mp4")
+                    .to_string_lossy()
+                    .to_string(),
+                orphan.to_string_lossy().to_string(),
+            ]
+        );
+
+        let status = |id: &str| {
+            changed
+                .iter()
+                .find(|e| e.id == id)
+                .map(|e| e.status.as_str())
+        };
+        assert_eq!(status("restored"), Some("completed"));
+        assert_eq!(status("moved"), Some("completed"));
+        assert_eq!(status("gone"), Some(STATUS_MISSING));
+        assert_eq!(status("unknown"), None);
+        assert_eq!(status("failed"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
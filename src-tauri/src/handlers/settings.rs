@@ -5,13 +5,23 @@
 
 use std::{fs, path::PathBuf};
 
-use crate::{models::settings::Settings, utils::paths};
+use crate::{
+    models::settings::Settings,
+    utils::{
+        bandwidth::BANDWIDTH_LIMITER,
+        downloads::SharedDownloadClient,
+        filename_template::{self, TemplateValues},
+        paths, proxy,
+    },
+};
 use tauri::{AppHandle, Manager};
 
 /// Saves application settings to the settings.json file.
 ///
 /// This function validates that the download output path exists and is a
 /// directory before saving. Settings are serialized as pretty-printed JSON.
+/// The bandwidth limit is applied to running downloads immediately, and a
+/// proxy change rebuilds the shared download client so new downloads use it.
 ///
 /// # Arguments
 ///
@@ -28,6 +38,8 @@ use tauri::{AppHandle, Manager};
 /// - Download path is not set (`ERR:SETTINGS_PATH_NOT_SET`)
 /// - Download path does not exist (`ERR:SETTINGS_PATH_NOT_EXIST`)
 /// - Download path is not a directory (`ERR:SETTINGS_PATH_NOT_DIRECTORY`)
+/// - Proxy settings are invalid (`ERR:SETTINGS_PROXY_INVALID`)
+/// - Filename template is invalid (`ERR:SETTINGS_TEMPLATE_INVALID`)
 /// - JSON serialization fails
 /// - File write fails
 pub async fn set_settings(app: &AppHandle, settings: &Settings) -> Result<(), String> {
@@ -48,9 +60,24 @@ pub async fn set_settings(app: &AppHandle, settings: &Settings) -> Result<(), St
     if !dl_output_dir_path.is_dir() {
         return Err("ERR:SETTINGS_PATH_NOT_DIRECTORY".to_string());
     }
+    if let Some(ref p) = settings.proxy {
+        proxy::validate(p)?;
+    }
+    if let Some(template) = settings.filename_template.as_deref() {
+        filename_template::validate_template(template).map_err(|e| {
+            log::warn!("[BE] set_settings: invalid filename template: {}", e);
+            "ERR:SETTINGS_TEMPLATE_INVALID".to_string()
+        })?;
+    }
 
     fs::write(&filepath, settings_str)
         .map_err(|e| format!("Failed to write settings.json: {}", e))?;
+    BANDWIDTH_LIMITER.apply_settings(settings);
+    if proxy::apply_settings(settings) {
+        if let Some(client) = app.try_state::<SharedDownloadClient>() {
+            client.rebuild();
+        }
+    }
 
     Ok(())
 }
@@ -124,3 +151,37 @@ pub async fn get_settings(app: &AppHandle) -> Result<Settings, String> {
 
     Ok(settings)
 }
+
+/// Renders a filename template for preview.
+///
+/// Uses the title replacement rules and output container of the current
+/// settings, and example values when `values` is `None`.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for settings access
+/// * `template` - Template to render (need not be saved yet)
+/// * `values` - Values of a real part, or `None` for example values
+///
+/// # Returns
+///
+/// The path relative to the download directory, with extension.
+///
+/// # Errors
+///
+/// Returns the `ERR::TEMPLATE_*` error of an invalid template.
+pub async fn preview_filename_template(
+    app: &AppHandle,
+    template: &str,
+    values: Option<TemplateValues>,
+) -> Result<String, String> {
+    let settings = get_settings(app).await?;
+    let values = values.unwrap_or_else(filename_template::example_values);
+    let rendered = filename_template::render_template(
+        template,
+        &values,
+        settings.title_replacements.as_deref(),
+    )?;
+    let extension = settings.output_container.unwrap_or_default().extension();
+    Ok(format!("{rendered}.{extension}"))
+}
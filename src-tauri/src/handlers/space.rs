@@ -0,0 +1,410 @@
+//! Bilibili Uploader Space Module
+//!
+//! This module lists and batch-downloads all videos of an uploader (UP主):
+//!
+//! ## Main Features
+//!
+//! - **Space Listing**: Pages through an uploader's videos with publish date,
+//!   duration and part count
+//! - **Batch Enqueue**: Adds all videos, or a keyword/date-filtered subset,
+//!   to the persistent download queue
+//!
+//! ## API Endpoints
+//!
+//! - Space videos: `GET https://api.bilibili.com/x/space/wbi/arc/search`
+//!   (WBI-signed)
+//! - Part count: `GET https://api.bilibili.com/x/web-interface/view`
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use futures::stream::{self, StreamExt};
+use reqwest::header;
+use reqwest::Client;
+use serde::Deserialize;
+use tauri::AppHandle;
+
+use crate::handlers::bilibili::{self, build_client, build_cookie_header_from_cache};
+use crate::handlers::cookie::read_cookie;
+use crate::handlers::queue::{self, QueueJob};
+use crate::models::bilibili_api::{
+    SpaceArcSearchApiResponse, SpaceArcSearchData, SpaceArcSearchVideo,
+};
+use crate::models::frontend_dto::{SpaceVideo, SpaceVideoListResponse};
+use crate::utils::download_archive::ArchiveMode;
+
+const SPACE_SEARCH_URL: &str = "https://api.bilibili.com/x/space/wbi/arc/search";
+
+/// Largest page size accepted by the space search API.
+const MAX_PAGE_SIZE: i32 = 50;
+
+/// Parallel view lookups used to fill in part counts for one page.
+const PART_COUNT_CONCURRENCY: usize = 4;
+
+/// Delay between page requests while walking a whole space.
+///
+/// Why: the space search endpoint answers rapid paging with risk-control
+///   code -412/-352 well before other endpoints do.
+const PAGE_DELAY: Duration = Duration::from_millis(500);
+
+/// Filter applied when enqueueing an uploader's videos.
+///
+/// All conditions are combined with AND. An empty filter selects every
+/// video in the space.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceDownloadFilter {
+    /// Case-insensitive title keyword. Also sent to the API to narrow the
+    /// listing server-side.
+    #[serde(default)]
+    pub keyword: Option<String>,
+    /// Only videos published at or after this Unix timestamp (seconds).
+    #[serde(default)]
+    pub published_after: Option<i64>,
+    /// Only videos published at or before this Unix timestamp (seconds).
+    #[serde(default)]
+    pub published_before: Option<i64>,
+    /// Only these BV IDs (e.g. the rows the user ticked in the listing).
+    #[serde(default)]
+    pub bvids: Option<Vec<String>>,
+}
+
+impl SpaceDownloadFilter {
+    /// Returns `true` if `video` passes every condition of the filter.
+    fn matches(&self, video: &SpaceArcSearchVideo) -> bool {
+        if let Some(keyword) = self.keyword().map(str::to_lowercase) {
+            if !video.title.to_lowercase().contains(&keyword) {
+                return false;
+            }
+        }
+        if self.published_after.is_some_and(|t| video.created < t) {
+            return false;
+        }
+        if self.published_before.is_some_and(|t| video.created > t) {
+            return false;
+        }
+        self.bvids
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == &video.bvid))
+    }
+
+    /// Returns the trimmed keyword, or `None` when empty.
+    fn keyword(&self) -> Option<&str> {
+        self.keyword
+            .as_deref()
+            .map(str::trim)
+            .filter(|k| !k.is_empty())
+    }
+}
+
+/// Fetches one page of an uploader's videos.
+///
+/// Videos are ordered newest first. Part counts are filled in with a
+/// `view` lookup per video, since the space API does not report them.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for accessing cookie cache
+/// * `mid` - Uploader's member ID
+/// * `page_num` - Page number (1-indexed)
+/// * `page_size` - Number of items per page (clamped to 1-50)
+/// * `keyword` - Optional title keyword to search within the space
+///
+/// # Returns
+///
+/// `SpaceVideoListResponse` containing:
+/// - `videos`: Videos on this page
+/// - `has_more`: Whether later pages exist
+/// - `total_count`: Total number of matching videos in the space
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - WBI mixin key cannot be fetched
+/// - `ERR::RATE_LIMITED`: Bilibili risk control rejected the request
+/// - `ERR::UNAUTHORIZED`: The API requires login
+/// - Network request fails or the response cannot be parsed
+pub async fn fetch_space_videos(
+    app: &AppHandle,
+    mid: i64,
+    page_num: i32,
+    page_size: i32,
+    keyword: Option<String>,
+) -> Result<SpaceVideoListResponse, String> {
+    log::info!(
+        "[BE] fetch_space_videos: requesting mid={}, page={}, size={}",
+        mid,
+        page_num,
+        page_size
+    );
+    let client = build_client()?;
+    let cookie_header = build_cookie_header_from_cache(app).ok();
+    let mixin_key = crate::utils::wbi::fetch_mixin_key(&client, cookie_header.as_deref()).await?;
+
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let data = search_page(
+        &client,
+        cookie_header.as_deref(),
+        &mixin_key,
+        mid,
+        page_num.max(1),
+        page_size,
+        keyword.as_deref(),
+    )
+    .await?;
+
+    let total_count = data.page.count;
+    let has_more = data.page.pn * data.page.ps < total_count;
+
+    let cookies = read_cookie(app)?.unwrap_or_default();
+    let videos = stream::iter(data.list.vlist)
+        .map(|v| {
+            let cookies = &cookies;
+            async move {
+                // A failed lookup only hides the part count; the video is
+                // still listed and can be enqueued.
+                let part_count = bilibili::fetch_video_title_by_bvid(&v.bvid, cookies)
+                    .await
+                    .ok()
+                    .and_then(|r| r.data)
+                    .map(|d| d.pages.map(|p| p.len() as i32).unwrap_or(1));
+                SpaceVideo {
+                    aid: v.aid,
+                    bvid: v.bvid,
+                    title: v.title,
+                    cover: v.pic,
+                    pubdate: v.created,
+                    duration: parse_length(&v.length),
+                    part_count,
+                }
+            }
+        })
+        .buffered(PART_COUNT_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await;
+
+    Ok(SpaceVideoListResponse {
+        videos,
+        has_more,
+        total_count,
+    })
+}
+
+/// Enqueues all videos of an uploader that match `filter`.
+///
+/// Walks every page of the space, keeps the videos passing the filter, and
+/// adds each of their parts to the download queue at the best available
+/// quality. Videos whose details cannot be fetched are skipped and logged.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `mid` - Uploader's member ID
+/// * `filter` - Keyword, publish date range and/or explicit BV ID subset
+/// * `archive_mode` - How items already in the download archive are handled
+///
+/// # Returns
+///
+/// The queue jobs that were added (one per video part).
+///
+/// # Errors
+///
+/// Returns an error if the space listing itself fails (see
+/// [`fetch_space_videos`]). Per-video failures do not abort the batch.
+pub async fn enqueue_space_videos(
+    app: &AppHandle,
+    mid: i64,
+    filter: SpaceDownloadFilter,
+    archive_mode: ArchiveMode,
+) -> Result<Vec<QueueJob>, String> {
+    log::info!(
+        "[BE] enqueue_space_videos: mid={}, filter={:?}",
+        mid,
+        filter
+    );
+    let client = build_client()?;
+    let cookie_header = build_cookie_header_from_cache(app).ok();
+    let mixin_key = crate::utils::wbi::fetch_mixin_key(&client, cookie_header.as_deref()).await?;
+
+    let mut selected: Vec<String> = Vec::new();
+    let mut page_num = 1;
+    loop {
+        let data = search_page(
+            &client,
+            cookie_header.as_deref(),
+            &mixin_key,
+            mid,
+            page_num,
+            MAX_PAGE_SIZE,
+            filter.keyword(),
+        )
+        .await?;
+        let reached_end = data.page.pn * data.page.ps >= data.page.count;
+        // Pages are ordered newest first, so once a page ends before the
+        // lower date bound no later page can match.
+        let past_range = filter.published_after.is_some_and(|after| {
+            data.list
+                .vlist
+                .last()
+                .is_some_and(|last| last.created < after)
+        });
+        if data.list.vlist.is_empty() {
+            break;
+        }
+        selected.extend(
+            data.list
+                .vlist
+                .iter()
+                .filter(|v| filter.matches(v))
+                .map(|v| v.bvid.clone()),
+        );
+        if reached_end || past_range {
+            break;
+        }
+        page_num += 1;
+        tokio::time::sleep(PAGE_DELAY).await;
+    }
+    log::info!(
+        "[BE] enqueue_space_videos: {} video(s) matched",
+        selected.len()
+    );
+
+    let mut options = Vec::new();
+    for bvid in selected {
+        match bilibili::fetch_video_info(app, &bvid).await {
+            Ok(video) => options.extend(queue::part_download_options(&video)),
+            Err(e) => log::warn!("[BE] enqueue_space_videos: skipping bvid={}: {}", bvid, e),
+        }
+    }
+    Ok(queue::enqueue_many(app, options, archive_mode).await)
+}
+
+/// Requests one WBI-signed page of the space search API.
+async fn search_page(
+    client: &Client,
+    cookie_header: Option<&str>,
+    mixin_key: &str,
+    mid: i64,
+    page_num: i32,
+    page_size: i32,
+    keyword: Option<&str>,
+) -> Result<SpaceArcSearchData, String> {
+    let mut params = BTreeMap::from([
+        ("mid".to_string(), mid.to_string()),
+        ("pn".to_string(), page_num.to_string()),
+        ("ps".to_string(), page_size.to_string()),
+        ("order".to_string(), "pubdate".to_string()),
+        ("keyword".to_string(), keyword.unwrap_or("").to_string()),
+        // Why: without the dm_* browser fingerprint fields the endpoint
+        //   rejects signed requests with -352 (risk control). Static values
+        //   describing a generic WebGL renderer are accepted.
+        ("dm_img_list".to_string(), "[]".to_string()),
+        (
+            "dm_img_str".to_string(),
+            "V2ViR0wgMS4wIChPcGVuR0wgRVMgMi4wIENocm9taXVtKQ".to_string(),
+        ),
+        (
+            "dm_cover_img_str".to_string(),
+            "QU5HTEUgKEludGVsLCBJbnRlbChSKSBIRCBHcmFwaGljcyBEaXJlY3QzRDExKQ".to_string(),
+        ),
+    ]);
+    let signature = crate::utils::wbi::generate_wbi_signature(&mut params, mixin_key);
+
+    let mut query: Vec<(&str, String)> = params
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.clone()))
+        .collect();
+    query.push(("w_rid", signature.w_rid));
+
+    let mut req = client
+        .get(SPACE_SEARCH_URL)
+        .header(
+            header::REFERER,
+            format!("https://space.bilibili.com/{mid}/video"),
+        )
+        .query(&query);
+    if let Some(c) = cookie_header {
+        req = req.header(header::COOKIE, c);
+    }
+    let response = req
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch space videos: {e}"))?
+        .json::<SpaceArcSearchApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse space videos response: {e}"))?;
+
+    match response.code {
+        0 => {}
+        -101 => return Err("ERR::UNAUTHORIZED".into()),
+        -352 | -412 => return Err("ERR::RATE_LIMITED".into()),
+        code => return Err(format!("API error (code {}): {}", code, response.message)),
+    }
+    response
+        .data
+        .ok_or_else(|| "No data in response".to_string())
+}
+
+/// Parses a space video length (`"mm:ss"` or `"h:mm:ss"`) into seconds.
+///
+/// Returns 0 for malformed values.
+fn parse_length(length: &str) -> i64 {
+    length
+        .split(':')
+        .try_fold(0i64, |acc, part| {
+            part.trim().parse::<i64>().ok().map(|n| acc * 60 + n)
+        })
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn video(bvid: &str, title: &str, created: i64) -> SpaceArcSearchVideo {
+        SpaceArcSearchVideo {
+            aid: 1,
+            bvid: bvid.to_string(),
+            title: title.to_string(),
+            pic: String::new(),
+            created,
+            length: "01:00".to_string(),
+            author: String::new(),
+            mid: 1,
+        }
+    }
+
+    #[test]
+    fn parse_length_handles_minutes_and_hours() {
+        assert_eq!(parse_length("03:25"), 205);
+        assert_eq!(parse_length("1:02:03"), 3723);
+        assert_eq!(parse_length("--"), 0);
+    }
+
+    #[test]
+    fn empty_filter_matches_everything() {
+        let filter = SpaceDownloadFilter::default();
+        assert!(filter.matches(&video("BV1", "Anything", 0)));
+    }
+
+    #[test]
+    fn filter_combines_keyword_date_and_ids() {
+        let filter = SpaceDownloadFilter {
+            keyword: Some(" vlog ".to_string()),
+            published_after: Some(100),
+            published_before: Some(200),
+            bvids: None,
+        };
+        assert!(filter.matches(&video("BV1", "Tokyo VLOG #3", 150)));
+        assert!(!filter.matches(&video("BV2", "Tutorial", 150)));
+        assert!(!filter.matches(&video("BV3", "vlog", 99)));
+        assert!(!filter.matches(&video("BV4", "vlog", 201)));
+
+        let filter = SpaceDownloadFilter {
+            bvids: Some(vec!["BV2".to_string()]),
+            ..Default::default()
+        };
+        assert!(!filter.matches(&video("BV1", "a", 0)));
+        assert!(filter.matches(&video("BV2", "b", 0)));
+    }
+}
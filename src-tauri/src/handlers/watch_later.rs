@@ -0,0 +1,250 @@
+//! Bilibili Watch Later (稍后再看) Module
+//!
+//! This module lists and batch-downloads the logged-in user's Watch Later
+//! list:
+//!
+//! ## Main Features
+//!
+//! - **Listing**: Fetches the whole Watch Later list in one request
+//! - **Batch Enqueue**: Adds all items, or a selected subset, to the
+//!   persistent download queue
+//! - **Remove After Download**: Optionally deletes an item from Watch Later
+//!   once every part of it has been downloaded by the queue
+//!
+//! ## API Endpoints
+//!
+//! - List: `GET https://api.bilibili.com/x/v2/history/toview`
+//! - Delete: `POST https://api.bilibili.com/x/v2/history/toview/del`
+
+use reqwest::header;
+use tauri::AppHandle;
+
+use crate::constants::REFERER;
+use crate::handlers::bilibili::{self, build_client, build_cookie_header_from_cache};
+use crate::handlers::cookie::read_cookie;
+use crate::handlers::queue::{self, QueueJob};
+use crate::models::bilibili_api::{StatusApiResponse, WatchLaterApiItem, WatchLaterApiResponse};
+use crate::models::frontend_dto::{WatchLaterEntry, WatchLaterResponse};
+use crate::utils::download_archive::ArchiveMode;
+
+const WATCH_LATER_URL: &str = "https://api.bilibili.com/x/v2/history/toview";
+const WATCH_LATER_DEL_URL: &str = "https://api.bilibili.com/x/v2/history/toview/del";
+
+/// Fetches the Watch Later list of the logged-in user.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for accessing cookie cache
+///
+/// # Returns
+///
+/// `WatchLaterResponse` containing:
+/// - `entries`: Watch Later items, most recently added first
+/// - `total_count`: Number of items in the list
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `ERR::COOKIE_MISSING`: Authentication cookies are not available
+/// - `ERR::UNAUTHORIZED`: The user is not logged in
+/// - Network request fails or the response cannot be parsed
+pub async fn fetch_watch_later(app: &AppHandle) -> Result<WatchLaterResponse, String> {
+    log::info!("[BE] fetch_watch_later: requesting list");
+    let cookie_header = build_cookie_header_from_cache(app)?;
+
+    let client = build_client()?;
+    let response = client
+        .get(WATCH_LATER_URL)
+        .header(header::COOKIE, &cookie_header)
+        .header(header::REFERER, REFERER)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch watch later list: {e}"))?
+        .json::<WatchLaterApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse watch later response: {e}"))?;
+
+    if response.code == -101 {
+        return Err("ERR::UNAUTHORIZED".into());
+    }
+    if response.code != 0 {
+        return Err(format!(
+            "API error (code {}): {}",
+            response.code, response.message
+        ));
+    }
+
+    let data = response.data.ok_or("No data in response")?;
+    let entries: Vec<WatchLaterEntry> = data
+        .list
+        .unwrap_or_default()
+        .into_iter()
+        .map(to_entry)
+        .collect();
+
+    Ok(WatchLaterResponse {
+        total_count: data.count.max(entries.len() as i64),
+        entries,
+    })
+}
+
+/// Enqueues Watch Later items for download.
+///
+/// Each item is resolved with `fetch_video_info` and all of its parts are
+/// queued at the best available quality.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `bvids` - Optional subset of items to enqueue (all when `None`)
+/// * `remove_after_download` - Delete each item from Watch Later once all
+///   of its parts have completed
+/// * `archive_mode` - How items already in the download archive are handled
+///
+/// # Returns
+///
+/// The queue jobs that were added (one per video part).
+///
+/// # Errors
+///
+/// Returns an error if the list itself cannot be fetched (see
+/// [`fetch_watch_later`]). Per-video failures do not abort the batch.
+pub async fn enqueue_watch_later(
+    app: &AppHandle,
+    bvids: Option<Vec<String>>,
+    remove_after_download: bool,
+    archive_mode: ArchiveMode,
+) -> Result<Vec<QueueJob>, String> {
+    log::info!(
+        "[BE] enqueue_watch_later: bvids={:?}, remove_after_download={}",
+        bvids,
+        remove_after_download
+    );
+    let list = fetch_watch_later(app).await?;
+
+    let mut options = Vec::new();
+    for entry in list
+        .entries
+        .iter()
+        .filter(|e| bvids.as_ref().is_none_or(|ids| ids.contains(&e.bvid)))
+    {
+        match bilibili::fetch_video_info(app, &entry.bvid).await {
+            Ok(video) => {
+                options.extend(
+                    queue::part_download_options(&video)
+                        .into_iter()
+                        .map(|mut opts| {
+                            if remove_after_download {
+                                opts.watch_later_aid = Some(entry.aid);
+                            }
+                            opts
+                        }),
+                );
+            }
+            Err(e) => log::warn!(
+                "[BE] enqueue_watch_later: skipping bvid={}: {}",
+                entry.bvid,
+                e
+            ),
+        }
+    }
+    Ok(queue::enqueue_many(app, options, archive_mode).await)
+}
+
+/// Removes a video from the Watch Later list.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `aid` - AV number of the item to remove
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - `ERR::COOKIE_MISSING`: The `bili_jct` CSRF cookie is not available
+/// - `ERR::UNAUTHORIZED`: The user is not logged in
+/// - Network request fails or the API returns a non-zero code
+pub async fn remove_from_watch_later(app: &AppHandle, aid: i64) -> Result<(), String> {
+    log::info!("[BE] remove_from_watch_later: aid={}", aid);
+    let cookie_header = build_cookie_header_from_cache(app)?;
+    let csrf = read_cookie(app)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|c| c.name == "bili_jct")
+        .map(|c| c.value)
+        .ok_or("ERR::COOKIE_MISSING")?;
+
+    let client = build_client()?;
+    let response = client
+        .post(WATCH_LATER_DEL_URL)
+        .header(header::COOKIE, &cookie_header)
+        .header(header::REFERER, REFERER)
+        .form(&[("aid", aid.to_string()), ("csrf", csrf)])
+        .send()
+        .await
+        .map_err(|e| format!("Failed to remove watch later item: {e}"))?
+        .json::<StatusApiResponse>()
+        .await
+        .map_err(|e| format!("Failed to parse watch later delete response: {e}"))?;
+
+    match response.code {
+        0 => Ok(()),
+        -101 => Err("ERR::UNAUTHORIZED".into()),
+        code => Err(format!("API error (code {}): {}", code, response.message)),
+    }
+}
+
+/// Converts an API item into the frontend DTO.
+fn to_entry(item: WatchLaterApiItem) -> WatchLaterEntry {
+    WatchLaterEntry {
+        url: format!("https://www.bilibili.com/video/{}", item.bvid),
+        title: item.title,
+        cover: item.pic,
+        aid: item.aid,
+        bvid: item.bvid,
+        cid: item.cid,
+        part_count: item.videos.max(1),
+        added_at: item.add_at,
+        duration: item.duration,
+        progress: item.progress,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn api_item_maps_to_entry() {
+        let item: WatchLaterApiItem = serde_json::from_value(json!({
+            "aid": 170001,
+            "bvid": "BV17x411w7KC",
+            "title": "Lecture",
+            "pic": "p.jpg",
+            "duration": 600,
+            "videos": 3,
+            "cid": 279786,
+            "add_at": 1700000000,
+            "progress": -1
+        }))
+        .unwrap();
+
+        let entry = to_entry(item);
+        assert_eq!(entry.url, "https://www.bilibili.com/video/BV17x411w7KC");
+        assert_eq!(entry.part_count, 3);
+        assert_eq!(entry.added_at, 1700000000);
+        assert_eq!(entry.progress, -1);
+    }
+
+    #[test]
+    fn missing_optional_fields_default() {
+        let item: WatchLaterApiItem = serde_json::from_value(json!({
+            "aid": 1, "bvid": "BV1", "title": "t", "pic": "", "duration": 1
+        }))
+        .unwrap();
+        let entry = to_entry(item);
+        assert_eq!(entry.part_count, 1);
+        assert_eq!(entry.cid, 0);
+    }
+}
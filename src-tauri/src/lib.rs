@@ -5,35 +5,48 @@
 //! downloads, cookie management, ffmpeg integration, and user settings.
 
 use std::path::PathBuf;
-use std::sync::Arc;
 
 use tauri::AppHandle;
 use tauri::Manager;
 
 use crate::handlers::audio;
+use crate::handlers::bangumi;
 use crate::handlers::bilibili;
 use crate::handlers::cleanup;
+use crate::handlers::collection;
 use crate::handlers::concat;
 use crate::handlers::cookie;
+use crate::handlers::danmaku;
 use crate::handlers::favorites;
 use crate::handlers::ffmpeg;
 use crate::handlers::github;
 use crate::handlers::init;
+use crate::handlers::live;
 use crate::handlers::qr_login;
+use crate::handlers::queue;
+use crate::handlers::reconcile;
 use crate::handlers::resolution;
 use crate::handlers::rotation;
 use crate::handlers::settings;
+use crate::handlers::space;
 use crate::handlers::trim;
 use crate::handlers::updater;
+use crate::handlers::watch_later;
 use crate::models::cookie::CookieCache;
 #[cfg(debug_assertions)]
 use crate::models::cookie::SimulateLogoutFlag;
+use crate::models::frontend_dto::DanmakuFiles;
 use crate::models::frontend_dto::FavoriteFolder;
+use crate::models::frontend_dto::FavoriteSyncCursor;
 use crate::models::frontend_dto::FavoriteVideoListResponse;
+use crate::models::frontend_dto::LiveRoomInfo;
 use crate::models::frontend_dto::Quality;
+use crate::models::frontend_dto::SpaceVideoListResponse;
 use crate::models::frontend_dto::SubtitleDto;
 use crate::models::frontend_dto::User;
 use crate::models::frontend_dto::Video;
+use crate::models::frontend_dto::VideoCollection;
+use crate::models::frontend_dto::WatchLaterResponse;
 use crate::models::history::HistoryEntry;
 use crate::models::history::HistoryFilters;
 use crate::models::qr_login::CookieRefreshInfo;
@@ -46,6 +59,8 @@ use crate::models::settings::Language;
 use crate::models::settings::Settings;
 use crate::models::settings::UiTheme;
 use crate::store::HistoryStore;
+use crate::utils::danmaku::DanmakuOptions;
+use crate::utils::download_archive::ArchiveMode;
 
 pub mod constants;
 pub mod emits;
@@ -87,7 +102,8 @@ pub use utils::wbi;
 /// **User & Video Information:**
 /// - `fetch_user`: Fetches user information from Bilibili
 /// - `fetch_video_info`: Retrieves video metadata
-/// - `fetch_bangumi_info`: Retrieves bangumi episode metadata
+/// - `fetch_bangumi_info`: Retrieves bangumi metadata by episode, season or media ID
+/// - `enqueue_bangumi_season`: Queues all downloadable episodes of a bangumi season
 /// - `fetch_subtitles_for_part`: Fetches subtitles for a video part
 /// - `fetch_part_qualities`: Fetches video/audio qualities for a part
 /// - `fetch_bangumi_part_qualities`: Fetches qualities for a bangumi part
@@ -97,23 +113,47 @@ pub use utils::wbi;
 /// - `download_video`: Downloads a video with specified quality
 /// - `cancel_download`: Cancels a specific download by ID
 /// - `cancel_all_downloads`: Cancels all active downloads
+/// - `enqueue_download`: Adds a download to the persistent backend queue
+/// - `get_download_queue`: Lists queued download jobs
+/// - `pause_download` / `resume_download`: Pauses or resumes a queued job
+/// - `pause_all_downloads` / `resume_all_downloads`: Pauses or resumes all jobs
+/// - `remove_queue_job`: Removes a job from the queue (cancelling it if running)
+/// - `clear_finished_queue_jobs`: Removes completed, failed and cancelled jobs
 /// - `cleanup_temp_files`: Cleans up orphaned temporary files
 /// - `trim_video`: Losslessly trims a local MP4 file by start/end time
+/// - `download_danmaku`: Saves the danmaku of a part as raw XML and ASS files
+///
+/// **Live:**
+/// - `fetch_live_room`: Resolves a live room and its broadcast status
+/// - `start_live_recording`: Starts recording a live room in the background
+/// - `stop_live_recording`: Stops a recording (remuxing chunks if requested)
+/// - `get_live_recordings`: Lists recordings that have not finished
 ///
 /// **Favorites & History:**
 /// - `fetch_favorite_folders`: Fetches all favorite folders
 /// - `fetch_favorite_videos`: Fetches videos from a favorite folder
+/// - `sync_favorite_folder`: Queues favorites not yet downloaded or queued
+/// - `get_favorite_sync_cursors`: Lists the last sync state of each folder
+/// - `fetch_space_videos`: Fetches an uploader's videos with pagination
+/// - `enqueue_space_videos`: Queues all (or filtered) videos of an uploader
+/// - `fetch_series`: Fetches an uploader series with all of its videos
+/// - `enqueue_collection`: Queues a collection or series in episode order
 /// - `fetch_watch_history`: Fetches user watch history with pagination
+/// - `fetch_watch_later`: Fetches the Watch Later (稍后再看) list
+/// - `enqueue_watch_later`: Queues all (or selected) Watch Later items
 /// - `get_history`: Retrieves all download history entries
 /// - `add_history_entry`: Adds a new history entry
 /// - `remove_history_entry`: Removes a history entry by ID
 /// - `clear_history`: Clears all history entries
 /// - `search_history`: Searches history with filters
 /// - `export_history`: Exports history in JSON or CSV format
+/// - `import_download_archive`: Adds completed history entries to the download archive
+/// - `reconcile_history`: Checks history entries against the files on disk
 ///
 /// **Settings & Paths:**
 /// - `get_settings`: Retrieves application settings
 /// - `set_settings`: Updates application settings
+/// - `preview_filename_template`: Renders a filename template for preview
 /// - `update_lib_path`: Updates library storage path and moves ffmpeg
 /// - `get_current_lib_path`: Returns the current library path
 /// - `get_os`: Returns the current operating system identifier
@@ -165,14 +205,24 @@ pub fn run() {
             fetch_user,
             fetch_video_info,
             fetch_bangumi_info,
+            enqueue_bangumi_season,
             fetch_subtitles_for_part,
             fetch_part_qualities,
             fetch_bangumi_part_qualities,
             download_video,
             cancel_download,
             cancel_all_downloads,
+            enqueue_download,
+            get_download_queue,
+            pause_download,
+            resume_download,
+            pause_all_downloads,
+            resume_all_downloads,
+            remove_queue_job,
+            clear_finished_queue_jobs,
             get_settings,
             set_settings,
+            preview_filename_template,
             update_lib_path,
             get_current_lib_path,
             get_os,
@@ -183,6 +233,8 @@ pub fn run() {
             clear_history,
             search_history,
             export_history,
+            import_download_archive,
+            reconcile_history,
             reveal_in_folder,
             open_file,
             get_release_notes,
@@ -190,10 +242,23 @@ pub fn run() {
             get_repo_stars,
             fetch_favorite_folders,
             fetch_favorite_videos,
+            sync_favorite_folder,
+            get_favorite_sync_cursors,
+            fetch_space_videos,
+            enqueue_space_videos,
+            fetch_series,
+            enqueue_collection,
             fetch_watch_history,
+            fetch_watch_later,
+            enqueue_watch_later,
             expand_short_url,
             cleanup_temp_files,
             trim_video,
+            download_danmaku,
+            fetch_live_room,
+            start_live_recording,
+            stop_live_recording,
+            get_live_recordings,
             rotate_video,
             concat_videos,
             extract_audio,
@@ -244,6 +309,12 @@ pub fn run() {
                     .await
                     .ok()
             });
+            if let Some(ref s) = settings {
+                crate::utils::bandwidth::BANDWIDTH_LIMITER.apply_settings(s);
+                // Must run before any client is built below so the shared
+                // download client starts with the configured proxy.
+                crate::utils::proxy::apply_settings(s);
+            }
             let window_theme = settings
                 .as_ref()
                 .and_then(|s| s.theme.clone())
@@ -381,9 +452,9 @@ pub fn run() {
             //   instead of building (and tearing down) a new client per
             //   download as before (issue #491).
             // Connection pool is tuned in build_download_client() (fixed at max
-            //   segment concurrency, see WHY docs there).
-            let client = crate::utils::downloads::build_download_client();
-            app.manage(Arc::new(client));
+            //   segment concurrency, see WHY docs there). Wrapped in
+            //   SharedDownloadClient so a proxy change can rebuild it.
+            app.manage(crate::utils::downloads::SharedDownloadClient::new());
             log::info!("[BE] Shared HTTP client initialized");
 
             // Development mode: Initialize simulate logout flag
@@ -729,6 +800,119 @@ async fn cancel_all_downloads(app: AppHandle, download_ids: Vec<String>) -> Resu
     Ok(count)
 }
 
+/// Adds a download to the persistent backend queue.
+///
+/// The job starts as soon as a running slot is free and survives app
+/// restarts. Progress is reported through the usual `progress` events, and
+/// every job state change emits a `queue:updated` event with the full list.
+///
+/// # Arguments
+///
+/// * `options` - Download options (same shape as `download_video`);
+///   `downloadId` becomes the job ID
+///
+/// # Returns
+///
+/// Returns the queued job.
+///
+/// # Errors
+///
+/// Returns `ERR::QUEUE_DUPLICATE` if an unfinished job with the same ID exists.
+///
+/// # Example
+///
+/// ```typescript
+/// const job = await invoke<QueueJob>('enqueue_download', { options });
+/// ```
+#[tauri::command]
+async fn enqueue_download(
+    app: AppHandle,
+    options: bilibili::DownloadOptions,
+) -> Result<queue::QueueJob, String> {
+    queue::enqueue(&app, options).await
+}
+
+/// Returns all jobs in the backend download queue, in queue order.
+#[tauri::command]
+async fn get_download_queue() -> Result<Vec<queue::QueueJob>, String> {
+    Ok(queue::get_jobs().await)
+}
+
+/// Pauses a queued download.
+///
+/// A running transfer is stopped but its completed segments are kept, so
+/// `resume_download` continues where it left off.
+///
+/// # Arguments
+///
+/// * `download_id` - ID of the queued job
+///
+/// # Returns
+///
+/// Returns `Ok(true)` if the job was pending or running, `Ok(false)` otherwise.
+#[tauri::command]
+async fn pause_download(app: AppHandle, download_id: String) -> Result<bool, String> {
+    Ok(queue::pause(&app, &download_id).await)
+}
+
+/// Resumes a paused (or failed) queued download.
+///
+/// # Arguments
+///
+/// * `download_id` - ID of the queued job
+///
+/// # Returns
+///
+/// Returns `Ok(true)` if the job was moved back to pending.
+#[tauri::command]
+async fn resume_download(app: AppHandle, download_id: String) -> Result<bool, String> {
+    Ok(queue::resume(&app, &download_id).await)
+}
+
+/// Pauses every pending and running queued download.
+///
+/// # Returns
+///
+/// Returns the number of jobs paused.
+#[tauri::command]
+async fn pause_all_downloads(app: AppHandle) -> Result<usize, String> {
+    Ok(queue::pause_all(&app).await)
+}
+
+/// Resumes every paused queued download.
+///
+/// # Returns
+///
+/// Returns the number of jobs resumed.
+#[tauri::command]
+async fn resume_all_downloads(app: AppHandle) -> Result<usize, String> {
+    Ok(queue::resume_all(&app).await)
+}
+
+/// Removes a job from the backend queue, cancelling it if it is running.
+///
+/// # Arguments
+///
+/// * `download_id` - ID of the queued job
+///
+/// # Returns
+///
+/// Returns `Ok(true)` if the job existed.
+#[tauri::command]
+async fn remove_queue_job(app: AppHandle, download_id: String) -> Result<bool, String> {
+    Ok(queue::remove(&app, &download_id).await)
+}
+
+/// Removes completed, failed and cancelled jobs from the backend queue.
+///
+/// # Returns
+///
+/// Returns the number of jobs removed.
+#[tauri::command]
+async fn clear_finished_queue_jobs(app: AppHandle) -> Result<usize, String> {
+    Ok(queue::clear_finished(&app).await)
+}
+
 /// Retrieves the current application settings.
 ///
 /// This command loads settings from the settings.json file. If the file doesn't
@@ -781,6 +965,31 @@ async fn set_settings(app: AppHandle, settings: Settings) -> Result<(), String>
     settings::set_settings(&app, &settings).await
 }
 
+/// Renders a filename template so users can check it before downloading.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for settings access
+/// * `template` - Template to render
+/// * `values` - Values of a part; example values when omitted
+///
+/// # Returns
+///
+/// Returns the rendered path relative to the download directory, including
+/// the extension of the output container.
+///
+/// # Errors
+///
+/// Returns an `ERR::TEMPLATE_*` error if the template is invalid.
+#[tauri::command]
+async fn preview_filename_template(
+    app: AppHandle,
+    template: String,
+    values: Option<crate::utils::filename_template::TemplateValues>,
+) -> Result<String, String> {
+    settings::preview_filename_template(&app, &template, values).await
+}
+
 /// Updates the library storage path and moves ffmpeg to the new location.
 ///
 /// This command:
@@ -917,8 +1126,7 @@ async fn get_app_info(app: AppHandle) -> AppInfo {
 /// Returns an error if the history store cannot be accessed.
 #[tauri::command]
 async fn get_history(app: AppHandle) -> Result<Vec<HistoryEntry>, String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    Ok(store.get_all())
+    HistoryStore::run_blocking(&app, |store| Ok(store.get_all())).await
 }
 
 /// Adds a new entry to download history.
@@ -933,8 +1141,7 @@ async fn get_history(app: AppHandle) -> Result<Vec<HistoryEntry>, String> {
 /// - The history store cannot be written to
 #[tauri::command]
 async fn add_history_entry(app: AppHandle, entry: HistoryEntry) -> Result<(), String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    store.add_entry(entry)
+    HistoryStore::run_blocking(&app, move |store| store.add_entry(entry)).await
 }
 
 /// Removes a history entry by ID.
@@ -948,8 +1155,7 @@ async fn add_history_entry(app: AppHandle, entry: HistoryEntry) -> Result<(), St
 /// - The history store cannot be written to
 #[tauri::command]
 async fn remove_history_entry(app: AppHandle, id: String) -> Result<(), String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    store.remove_entry(&id)
+    HistoryStore::run_blocking(&app, move |store| store.remove_entry(&id)).await
 }
 
 /// Clears all download history entries.
@@ -961,8 +1167,7 @@ async fn remove_history_entry(app: AppHandle, id: String) -> Result<(), String>
 /// Returns an error if the history store cannot be written to.
 #[tauri::command]
 async fn clear_history(app: AppHandle) -> Result<(), String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    store.clear()
+    HistoryStore::run_blocking(&app, |store| store.clear()).await
 }
 
 /// Searches history entries with query and filters.
@@ -978,8 +1183,7 @@ async fn search_history(
     query: Option<String>,
     filters: Option<HistoryFilters>,
 ) -> Result<Vec<HistoryEntry>, String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    Ok(store.search(query, filters))
+    HistoryStore::run_blocking(&app, move |store| Ok(store.search(query, filters))).await
 }
 
 /// Escapes a string value for RFC 4180 compliant CSV output.
@@ -1015,8 +1219,7 @@ fn escape_csv(s: &str) -> String {
 /// - Serialization fails
 #[tauri::command]
 async fn export_history(app: AppHandle, format: String) -> Result<String, String> {
-    let store = HistoryStore::new(&app).map_err(|e| e.to_string())?;
-    let entries = store.get_all();
+    let entries = HistoryStore::run_blocking(&app, |store| Ok(store.get_all())).await?;
 
     match format.as_str() {
         "json" => serde_json::to_string_pretty(&entries).map_err(|e| e.to_string()),
@@ -1049,6 +1252,44 @@ async fn export_history(app: AppHandle, format: String) -> Result<String, String
     }
 }
 
+/// Adds the completed history entries to the download archive.
+///
+/// Downloads finished before the archive existed are then skipped by batch
+/// jobs like any other archived item.
+///
+/// # Returns
+///
+/// Number of items added to the archive.
+///
+/// # Errors
+///
+/// Returns an error if the history cannot be loaded or the archive cannot
+/// be written.
+#[tauri::command]
+async fn import_download_archive(app: AppHandle) -> Result<usize, String> {
+    bilibili::import_download_archive(&app).await
+}
+
+/// Reconciles the download history with the files on disk.
+///
+/// Completed entries whose file is gone are marked `missing`, files moved
+/// within the download output directory are found again by name and size,
+/// and media files there without a history entry are reported as orphans.
+///
+/// # Returns
+///
+/// A report of the relocated, restored and missing entries and the orphan
+/// files.
+///
+/// # Errors
+///
+/// Returns an error if the output path is not configured or the history
+/// store cannot be accessed.
+#[tauri::command]
+async fn reconcile_history(app: AppHandle) -> Result<reconcile::ReconcileReport, String> {
+    reconcile::reconcile_history(&app).await
+}
+
 /// Reveals a file in the system's file manager.
 ///
 /// Opens the parent folder and selects the specified file.
@@ -1270,6 +1511,158 @@ async fn fetch_favorite_videos(
         .map_err(|e| e.to_string())
 }
 
+/// Syncs a favorite folder into the download queue.
+///
+/// Walks the whole folder and enqueues only parts that are neither in the
+/// download history nor already queued, then records the folder's sync
+/// cursor.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `media_id` - Favorite folder ID
+///
+/// # Returns
+///
+/// The queued jobs, skip counts and the recorded cursor.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Cookies are unavailable (`ERR::COOKIE_MISSING`)
+/// - The folder listing fails
+#[tauri::command]
+async fn sync_favorite_folder(
+    app: AppHandle,
+    media_id: i64,
+) -> Result<favorites::FavoriteSyncReport, String> {
+    favorites::sync_favorite_folder(&app, media_id).await
+}
+
+/// Returns the last sync cursor of every synced favorite folder.
+#[tauri::command]
+fn get_favorite_sync_cursors(app: AppHandle) -> Vec<FavoriteSyncCursor> {
+    favorites::get_sync_cursors(&app)
+}
+
+/// Fetches one page of an uploader's (UP主) videos, newest first.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `mid` - Uploader's member ID
+/// * `page_num` - Page number (1-indexed)
+/// * `page_size` - Number of items per page (max 50)
+/// * `keyword` - Optional title keyword to search within the space
+///
+/// # Returns
+///
+/// Paginated list of videos with publish date, duration and part count.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Bilibili risk control rejects the request (`ERR::RATE_LIMITED`)
+/// - API request fails
+#[tauri::command]
+async fn fetch_space_videos(
+    app: AppHandle,
+    mid: i64,
+    page_num: i32,
+    page_size: i32,
+    keyword: Option<String>,
+) -> Result<SpaceVideoListResponse, String> {
+    space::fetch_space_videos(&app, mid, page_num, page_size, keyword).await
+}
+
+/// Adds all videos of an uploader, or a filtered subset, to the download
+/// queue.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `mid` - Uploader's member ID
+/// * `filter` - Optional keyword, publish date range and BV ID subset
+/// * `archive_mode` - How items already in the download archive are
+///   handled (skipped when omitted)
+///
+/// # Returns
+///
+/// The queued jobs, one per video part.
+///
+/// # Example
+///
+/// ```typescript
+/// const jobs = await invoke<QueueJob[]>('enqueue_space_videos', {
+///   mid: 12345,
+///   filter: { keyword: 'vlog', publishedAfter: 1704067200 },
+/// });
+/// ```
+#[tauri::command]
+async fn enqueue_space_videos(
+    app: AppHandle,
+    mid: i64,
+    filter: Option<space::SpaceDownloadFilter>,
+    archive_mode: Option<ArchiveMode>,
+) -> Result<Vec<queue::QueueJob>, String> {
+    space::enqueue_space_videos(
+        &app,
+        mid,
+        filter.unwrap_or_default(),
+        archive_mode.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Fetches an uploader series (系列) with all of its videos.
+///
+/// UGC collections (合集) need no separate call: they are returned in
+/// `Video.collection` by `fetch_video_info`.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `mid` - Series owner's member ID
+/// * `series_id` - Series ID
+///
+/// # Returns
+///
+/// The series with its episodes in publish order.
+///
+/// # Errors
+///
+/// Returns an error if the API request fails.
+#[tauri::command]
+async fn fetch_series(app: AppHandle, mid: i64, series_id: i64) -> Result<VideoCollection, String> {
+    collection::fetch_series(&app, mid, series_id).await
+}
+
+/// Adds the episodes of a collection or series to the download queue.
+///
+/// Filenames are prefixed with the collection title and episode number so
+/// the files sort in episode order.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `collection` - Collection from `Video.collection` or `fetch_series`
+/// * `bvids` - Optional subset of episodes (all when omitted)
+/// * `archive_mode` - How items already in the download archive are
+///   handled (skipped when omitted)
+///
+/// # Returns
+///
+/// The queued jobs, one per video part.
+#[tauri::command]
+async fn enqueue_collection(
+    app: AppHandle,
+    collection: VideoCollection,
+    bvids: Option<Vec<String>>,
+    archive_mode: Option<ArchiveMode>,
+) -> Result<Vec<queue::QueueJob>, String> {
+    collection::enqueue_collection(&app, &collection, bvids, archive_mode.unwrap_or_default()).await
+}
+
 /// Fetches watch history from Bilibili with pagination support.
 ///
 /// This command retrieves the user's viewing history from Bilibili.
@@ -1303,6 +1696,57 @@ async fn fetch_watch_history(
     bilibili::fetch_watch_history(&app, max, view_at).await
 }
 
+/// Fetches the Watch Later (稍后再看) list of the logged-in user.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+///
+/// # Returns
+///
+/// All Watch Later items with the list size.
+///
+/// # Errors
+///
+/// Returns an error if:
+/// - Cookies are unavailable (`ERR::COOKIE_MISSING`)
+/// - User is not logged in (`ERR::UNAUTHORIZED`)
+/// - API request fails
+#[tauri::command]
+async fn fetch_watch_later(app: AppHandle) -> Result<WatchLaterResponse, String> {
+    watch_later::fetch_watch_later(&app).await
+}
+
+/// Adds Watch Later items to the download queue.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `bvids` - Optional subset of items (all when omitted)
+/// * `remove_after_download` - Remove each item from Watch Later once all
+///   of its parts have downloaded
+/// * `archive_mode` - How items already in the download archive are
+///   handled (skipped when omitted)
+///
+/// # Returns
+///
+/// The queued jobs, one per video part.
+#[tauri::command]
+async fn enqueue_watch_later(
+    app: AppHandle,
+    bvids: Option<Vec<String>>,
+    remove_after_download: Option<bool>,
+    archive_mode: Option<ArchiveMode>,
+) -> Result<Vec<queue::QueueJob>, String> {
+    watch_later::enqueue_watch_later(
+        &app,
+        bvids,
+        remove_after_download.unwrap_or(false),
+        archive_mode.unwrap_or_default(),
+    )
+    .await
+}
+
 /// Cleans up orphaned temporary files from interrupted downloads.
 ///
 /// Scans the lib directory for temp files matching:
@@ -1339,6 +1783,81 @@ async fn trim_video(
     trim::trim_video(&app, &options).await
 }
 
+/// Saves the danmaku of a video part as `{filename}.danmaku.xml` and
+/// `{filename}.danmaku.ass` in the download directory.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `cid` - Content ID of the video part
+/// * `duration_seconds` - Part duration in seconds
+/// * `filename` - Base filename
+/// * `options` - ASS font size, opacity, density and lane layout
+///
+/// # Errors
+///
+/// Returns an error if the download directory is not configured or the
+/// danmaku cannot be fetched or written.
+#[tauri::command]
+async fn download_danmaku(
+    app: AppHandle,
+    cid: i64,
+    duration_seconds: i64,
+    filename: String,
+    options: Option<DanmakuOptions>,
+) -> Result<DanmakuFiles, String> {
+    danmaku::download_danmaku(&app, cid, duration_seconds, &filename, options).await
+}
+
+/// Resolves a live room (short or long ID) and its broadcast status.
+///
+/// # Errors
+///
+/// Returns `ERR::LIVE_ROOM_NOT_FOUND` for unknown rooms, or an error if the
+/// API request fails.
+#[tauri::command]
+async fn fetch_live_room(app: AppHandle, room_id: i64) -> Result<LiveRoomInfo, String> {
+    live::fetch_live_room(&app, room_id).await
+}
+
+/// Starts recording a live room.
+///
+/// Progress is emitted as `progress` events under the recording ID and
+/// state changes as `live:updated` events.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `options` - Room, protocol (FLV/HLS), quality, split and remux options
+///
+/// # Errors
+///
+/// Returns `ERR::LIVE_OFFLINE` if the room is not broadcasting, or
+/// `ERR::LIVE_ALREADY_RECORDING` if it is already being recorded.
+#[tauri::command]
+async fn start_live_recording(
+    app: AppHandle,
+    options: live::LiveRecordOptions,
+) -> Result<live::LiveRecording, String> {
+    live::start_live_recording(&app, options).await
+}
+
+/// Stops a live recording.
+///
+/// # Returns
+///
+/// `true` if the recording was running.
+#[tauri::command]
+async fn stop_live_recording(id: String) -> bool {
+    live::stop_live_recording(&id).await
+}
+
+/// Lists live recordings that have not finished yet.
+#[tauri::command]
+async fn get_live_recordings() -> Vec<live::LiveRecording> {
+    live::get_live_recordings().await
+}
+
 /// Rotates a local MP4 file by 90°, 180°, or 270°.
 ///
 /// Returns the absolute path of the written output file.
@@ -1443,15 +1962,18 @@ async fn set_simulate_logout(app: AppHandle, enabled: bool) -> Result<(), String
 // Bangumi Commands
 // ============================================================================
 
-/// Retrieves comprehensive metadata for a Bilibili bangumi episode.
+/// Retrieves comprehensive metadata for a Bilibili bangumi season.
 ///
 /// This command fetches bangumi information including title, episodes,
-/// and available quality options.
+/// extra sections (PVs, OVAs), and available quality options. Exactly one of
+/// the IDs is used, in the order `ep_id`, `season_id`, `media_id`.
 ///
 /// # Arguments
 ///
 /// * `app` - Tauri application handle for accessing the cookie cache
-/// * `ep_id` - Bangumi episode ID (e.g., 3051843)
+/// * `ep_id` - Bangumi episode ID (e.g., 3051843 from `/bangumi/play/ep…`)
+/// * `season_id` - Season ID (from `/bangumi/play/ss…`)
+/// * `media_id` - Media ID (from `/bangumi/media/md…`)
 ///
 /// # Returns
 ///
@@ -1460,17 +1982,77 @@ async fn set_simulate_logout(app: AppHandle, enabled: bool) -> Result<(), String
 /// # Errors
 ///
 /// Returns an error if:
-/// - Episode is not found (`ERR::BANGUMI_NOT_FOUND`)
+/// - No ID is given or the episode is not found (`ERR::BANGUMI_NOT_FOUND`)
 /// - Episode is VIP-only (`ERR::BANGUMI_VIP_ONLY`)
 /// - Region restricted (`ERR::BANGUMI_REGION_RESTRICTED`)
 /// - API request fails
 #[tauri::command]
-async fn fetch_bangumi_info(app: AppHandle, ep_id: i64) -> Result<Video, String> {
-    bilibili::fetch_bangumi_info(&app, ep_id)
+async fn fetch_bangumi_info(
+    app: AppHandle,
+    ep_id: Option<i64>,
+    season_id: Option<i64>,
+    media_id: Option<i64>,
+) -> Result<Video, String> {
+    let id = bangumi_id(ep_id, season_id, media_id)?;
+    bilibili::fetch_bangumi_info(&app, id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Queues every downloadable episode of a bangumi season.
+///
+/// VIP-only and preview-only episodes are checked up front and reported in
+/// the result instead of failing the batch. Accepts the same IDs as
+/// `fetch_bangumi_info`.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle
+/// * `ep_id` - Any episode ID of the season
+/// * `season_id` - Season ID
+/// * `media_id` - Media ID
+/// * `include_extras` - Also queue PVs, OVAs and other extra sections
+/// * `allow_previews` - Queue preview-only episodes instead of skipping them
+/// * `archive_mode` - How items already in the download archive are
+///   handled (skipped when omitted)
+///
+/// # Returns
+///
+/// The queued jobs and the skipped episodes with their reason.
+#[tauri::command]
+async fn enqueue_bangumi_season(
+    app: AppHandle,
+    ep_id: Option<i64>,
+    season_id: Option<i64>,
+    media_id: Option<i64>,
+    include_extras: Option<bool>,
+    allow_previews: Option<bool>,
+    archive_mode: Option<ArchiveMode>,
+) -> Result<bangumi::SeasonEnqueueResult, String> {
+    let id = bangumi_id(ep_id, season_id, media_id)?;
+    bangumi::enqueue_bangumi_season(
+        &app,
+        id,
+        include_extras.unwrap_or(false),
+        allow_previews.unwrap_or(false),
+        archive_mode.unwrap_or_default(),
+    )
+    .await
+}
+
+/// Picks the bangumi ID from optional command arguments.
+fn bangumi_id(
+    ep_id: Option<i64>,
+    season_id: Option<i64>,
+    media_id: Option<i64>,
+) -> Result<bilibili::BangumiId, String> {
+    ep_id
+        .map(bilibili::BangumiId::Episode)
+        .or(season_id.map(bilibili::BangumiId::Season))
+        .or(media_id.map(bilibili::BangumiId::Media))
+        .ok_or_else(|| "ERR::BANGUMI_NOT_FOUND".to_string())
+}
+
 /// Fetches available video and audio qualities for a bangumi episode part.
 ///
 /// Used for lazy-loading qualities when the part is rendered.
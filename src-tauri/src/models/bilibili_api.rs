@@ -69,6 +69,87 @@ pub struct WebInterfaceApiResponseData {
     /// Redirect URL for special content (e.g., bangumi episode)
     #[serde(default, rename = "redirect_url")]
     pub redirect_url: Option<String>,
+    /// Collection (合集) this video belongs to, if any
+    #[serde(default)]
+    pub ugc_season: Option<UgcSeason>,
+    /// Video description
+    #[serde(default)]
+    pub desc: String,
+    /// Publish time (Unix timestamp)
+    #[serde(default)]
+    pub pubdate: i64,
+    /// Uploader
+    #[serde(default)]
+    pub owner: Option<VideoOwner>,
+}
+
+/// Uploader of a video in the view API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoOwner {
+    pub mid: i64,
+    pub name: String,
+}
+
+/// Archive tags API response.
+///
+/// Endpoint: `https://api.bilibili.com/x/tag/archive/tags?bvid={id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveTagsApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<Vec<ArchiveTag>>,
+}
+
+/// Tag attached to a video.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveTag {
+    pub tag_name: String,
+}
+
+/// UGC collection (合集) embedded in the view API response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UgcSeason {
+    pub id: i64,
+    pub title: String,
+    #[serde(default)]
+    pub cover: String,
+    #[serde(default)]
+    pub mid: i64,
+    #[serde(default)]
+    pub sections: Vec<UgcSeasonSection>,
+}
+
+/// Section of a UGC collection. Most collections have a single section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UgcSeasonSection {
+    pub id: i64,
+    pub title: String,
+    #[serde(default)]
+    pub episodes: Vec<UgcSeasonEpisode>,
+}
+
+/// Episode (one BV) of a UGC collection section.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UgcSeasonEpisode {
+    pub aid: i64,
+    pub bvid: String,
+    pub cid: i64,
+    pub title: String,
+    #[serde(default)]
+    pub arc: Option<UgcSeasonArc>,
+    /// Parts of the episode's video
+    #[serde(default)]
+    pub pages: Option<Vec<WebInterfaceApiResponsePage>>,
+}
+
+/// Archive metadata of a collection episode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UgcSeasonArc {
+    #[serde(default)]
+    pub pic: String,
+    #[serde(default)]
+    pub duration: i64,
 }
 
 /// Player API response for DASH streams.
@@ -112,22 +193,67 @@ pub struct XPlayerApiResponseDash {
     pub video: Vec<XPlayerApiResponseVideo>,
     /// Available audio stream qualities
     pub audio: Vec<XPlayerApiResponseVideo>,
-    /// Unparsed top-level DASH fields (e.g. `dolby`, `flac`) captured for
-    /// diagnostic logging only. These VIP-only audio objects are
-    /// intentionally NOT fed into stream selection (see issue #467
-    /// investigation), but recording their presence helps confirm whether
-    /// the manifest included them for a given account.
+    /// Dolby Atmos audio (VIP only)
+    #[serde(default)]
+    pub dolby: Option<XPlayerApiResponseDolby>,
+    /// Hi-Res lossless audio (VIP only)
+    #[serde(default)]
+    pub flac: Option<XPlayerApiResponseFlac>,
+    /// Unparsed top-level DASH fields captured for diagnostic logging only.
     #[serde(flatten, default)]
     pub extra: std::collections::HashMap<String, serde_json::Value>,
 }
 
+impl XPlayerApiResponseDash {
+    /// Returns every audio stream: the standard AAC streams first, then the
+    /// Dolby and Hi-Res streams.
+    ///
+    /// Keeping the standard streams first means "best available" and
+    /// missing-quality fallbacks still resolve to AAC; the VIP streams are
+    /// only used when selected explicitly.
+    pub fn audio_streams(&self) -> Vec<XPlayerApiResponseVideo> {
+        let dolby = self.dolby.iter().flat_map(|d| d.audio.iter().flatten());
+        let flac = self.flac.iter().flat_map(|f| f.audio.iter());
+        self.audio
+            .iter()
+            .chain(dolby)
+            .chain(flac)
+            .cloned()
+            .collect()
+    }
+}
+
+/// `dash.dolby` object of the playurl response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XPlayerApiResponseDolby {
+    /// 1 = Dolby audio, 2 = Dolby Atmos
+    #[serde(default, rename = "type")]
+    pub kind: i32,
+    #[serde(default)]
+    pub audio: Option<Vec<XPlayerApiResponseVideo>>,
+}
+
+/// `dash.flac` object of the playurl response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct XPlayerApiResponseFlac {
+    /// Whether the player shows the Hi-Res option
+    #[serde(default)]
+    pub display: bool,
+    #[serde(default)]
+    pub audio: Option<XPlayerApiResponseVideo>,
+}
+
 /// Individual video or audio stream.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct XPlayerApiResponseVideo {
     pub id: i32,
+    /// Absent (0) for audio streams
+    #[serde(default)]
     pub codecid: i16,
     pub bandwidth: i64,
+    #[serde(default)]
     pub width: i16,
+    #[serde(default)]
     pub height: i16,
     #[serde(rename = "baseUrl")]
     pub base_url: String,
@@ -350,6 +476,64 @@ pub struct WatchHistoryCursor {
     pub is_end: bool,
 }
 
+// ============================================================================
+// Watch Later APIs
+// ============================================================================
+
+/// Watch Later (稍后再看) list API response.
+///
+/// Endpoint: `https://api.bilibili.com/x/v2/history/toview`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchLaterApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<WatchLaterApiResponseData>,
+}
+
+/// Watch Later list data (the API returns the whole list at once).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchLaterApiResponseData {
+    #[serde(default)]
+    pub count: i64,
+    #[serde(default)]
+    pub list: Option<Vec<WatchLaterApiItem>>,
+}
+
+/// Individual Watch Later item from API.
+///
+/// # Note
+///
+/// `videos` is the part count, `cid` is the first part and `add_at` is the
+/// time the item was added (Unix timestamp). `progress` is -1 once watched
+/// to the end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchLaterApiItem {
+    pub aid: i64,
+    pub bvid: String,
+    pub title: String,
+    pub pic: String,
+    pub duration: i64,
+    #[serde(default)]
+    pub videos: i32,
+    #[serde(default)]
+    pub cid: i64,
+    #[serde(default)]
+    pub add_at: i64,
+    #[serde(default)]
+    pub progress: i64,
+}
+
+/// Response of write APIs that only report a status code.
+///
+/// Endpoint: `https://api.bilibili.com/x/v2/history/toview/del`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusApiResponse {
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+}
+
 // ============================================================================
 // Subtitle APIs
 // ============================================================================
@@ -365,11 +549,28 @@ pub struct PlayerV2ApiResponse {
     pub data: Option<PlayerV2ApiData>,
 }
 
-/// Player v2 API data containing subtitle information.
+/// Player v2 API data containing subtitle and chapter information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerV2ApiData {
     #[serde(default)]
     pub subtitle: Option<PlayerV2Subtitle>,
+    /// Chapters (分段章节) set by the uploader
+    #[serde(default)]
+    pub view_points: Option<Vec<PlayerV2ViewPoint>>,
+}
+
+/// Chapter (view point) in player v2 API response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerV2ViewPoint {
+    /// Chapter title
+    #[serde(default)]
+    pub content: String,
+    /// Start offset in seconds
+    #[serde(default)]
+    pub from: i64,
+    /// End offset in seconds
+    #[serde(default)]
+    pub to: i64,
 }
 
 /// Subtitle container in player v2 API response.
@@ -612,6 +813,36 @@ pub struct BangumiSeasonResult {
     pub season_id: i64,
     pub title: String,
     pub cover: String,
+    /// Season synopsis
+    #[serde(default)]
+    pub evaluate: String,
+    /// Genre labels (e.g. "原创", "奇幻")
+    #[serde(default)]
+    pub styles: Vec<String>,
+    /// Main episodes
+    #[serde(default)]
+    pub episodes: Vec<BangumiEpisode>,
+    /// Extra sections (PVs, OVAs, specials) listed below the main episodes
+    #[serde(default)]
+    pub section: Vec<BangumiSection>,
+}
+
+impl BangumiSeasonResult {
+    /// Finds an episode by ID in the main episodes or any extra section.
+    pub fn find_episode(&self, ep_id: i64) -> Option<&BangumiEpisode> {
+        self.episodes
+            .iter()
+            .chain(self.section.iter().flat_map(|s| s.episodes.iter()))
+            .find(|ep| ep.id == ep_id)
+    }
+}
+
+/// Extra section of a bangumi season (e.g. "PV", "特别篇").
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BangumiSection {
+    pub id: i64,
+    #[serde(default)]
+    pub title: String,
     #[serde(default)]
     pub episodes: Vec<BangumiEpisode>,
 }
@@ -641,6 +872,32 @@ pub struct BangumiEpisode {
     pub duration: i64,
 }
 
+/// Bangumi media (review) API response, used to resolve `md` IDs.
+///
+/// Endpoint: `https://api.bilibili.com/pgc/review/user?media_id={media_id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BangumiMediaApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub result: Option<BangumiMediaResult>,
+}
+
+/// Bangumi media result wrapper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BangumiMediaResult {
+    pub media: BangumiMedia,
+}
+
+/// Bangumi media entry linking a media ID to its season.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BangumiMedia {
+    pub media_id: i64,
+    pub season_id: i64,
+    #[serde(default)]
+    pub title: String,
+}
+
 /// Bangumi player API response for DASH streams.
 ///
 /// Endpoint: `https://api.bilibili.com/pgc/player/web/playurl`
@@ -707,3 +964,255 @@ pub struct SupportFormat {
     #[serde(default)]
     pub display_desc: String,
 }
+
+// ============================================================================
+// Uploader Space APIs
+// ============================================================================
+
+/// Uploader space video search API response.
+///
+/// Endpoint: `https://api.bilibili.com/x/space/wbi/arc/search` (WBI-signed)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceArcSearchApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<SpaceArcSearchData>,
+}
+
+/// Space video search data with pagination info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceArcSearchData {
+    pub list: SpaceArcSearchList,
+    pub page: SpaceArcSearchPage,
+}
+
+/// Wrapper for the video list (`list.vlist`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceArcSearchList {
+    #[serde(default)]
+    pub vlist: Vec<SpaceArcSearchVideo>,
+}
+
+/// Pagination info for space video search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceArcSearchPage {
+    pub pn: i64,
+    pub ps: i64,
+    pub count: i64,
+}
+
+/// Single video in an uploader's space.
+///
+/// # Note
+///
+/// `length` is a display string (`"mm:ss"` or `"h:mm:ss"`), not seconds.
+/// `created` is the publish time as a Unix timestamp.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpaceArcSearchVideo {
+    pub aid: i64,
+    pub bvid: String,
+    pub title: String,
+    pub pic: String,
+    pub created: i64,
+    pub length: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default)]
+    pub mid: i64,
+}
+
+// ============================================================================
+// Series APIs
+// ============================================================================
+
+/// Series metadata API response.
+///
+/// Endpoint: `https://api.bilibili.com/x/series/series?series_id={id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesMetaApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<SeriesMetaData>,
+}
+
+/// Wrapper for series metadata (`data.meta`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesMetaData {
+    pub meta: SeriesMeta,
+}
+
+/// Series name and owner.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesMeta {
+    pub series_id: i64,
+    pub mid: i64,
+    pub name: String,
+    #[serde(default)]
+    pub cover: String,
+    #[serde(default)]
+    pub total: i64,
+}
+
+/// Series archive list API response.
+///
+/// Endpoint: `https://api.bilibili.com/x/series/archives`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesArchivesApiResponse {
+    pub code: i64,
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<SeriesArchivesData>,
+}
+
+/// Series archives with pagination info.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesArchivesData {
+    #[serde(default)]
+    pub archives: Vec<SeriesArchive>,
+    pub page: SeriesArchivesPage,
+}
+
+/// Pagination info for series archives (`num` is the 1-indexed page).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesArchivesPage {
+    pub num: i64,
+    pub size: i64,
+    pub total: i64,
+}
+
+/// Single video in a series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesArchive {
+    pub aid: i64,
+    pub bvid: String,
+    pub title: String,
+    #[serde(default)]
+    pub pic: String,
+    #[serde(default)]
+    pub duration: i64,
+    #[serde(default)]
+    pub pubdate: i64,
+}
+
+// ============================================================================
+// Live APIs
+// ============================================================================
+
+/// Live room ID resolution API response.
+///
+/// Endpoint: `https://api.live.bilibili.com/room/v1/Room/room_init?id={id}`
+/// (accepts both short and long room IDs)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRoomInitApiResponse {
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<LiveRoomInitData>,
+}
+
+/// Resolved live room. `live_status` is 1 while broadcasting.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRoomInitData {
+    pub room_id: i64,
+    #[serde(default)]
+    pub short_id: i64,
+    pub uid: i64,
+    pub live_status: i32,
+}
+
+/// Live room info API response.
+///
+/// Endpoint: `https://api.live.bilibili.com/room/v1/Room/get_info?room_id={id}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRoomInfoApiResponse {
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<LiveRoomInfoData>,
+}
+
+/// Live room title and cover.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveRoomInfoData {
+    pub title: String,
+    #[serde(default)]
+    pub user_cover: String,
+    #[serde(default)]
+    pub live_time: String,
+}
+
+/// Live playurl API response.
+///
+/// Endpoint: `https://api.live.bilibili.com/xlive/web-room/v2/index/getRoomPlayInfo`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivePlayInfoApiResponse {
+    pub code: i64,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub data: Option<LivePlayInfoData>,
+}
+
+/// Room play info. `playurl_info` is absent while the room is offline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivePlayInfoData {
+    pub room_id: i64,
+    pub live_status: i32,
+    #[serde(default)]
+    pub playurl_info: Option<LivePlayurlInfo>,
+}
+
+/// Wrapper for the playurl (`playurl_info.playurl`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivePlayurlInfo {
+    pub playurl: LivePlayurl,
+}
+
+/// Available streams grouped by protocol.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LivePlayurl {
+    #[serde(default)]
+    pub stream: Vec<LiveStream>,
+}
+
+/// Streams of one protocol (`http_stream` for FLV, `http_hls` for HLS).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveStream {
+    pub protocol_name: String,
+    #[serde(default)]
+    pub format: Vec<LiveStreamFormatEntry>,
+}
+
+/// Streams of one container (`flv`, `ts` or `fmp4`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveStreamFormatEntry {
+    pub format_name: String,
+    #[serde(default)]
+    pub codec: Vec<LiveStreamCodec>,
+}
+
+/// Stream of one codec (`avc` or `hevc`).
+///
+/// The full URL is `url_info[i].host + base_url + url_info[i].extra`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveStreamCodec {
+    pub codec_name: String,
+    pub current_qn: i32,
+    #[serde(default)]
+    pub accept_qn: Vec<i32>,
+    pub base_url: String,
+    #[serde(default)]
+    pub url_info: Vec<LiveStreamUrlInfo>,
+}
+
+/// CDN host and signed query for a live stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LiveStreamUrlInfo {
+    pub host: String,
+    #[serde(default)]
+    pub extra: String,
+}
@@ -5,6 +5,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::utils::codec::DynamicRange;
+
 /// User information structure sent to the frontend.
 ///
 /// Contains authentication status and basic profile information.
@@ -58,6 +60,39 @@ pub struct Video {
     /// Season title for bangumi content
     #[serde(default)]
     pub season_title: Option<String>,
+    /// Collection (合集) this video belongs to, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection: Option<VideoCollection>,
+    /// Bangumi extras (PVs, OVAs, specials) listed after the main episodes
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub extra_sections: Vec<VideoSection>,
+    /// Descriptive metadata to embed into downloaded files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<VideoMetadata>,
+}
+
+/// Descriptive metadata written into downloaded files as container tags.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoMetadata {
+    /// Title (of the part, for multi-part videos)
+    pub title: String,
+    /// Uploader name
+    #[serde(default)]
+    pub uploader: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Publish time (Unix timestamp)
+    #[serde(default)]
+    pub publish_date: Option<i64>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Page URL of the video (or part/episode)
+    #[serde(default)]
+    pub source_url: Option<String>,
+    /// Cover image URL (`pic`), embedded as artwork
+    #[serde(default)]
+    pub cover_url: Option<String>,
 }
 
 fn default_content_type() -> String {
@@ -97,6 +132,31 @@ pub struct VideoPart {
     #[serde(default)]
     pub is_preview: Option<bool>,
 }
+
+/// Chapter marker of a video part.
+///
+/// Written into merged MP4 files as container chapters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chapter {
+    pub title: String,
+    /// Start offset in seconds
+    pub start: i64,
+    /// End offset in seconds
+    pub end: i64,
+}
+
+/// Titled group of extra parts (bangumi PV/OVA sections).
+///
+/// Page numbers continue after the main parts, so every part of a video has
+/// a unique page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoSection {
+    pub title: String,
+    pub parts: Vec<VideoPart>,
+}
+
 /// Thumbnail information with the image URL.
 ///
 /// The frontend uses the URL directly with referrerPolicy="no-referrer"
@@ -118,6 +178,56 @@ pub struct Quality {
     pub codecid: i16,
     /// Human-readable quality label (e.g. "1080P 高清")
     pub quality: String,
+    /// SDR, HDR or Dolby Vision (always SDR for audio qualities)
+    #[serde(rename = "dynamicRange", default)]
+    pub dynamic_range: DynamicRange,
+}
+
+// Collection DTOs
+
+/// Ordered group of videos: a UGC collection (合集) or an uploader series.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoCollection {
+    /// Collection kind
+    pub kind: CollectionKind,
+    /// Season ID (for `season`) or series ID (for `series`)
+    pub id: i64,
+    /// Owner's member ID
+    pub mid: i64,
+    pub title: String,
+    pub cover: String,
+    /// Episodes in playback order
+    pub episodes: Vec<CollectionEpisode>,
+}
+
+/// Kind of [`VideoCollection`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CollectionKind {
+    /// UGC collection (合集, `ugc_season`)
+    Season,
+    /// Uploader series (系列)
+    Series,
+}
+
+/// Single episode (one BV) of a collection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionEpisode {
+    /// 1-indexed position across all sections
+    pub index: i32,
+    /// Section title (collections only, `None` for single-section ones)
+    #[serde(default)]
+    pub section_title: Option<String>,
+    pub bvid: String,
+    pub title: String,
+    pub cover: String,
+    /// Duration in seconds
+    pub duration: i64,
+    /// Number of parts. `None` if unknown.
+    #[serde(default)]
+    pub part_count: Option<i32>,
 }
 
 // Favorite DTOs
@@ -179,6 +289,49 @@ pub struct FavoriteVideoListResponse {
     pub total_count: i64,
 }
 
+/// Per-folder state of the last favorites sync, persisted between runs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct FavoriteSyncCursor {
+    /// Favorite folder ID
+    pub media_id: i64,
+    /// Completion time of the last sync (ISO 8601 format)
+    pub last_synced_at: String,
+    /// Newest `fav_time` (Unix seconds) seen by the last sync
+    pub last_fav_time: i64,
+    /// Number of videos in the folder at the last sync
+    pub total_count: i64,
+    /// Number of jobs the last sync enqueued
+    pub last_enqueued: usize,
+}
+
+// Uploader Space DTOs
+
+/// Video in an uploader's space sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceVideo {
+    pub aid: i64,
+    pub bvid: String,
+    pub title: String,
+    pub cover: String,
+    /// Publish time (Unix timestamp, seconds)
+    pub pubdate: i64,
+    /// Duration in seconds
+    pub duration: i64,
+    /// Number of parts. `None` if the view lookup failed.
+    pub part_count: Option<i32>,
+}
+
+/// Paginated uploader space video list response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpaceVideoListResponse {
+    pub videos: Vec<SpaceVideo>,
+    pub has_more: bool,
+    pub total_count: i64,
+}
+
 // Watch History DTOs
 
 /// Watch history entry sent to the frontend.
@@ -209,6 +362,67 @@ pub struct WatchHistoryCursor {
     pub is_end: bool,
 }
 
+// Watch Later DTOs
+
+/// Watch Later (稍后再看) entry sent to the frontend.
+///
+/// Mirrors [`WatchHistoryEntry`], with the time the item was added instead
+/// of the time it was viewed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchLaterEntry {
+    pub title: String,
+    pub cover: String,
+    pub aid: i64,
+    pub bvid: String,
+    pub cid: i64,
+    pub part_count: i32,
+    pub added_at: i64,
+    pub duration: i64,
+    pub progress: i64,
+    pub url: String,
+}
+
+/// Watch Later list response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchLaterResponse {
+    pub entries: Vec<WatchLaterEntry>,
+    pub total_count: i64,
+}
+
+// Live DTOs
+
+/// Live room information sent to the frontend.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveRoomInfo {
+    /// Long (real) room ID
+    pub room_id: i64,
+    /// Short room ID (0 if the room has none)
+    pub short_id: i64,
+    /// Streamer's member ID
+    pub uid: i64,
+    pub title: String,
+    pub cover: String,
+    /// Whether the room is broadcasting
+    pub is_live: bool,
+}
+
+// Danmaku DTOs
+
+/// Files written by a standalone danmaku download.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DanmakuFiles {
+    /// Raw comments in Bilibili XML format
+    pub xml_path: String,
+    /// Comments converted to an ASS subtitle
+    pub ass_path: String,
+    /// Number of comments
+    pub count: usize,
+}
+
 // Subtitle DTOs
 
 /// Subtitle information sent to the frontend.
@@ -16,42 +16,166 @@ pub struct HistoryEntry {
     pub id: String,
     /// Video title fetched from Bilibili.
     pub title: String,
+    /// Uploader name (absent in entries recorded before it was tracked).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploader: Option<String>,
     /// Bilibili video ID (BV identifier, optional for backward compatibility).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub bvid: Option<String>,
+    /// Content ID of the downloaded part (absent in entries recorded before
+    /// it was tracked).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cid: Option<i64>,
+    /// Page number of the part within a multi-part video.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<i32>,
     /// Bilibili video URL.
     pub url: String,
     /// Download completion timestamp (ISO 8601 format).
     pub downloaded_at: String,
-    /// Download status: "completed" or "failed".
+    /// Download status: "completed", "failed" or "missing" (the output file
+    /// was not found by the last `reconcile_history`).
     pub status: String,
     /// Downloaded file size in bytes (optional).
     pub file_size: Option<u64>,
     /// Video quality (e.g., "1080P60", optional).
     pub quality: Option<String>,
+    /// Video codec label ("AVC", "HEVC" or "AV1"; absent for durl streams
+    /// and audio-only downloads).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codec: Option<String>,
+    /// Duration of the part in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_seconds: Option<i64>,
+    /// Absolute path of the output file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_path: Option<String>,
     /// Thumbnail URL (original Bilibili URL).
     /// Frontend fetches and converts to base64 on-demand via API.
     pub thumbnail_url: Option<String>,
+    /// Download mode: "audio" (audio-only), "video" (video-only) or
+    /// "streams" (raw streams kept); absent for a merged video.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+    /// File names of the raw streams kept next to the output.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stream_files: Vec<String>,
     /// Version for data migration support.
     #[serde(default = "default_version")]
     pub version: String,
 }
 
-/// Returns the default version string for new history entries.
+/// Version written into new history entries.
+///
+/// - `1.0`: initial format; later 1.0 entries also record `uploader`
+/// - `2.0`: adds `page`, `codec`, `durationSeconds` and `outputPath` (see
+///   [`HistoryEntry::migrate`])
+pub const CURRENT_HISTORY_VERSION: &str = "2.0";
+
+/// Returns the version assumed for entries stored without one.
 fn default_version() -> String {
     "1.0".to_string()
 }
 
+impl HistoryEntry {
+    /// Upgrades an entry recorded by an older version to
+    /// [`CURRENT_HISTORY_VERSION`].
+    ///
+    /// 1.0 entries get their page back from their URL (see
+    /// [`HistoryEntry::url_page`]); codec, duration and output path were
+    /// never recorded and stay empty.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the entry was changed.
+    pub fn migrate(&mut self) -> bool {
+        if self.version == CURRENT_HISTORY_VERSION {
+            return false;
+        }
+        if self.page.is_none() {
+            self.page = self.url_page();
+        }
+        self.version = CURRENT_HISTORY_VERSION.to_string();
+        true
+    }
+
+    /// Returns the page named by the `p` query parameter of the URL.
+    ///
+    /// # Returns
+    ///
+    /// `None` if the URL cannot be parsed or has no positive `p`.
+    pub fn url_page(&self) -> Option<i32> {
+        let url = reqwest::Url::parse(&self.url).ok()?;
+        let (_, page) = url.query_pairs().find(|(name, _)| name == "p")?;
+        page.parse().ok().filter(|&page| page > 0)
+    }
+}
+
+/// Field history search results are sorted by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HistorySortField {
+    /// Order in which entries were added (default)
+    #[default]
+    Added,
+    /// Download completion timestamp
+    DownloadedAt,
+    /// Title (case-insensitive)
+    Title,
+    /// File size; entries without a size come first in ascending order
+    FileSize,
+}
+
+/// Sort direction of history search results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortOrder {
+    /// Oldest / smallest first
+    Asc,
+    /// Newest / largest first (default)
+    #[default]
+    Desc,
+}
+
 /// Filter for history search.
 ///
-/// Supports filtering by status, date range, and text search.
+/// Supports filtering by status, date and size range, quality and
+/// uploader, plus sorting and pagination. Omitted fields do not filter.
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HistoryFilters {
     /// Filter by download status.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub status: Option<String>,
-    /// Filter by date range start (ISO 8601 format).
+    /// Filter by date range start (ISO 8601 format, inclusive).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub date_from: Option<String>,
+    /// Filter by date range end (ISO 8601 format, inclusive; a bare date
+    /// such as `2026-01-31` covers the whole day).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date_to: Option<String>,
+    /// Filter by quality label (e.g. "1080P").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality: Option<String>,
+    /// Filter by uploader name (case-insensitive exact match).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uploader: Option<String>,
+    /// Minimum file size in bytes (inclusive).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_file_size: Option<u64>,
+    /// Maximum file size in bytes (inclusive).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_file_size: Option<u64>,
+    /// Sort field (insertion order when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_by: Option<HistorySortField>,
+    /// Sort direction (descending when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_order: Option<SortOrder>,
+    /// Maximum number of entries to return (all when omitted).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+    /// Number of matching entries to skip (for pagination).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u32>,
 }
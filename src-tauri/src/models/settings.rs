@@ -1,6 +1,6 @@
 //! Application settings persisted to settings.json
 
-use crate::utils::codec::VideoCodecPriority;
+use crate::utils::codec::{OutputContainer, VideoCodecPriority};
 use serde::{Deserialize, Serialize};
 
 /// Title replacement rule for filename sanitization.
@@ -201,6 +201,188 @@ pub struct Settings {
         skip_serializing_if = "Option::is_none"
     )]
     pub download_parallelism: Option<u8>,
+    /// Global download bandwidth cap shared by all running downloads.
+    /// Unlimited if not set.
+    #[serde(
+        rename = "bandwidthLimit",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub bandwidth_limit: Option<BandwidthLimit>,
+    /// Outbound proxy for API and/or CDN traffic. Direct connection if not
+    /// set.
+    #[serde(rename = "proxy", default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxySettings>,
+    /// Whether to embed the video cover into downloaded MP4 files.
+    /// Defaults to true.
+    #[serde(
+        rename = "embedCover",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub embed_cover: Option<bool>,
+    /// Whether to keep the cover next to each download as
+    /// `{filename}.cover.jpg`. Defaults to false.
+    #[serde(
+        rename = "saveCoverSidecar",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub save_cover_sidecar: Option<bool>,
+    /// Container of merged downloads. Defaults to MP4; Hi-Res FLAC audio
+    /// is always written to MKV.
+    #[serde(
+        rename = "outputContainer",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub output_container: Option<OutputContainer>,
+    /// Filename template of downloads (see
+    /// [`crate::utils::filename_template`]), e.g. `{uploader}/{title}`.
+    /// The frontend filename is used if not set.
+    #[serde(
+        rename = "filenameTemplate",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub filename_template: Option<String>,
+}
+
+/// Outbound proxy configuration applied by [`crate::utils::proxy`] to every
+/// HTTP client the backend builds.
+///
+/// Caution: the password is stored in settings.json in plain text like the
+///   rest of the settings; it is not routed through secure storage.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    /// Whether the proxy is active.
+    pub enabled: bool,
+    /// Proxy protocol.
+    pub protocol: ProxyProtocol,
+    /// Proxy host name or IP address.
+    pub host: String,
+    /// Proxy port.
+    pub port: u16,
+    /// Optional user name for proxy authentication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// Optional password for proxy authentication.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+    /// Hosts that bypass the proxy (e.g. `"localhost"`, `".example.com"`,
+    /// `"192.168.0.0/16"`).
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+    /// Which traffic goes through the proxy. Defaults to all traffic.
+    #[serde(default)]
+    pub scope: ProxyScope,
+}
+
+/// Proxy protocol.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyProtocol {
+    /// Plain HTTP proxy (CONNECT for HTTPS targets).
+    Http,
+    /// HTTP proxy reached over TLS.
+    Https,
+    /// SOCKS5 proxy with remote DNS resolution.
+    Socks5,
+}
+
+/// Which traffic is routed through the proxy.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProxyScope {
+    /// API calls and CDN downloads (default).
+    #[default]
+    All,
+    /// Only Bilibili API calls (metadata, login, subtitles).
+    Api,
+    /// Only CDN media downloads and CDN probes.
+    Cdn,
+}
+
+/// Global download bandwidth cap with an optional time-of-day schedule.
+///
+/// The cap is enforced process-wide by
+/// [`crate::utils::bandwidth::BANDWIDTH_LIMITER`], so it bounds the sum of
+/// all segment downloads across all files rather than each connection.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthLimit {
+    /// Whether the cap is active. When false, downloads are unlimited
+    /// regardless of the configured values.
+    pub enabled: bool,
+    /// Cap in KB/s applied outside any schedule window. 0 means unlimited.
+    pub limit_kbps: u32,
+    /// Time-of-day windows overriding `limit_kbps`. The first matching
+    /// window wins.
+    #[serde(default)]
+    pub schedule: Vec<BandwidthScheduleRule>,
+}
+
+/// Time-of-day window with its own bandwidth cap.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BandwidthScheduleRule {
+    /// Window start in local time, `"HH:MM"` (inclusive).
+    pub start: String,
+    /// Window end in local time, `"HH:MM"` (exclusive). An end earlier than
+    /// the start wraps past midnight; an end equal to the start covers the
+    /// whole day.
+    pub end: String,
+    /// Cap in KB/s while the window is active. 0 means unlimited.
+    pub limit_kbps: u32,
+}
+
+impl BandwidthScheduleRule {
+    /// Returns `true` if `minute_of_day` (0-1439) falls inside this window.
+    ///
+    /// Rules with a malformed `start`/`end` never match.
+    pub fn contains(&self, minute_of_day: u32) -> bool {
+        let (Some(start), Some(end)) = (parse_hhmm(&self.start), parse_hhmm(&self.end)) else {
+            return false;
+        };
+        match start.cmp(&end) {
+            std::cmp::Ordering::Less => start <= minute_of_day && minute_of_day < end,
+            std::cmp::Ordering::Greater => minute_of_day >= start || minute_of_day < end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+impl BandwidthLimit {
+    /// Resolves the cap in bytes per second for the given local time.
+    ///
+    /// # Arguments
+    ///
+    /// * `minute_of_day` - Local time as minutes since midnight (0-1439)
+    ///
+    /// # Returns
+    ///
+    /// `Some(bytes_per_sec)` when a cap applies, or `None` when downloads
+    /// are unlimited (disabled, or the effective limit is 0).
+    pub fn bytes_per_sec_at(&self, minute_of_day: u32) -> Option<u64> {
+        if !self.enabled {
+            return None;
+        }
+        let kbps = self
+            .schedule
+            .iter()
+            .find(|rule| rule.contains(minute_of_day))
+            .map(|rule| rule.limit_kbps)
+            .unwrap_or(self.limit_kbps);
+        (kbps > 0).then(|| kbps as u64 * 1024)
+    }
+}
+
+/// Parses `"HH:MM"` into minutes since midnight.
+fn parse_hhmm(value: &str) -> Option<u32> {
+    let (h, m) = value.trim().split_once(':')?;
+    let (h, m) = (h.parse::<u32>().ok()?, m.parse::<u32>().ok()?);
+    (h < 24 && m < 60).then_some(h * 60 + m)
 }
 
 /// Trim mode for the MP4 trimming feature.
@@ -225,6 +407,16 @@ pub enum AudioFormat {
     M4a,
 }
 
+impl AudioFormat {
+    /// Returns the file extension of the format (without the dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            AudioFormat::Mp3 => "mp3",
+            AudioFormat::M4a => "m4a",
+        }
+    }
+}
+
 /// Rotation mode for the MP4 rotation feature.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -422,4 +614,50 @@ mod tests {
         };
         assert_eq!(Settings::resolve_segment_concurrency(&Some(settings)), 8);
     }
+
+    fn rule(start: &str, end: &str, limit_kbps: u32) -> BandwidthScheduleRule {
+        BandwidthScheduleRule {
+            start: start.to_string(),
+            end: end.to_string(),
+            limit_kbps,
+        }
+    }
+
+    #[test]
+    fn test_bandwidth_limit_disabled_or_zero_is_unlimited() {
+        let limit = BandwidthLimit {
+            enabled: false,
+            limit_kbps: 512,
+            schedule: vec![],
+        };
+        assert_eq!(limit.bytes_per_sec_at(600), None);
+
+        let limit = BandwidthLimit {
+            enabled: true,
+            limit_kbps: 0,
+            schedule: vec![],
+        };
+        assert_eq!(limit.bytes_per_sec_at(600), None);
+    }
+
+    #[test]
+    fn test_bandwidth_limit_schedule_overrides_base() {
+        // Office hours capped at 256 KB/s, nights unlimited, otherwise 1 MB/s
+        let limit = BandwidthLimit {
+            enabled: true,
+            limit_kbps: 1024,
+            schedule: vec![rule("09:00", "18:00", 256), rule("23:00", "06:00", 0)],
+        };
+        assert_eq!(limit.bytes_per_sec_at(9 * 60), Some(256 * 1024));
+        assert_eq!(limit.bytes_per_sec_at(18 * 60), Some(1024 * 1024));
+        assert_eq!(limit.bytes_per_sec_at(23 * 60 + 30), None);
+        assert_eq!(limit.bytes_per_sec_at(5 * 60), None);
+    }
+
+    #[test]
+    fn test_bandwidth_schedule_rule_malformed_never_matches() {
+        assert!(!rule("25:00", "06:00", 1).contains(0));
+        assert!(!rule("9am", "18:00", 1).contains(600));
+        assert!(rule("00:00", "00:00", 1).contains(1439));
+    }
 }
@@ -1,36 +1,113 @@
 //! History Store
 //!
-//! This module provides persistent storage for download history using
-//! tauri-plugin-store with versioning, migration, and concurrent
-//! write protection.
+//! This module provides persistent storage for download history in a
+//! SQLite database (`history.db` in the app data directory). The columns
+//! used by filters are indexed, and an FTS5 table with the trigram
+//! tokenizer indexes title, uploader and URL so text search stays a
+//! case-insensitive substring match without scanning every row.
+//!
+//! Earlier versions kept history as one array in `history.json`
+//! (tauri-plugin-store). It is imported the first time the store is opened
+//! and then renamed to `history.json.migrated`.
+
+use crate::models::history::{
+    HistoryEntry, HistoryFilters, HistorySortField, SortOrder, CURRENT_HISTORY_VERSION,
+};
+use rusqlite::functions::FunctionFlags;
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, Row, Transaction, TransactionBehavior};
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+const DB_FILE: &str = "history.db";
+const LEGACY_JSON_FILE: &str = "history.json";
+const LEGACY_ENTRIES_KEY: &str = "entries";
+const SCHEMA_VERSION: i32 = 2;
+
+/// Shortest query the trigram index can answer; shorter queries fall back
+/// to a scan of the case-folded columns.
+const MIN_FTS_QUERY_CHARS: usize = 3;
+
+/// Schema of version 1.
+///
+/// `seq` gives rows a stable rowid for the external-content FTS table and
+/// keeps insertion order (newest last). The triggers keep `history_fts` in
+/// sync with `history`.
+const SCHEMA_V1: &str = "
+CREATE TABLE IF NOT EXISTS history (
+    seq INTEGER PRIMARY KEY AUTOINCREMENT,
+    id TEXT NOT NULL UNIQUE,
+    title TEXT NOT NULL,
+    uploader TEXT,
+    bvid TEXT,
+    cid INTEGER,
+    url TEXT NOT NULL,
+    downloaded_at TEXT NOT NULL,
+    status TEXT NOT NULL,
+    file_size INTEGER,
+    quality TEXT,
+    thumbnail_url TEXT,
+    mode TEXT,
+    stream_files TEXT NOT NULL DEFAULT '[]',
+    version TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS history_downloaded_at ON history (downloaded_at);
+CREATE INDEX IF NOT EXISTS history_status ON history (status);
+CREATE INDEX IF NOT EXISTS history_bvid_cid ON history (bvid, cid);
+CREATE VIRTUAL TABLE IF NOT EXISTS history_fts USING fts5(
+    title, uploader, url,
+    content = 'history', content_rowid = 'seq', tokenize = 'trigram'
+);
+CREATE TRIGGER IF NOT EXISTS history_fts_insert AFTER INSERT ON history BEGIN
+    INSERT INTO history_fts (rowid, title, uploader, url)
+    VALUES (new.seq, new.title, new.uploader, new.url);
+END;
+CREATE TRIGGER IF NOT EXISTS history_fts_delete AFTER DELETE ON history BEGIN
+    INSERT INTO history_fts (history_fts, rowid, title, uploader, url)
+    VALUES ('delete', old.seq, old.title, old.uploader, old.url);
+END;
+CREATE TRIGGER IF NOT EXISTS history_fts_update AFTER UPDATE ON history BEGIN
+    INSERT INTO history_fts (history_fts, rowid, title, uploader, url)
+    VALUES ('delete', old.seq, old.title, old.uploader, old.url);
+    INSERT INTO history_fts (rowid, title, uploader, url)
+    VALUES (new.seq, new.title, new.uploader, new.url);
+END;
+";
 
-use crate::models::history::{HistoryEntry, HistoryFilters};
-use serde_json::json;
-use std::sync::Arc;
-use tauri::AppHandle;
-use tauri_plugin_store::StoreExt;
+/// Schema of version 2: the columns of history entry version 2.0 and
+/// indexes for the quality, uploader and size filters.
+const SCHEMA_V2: &str = "
+ALTER TABLE history ADD COLUMN page INTEGER;
+ALTER TABLE history ADD COLUMN codec TEXT;
+ALTER TABLE history ADD COLUMN duration_seconds INTEGER;
+ALTER TABLE history ADD COLUMN output_path TEXT;
+CREATE INDEX IF NOT EXISTS history_quality ON history (quality);
+CREATE INDEX IF NOT EXISTS history_uploader ON history (uploader COLLATE NOCASE);
+CREATE INDEX IF NOT EXISTS history_file_size ON history (file_size);
+";
 
-const VERSION_KEY: &str = "__version__";
-const ENTRIES_KEY: &str = "entries";
-const CURRENT_VERSION: &str = "1.0";
+const COLUMNS: &str = "id, title, uploader, bvid, cid, url, downloaded_at, status, file_size, \
+                       quality, thumbnail_url, mode, stream_files, version, page, codec, \
+                       duration_seconds, output_path";
 
-/// History store wrapper for tauri-plugin-store.
+/// History store backed by SQLite.
 ///
-/// Provides thread-safe operations with file locking for concurrent
-/// write protection and automatic version migration.
+/// Each instance owns its own connection; concurrent writers from other
+/// instances wait on the database lock (see `busy_timeout`).
 pub struct HistoryStore {
-    store: Arc<tauri_plugin_store::Store<tauri::Wry>>,
+    conn: Connection,
 }
 
 impl HistoryStore {
-    /// Creates a new HistoryStore instance backed by a persistent JSON file.
+    /// Opens the history database in the app data directory.
     ///
-    /// This function initializes or opens the history.json file from the
-    /// application's store directory using tauri-plugin-store.
+    /// Creates the database and schema on first use and imports the legacy
+    /// `history.json` if it is still present. A failed import is logged and
+    /// retried on the next open.
     ///
     /// # Arguments
     ///
-    /// * `app` - Tauri application handle for accessing the store
+    /// * `app` - Tauri application handle for resolving the app data directory
     ///
     /// # Returns
     ///
@@ -38,19 +115,118 @@ impl HistoryStore {
     ///
     /// # Errors
     ///
-    /// Returns an error if the store cannot be created or opened.
+    /// Returns an error if the app data directory cannot be resolved or the
+    /// database cannot be opened.
     pub fn new(app: &AppHandle) -> Result<Self, Box<dyn std::error::Error>> {
-        let store = app
-            .store("history.json")
-            .map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
+        let dir = app.path().app_data_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let conn = Connection::open(dir.join(DB_FILE))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        let store = Self::init(conn)?;
+        if let Err(e) = store.import_legacy_json(&dir.join(LEGACY_JSON_FILE)) {
+            log::warn!("[BE] history_store: failed to import history.json: {}", e);
+        }
+        Ok(store)
+    }
 
-        Ok(Self { store })
+    /// Opens the store and runs `f` with it on the blocking thread pool.
+    ///
+    /// SQLite calls block (up to the `busy_timeout` while another instance
+    /// writes), so async code must not run them on a runtime worker.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the store cannot be opened, the task panics or
+    /// `f` fails.
+    pub async fn run_blocking<T, F>(app: &AppHandle, f: F) -> Result<T, String>
+    where
+        T: Send + 'static,
+        F: FnOnce(&HistoryStore) -> Result<T, String> + Send + 'static,
+    {
+        let app = app.clone();
+        tokio::task::spawn_blocking(move || {
+            let store = Self::new(&app).map_err(|e| e.to_string())?;
+            f(&store)
+        })
+        .await
+        .map_err(|e| format!("History task failed: {e}"))?
     }
 
-    /// Loads all history entries from the persistent store.
+    /// Prepares a connection: lock timeout, SQL functions, schema and entry
+    /// migration.
+    ///
+    /// Schema upgrades run in an immediate transaction so that two
+    /// instances opening an old database do not both apply them.
+    fn init(conn: Connection) -> Result<Self, rusqlite::Error> {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        // `fold_case` lowercases with Unicode rules; the built-in `lower()`
+        // and `LIKE` only fold ASCII.
+        conn.create_scalar_function(
+            "fold_case",
+            1,
+            FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+            |ctx| Ok(ctx.get::<Option<String>>(0)?.map(|s| s.to_lowercase())),
+        )?;
+        let version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+        if version < SCHEMA_VERSION {
+            let tx = Transaction::new_unchecked(&conn, TransactionBehavior::Immediate)?;
+            let version: i32 = tx.pragma_query_value(None, "user_version", |row| row.get(0))?;
+            if version < 1 {
+                tx.execute_batch(SCHEMA_V1)?;
+            }
+            if version < 2 {
+                tx.execute_batch(SCHEMA_V2)?;
+                let migrated = migrate_entries(&tx)?;
+                if migrated > 0 {
+                    log::info!("[BE] history_store: migrated {} entries", migrated);
+                }
+            }
+            tx.pragma_update(None, "user_version", SCHEMA_VERSION)?;
+            tx.commit()?;
+        }
+        Ok(Self { conn })
+    }
+
+    /// Imports the entries of the legacy `history.json` store.
+    ///
+    /// The file is renamed once its entries are in the database, so the
+    /// import runs only once. Entries already present (same ID) are kept.
+    ///
+    /// # Returns
     ///
-    /// Retrieves the entries array from the store and deserializes it
-    /// into a vector of `HistoryEntry` structures.
+    /// Number of entries imported (0 when there is no legacy file).
+    fn import_legacy_json(&self, path: &Path) -> Result<usize, String> {
+        if !path.exists() {
+            return Ok(0);
+        }
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut store: serde_json::Value =
+            serde_json::from_str(&content).map_err(|e| e.to_string())?;
+        let mut entries: Vec<HistoryEntry> = match store.get_mut(LEGACY_ENTRIES_KEY) {
+            Some(value) => serde_json::from_value(value.take()).map_err(|e| e.to_string())?,
+            None => Vec::new(),
+        };
+
+        // The array is newest first; insert oldest first to keep the order.
+        let tx = self
+            .conn
+            .unchecked_transaction()
+            .map_err(|e| e.to_string())?;
+        for entry in entries.iter_mut().rev() {
+            entry.migrate();
+            insert(&tx, entry, true).map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+
+        std::fs::rename(path, path.with_extension("json.migrated")).map_err(|e| e.to_string())?;
+        log::info!(
+            "[BE] history_store: imported {} entries from history.json",
+            entries.len()
+        );
+        Ok(entries.len())
+    }
+
+    /// Loads all history entries, newest first.
     ///
     /// # Returns
     ///
@@ -58,55 +234,56 @@ impl HistoryStore {
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - The stored value is not a valid JSON array
-    /// - Deserialization into `HistoryEntry` fails
+    /// Returns an error if the query fails.
     pub fn load(&self) -> Result<Vec<HistoryEntry>, String> {
-        let entries_value = self.store.get(ENTRIES_KEY).unwrap_or(json!([]));
-        serde_json::from_value(entries_value).map_err(|e| e.to_string())
+        self.query(
+            &format!("SELECT {COLUMNS} FROM history ORDER BY seq DESC"),
+            Vec::new(),
+        )
     }
 
-    /// Saves history entries to the persistent store with atomic write.
+    /// Adds a single entry as the newest one.
     ///
-    /// Serializes the entries vector and writes it to the store with version
-    /// information. The write operation is atomic via tauri-plugin-store.
+    /// An entry with the same ID is updated in place instead. Entries of an
+    /// older version are migrated first.
     ///
     /// # Arguments
     ///
-    /// * `entries` - Vector of history entries to save
-    ///
-    /// # Returns
-    ///
-    /// Returns `Ok(())` on success.
+    /// * `entry` - The history entry to add
     ///
     /// # Errors
     ///
-    /// Returns an error if:
-    /// - Serialization to JSON fails
-    /// - Store write operation fails
-    pub fn save(&self, entries: &Vec<HistoryEntry>) -> Result<(), String> {
-        let entries_value = serde_json::to_value(entries).map_err(|e| e.to_string())?;
-
-        self.store.set(VERSION_KEY, CURRENT_VERSION);
-        self.store.set(ENTRIES_KEY, entries_value);
-        self.store.save().map_err(|e| e.to_string())
+    /// Returns an error if the insert fails.
+    pub fn add_entry(&self, mut entry: HistoryEntry) -> Result<(), String> {
+        entry.migrate();
+        insert(&self.conn, &entry, false).map_err(|e| e.to_string())
     }
 
-    /// Adds a single entry to the beginning of history.
+    /// Saves the status and output path of existing entries in one
+    /// transaction.
     ///
-    /// Inserts the new entry at index 0 (newest first).
+    /// Only these two columns are written, so other fields changed since
+    /// the entries were loaded are kept, and entries removed meanwhile
+    /// stay removed.
     ///
     /// # Arguments
     ///
-    /// * `entry` - The history entry to add
+    /// * `entries` - The changed history entries
     ///
     /// # Errors
     ///
-    /// Returns an error if loading or saving fails.
-    pub fn add_entry(&self, entry: HistoryEntry) -> Result<(), String> {
-        let mut entries = self.load()?;
-        entries.insert(0, entry);
-        self.save(&entries)
+    /// Returns an error if an update fails; no entry is changed then.
+    pub fn update_locations(&self, entries: &[HistoryEntry]) -> Result<(), String> {
+        let tx = Transaction::new_unchecked(&self.conn, TransactionBehavior::Immediate)
+            .map_err(|e| e.to_string())?;
+        for entry in entries {
+            tx.execute(
+                "UPDATE history SET status = ?1, output_path = ?2 WHERE id = ?3",
+                params![entry.status, entry.output_path, entry.id],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
     }
 
     /// Removes an entry by ID.
@@ -119,21 +296,24 @@ impl HistoryStore {
     ///
     /// # Errors
     ///
-    /// Returns an error only if loading or saving fails.
+    /// Returns an error only if the delete fails.
     pub fn remove_entry(&self, id: &str) -> Result<(), String> {
-        let mut entries = self.load()?;
-        entries.retain(|e| e.id != id);
-        self.save(&entries)
+        self.conn
+            .execute("DELETE FROM history WHERE id = ?1", params![id])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     }
 
     /// Removes all history entries from the store.
     ///
     /// # Errors
     ///
-    /// Returns an error if saving fails.
+    /// Returns an error if the delete fails.
     pub fn clear(&self) -> Result<(), String> {
-        self.store.set(ENTRIES_KEY, json!([]));
-        self.store.save().map_err(|e| e.to_string())
+        self.conn
+            .execute("DELETE FROM history", [])
+            .map(|_| ())
+            .map_err(|e| e.to_string())
     }
 
     /// Retrieves all history entries from the store.
@@ -151,53 +331,537 @@ impl HistoryStore {
 
     /// Searches history entries with optional query string and filters.
     ///
+    /// Results are newest first unless `sort_by` / `sort_order` say
+    /// otherwise; a failing query returns no entries.
+    ///
     /// # Filtering Logic
     ///
-    /// - **Query**: Searches case-insensitive in title and URL
+    /// - **Query**: Searches case-insensitive in title, uploader and URL
     /// - **Status**: Filters by status ("completed", "failed", or "all")
-    /// - **Date range**: Filters entries after `date_from` (if provided)
+    /// - **Date range**: Filters entries between `date_from` and `date_to`
+    ///   (both inclusive; a bare date compares by day)
+    /// - **Quality / uploader**: Exact match (uploader ignores case)
+    /// - **Size range**: Filters by `min_file_size` / `max_file_size`;
+    ///   entries without a recorded size are excluded by either bound
+    /// - **Pagination**: `limit` and `offset` apply after sorting
     pub fn search(
         &self,
         query: Option<String>,
         filters: Option<HistoryFilters>,
     ) -> Vec<HistoryEntry> {
-        let entries = self.get_all();
         let filters = filters.unwrap_or_default();
+        let mut clauses: Vec<&str> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
 
-        entries
-            .into_iter()
-            .filter(|entry| {
-                // Query search: match title or URL case-insensitively
-                if let Some(q) = query.as_ref().filter(|s| !s.is_empty()) {
-                    let query_lower = q.to_lowercase();
-                    let matches = entry.title.to_lowercase().contains(&query_lower)
-                        || entry.url.to_lowercase().contains(&query_lower);
-                    if !matches {
-                        return false;
-                    }
-                }
+        // Query search: substring of title, uploader or URL
+        if let Some(q) = query.as_ref().filter(|s| !s.is_empty()) {
+            if q.chars().count() >= MIN_FTS_QUERY_CHARS {
+                clauses.push("seq IN (SELECT rowid FROM history_fts WHERE history_fts MATCH ?)");
+                values.push(Value::Text(format!("\"{}\"", q.replace('"', "\"\""))));
+            } else {
+                clauses.push(
+                    "(instr(fold_case(title), ?) > 0 OR instr(fold_case(uploader), ?) > 0 \
+                     OR instr(fold_case(url), ?) > 0)",
+                );
+                let folded = Value::Text(q.to_lowercase());
+                values.extend([folded.clone(), folded.clone(), folded]);
+            }
+        }
 
-                // Status filter
-                if let Some(status) = filters.status.as_ref().filter(|s| *s != "all") {
-                    let entry_status = if entry.status == "success" || entry.status == "completed" {
-                        "completed"
-                    } else {
-                        entry.status.as_str()
-                    };
-                    if entry_status != status {
-                        return false;
-                    }
-                }
+        // Status filter ("success" is the legacy spelling of "completed")
+        if let Some(status) = filters.status.as_ref().filter(|s| *s != "all") {
+            if status == "completed" {
+                clauses.push("status IN ('completed', 'success')");
+            } else {
+                clauses.push("status = ?");
+                values.push(Value::Text(status.clone()));
+            }
+        }
 
-                // Date range filter
-                if let Some(date_from) = filters.date_from.as_ref() {
-                    if &entry.downloaded_at < date_from {
-                        return false;
-                    }
-                }
+        // Date range filter
+        if let Some(date_from) = filters.date_from.as_ref() {
+            clauses.push("downloaded_at >= ?");
+            values.push(Value::Text(date_from.clone()));
+        }
+        // Compare only as many characters as given, so a bare date includes
+        // every entry of that day.
+        if let Some(date_to) = filters.date_to.as_ref() {
+            clauses.push("substr(downloaded_at, 1, length(?)) <= ?");
+            values.push(Value::Text(date_to.clone()));
+            values.push(Value::Text(date_to.clone()));
+        }
 
-                true
+        if let Some(quality) = filters.quality.as_ref() {
+            clauses.push("quality = ?");
+            values.push(Value::Text(quality.clone()));
+        }
+        if let Some(uploader) = filters.uploader.as_ref() {
+            clauses.push("uploader = ? COLLATE NOCASE");
+            values.push(Value::Text(uploader.clone()));
+        }
+
+        // Size range filter (bounds beyond SQLite's integer range are
+        // clamped instead of wrapping negative)
+        if let Some(min) = filters.min_file_size {
+            clauses.push("file_size >= ?");
+            values.push(Value::Integer(i64::try_from(min).unwrap_or(i64::MAX)));
+        }
+        if let Some(max) = filters.max_file_size {
+            clauses.push("file_size <= ?");
+            values.push(Value::Integer(i64::try_from(max).unwrap_or(i64::MAX)));
+        }
+
+        let where_clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        let direction = match filters.sort_order.unwrap_or_default() {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        };
+        // `seq` breaks ties so pages stay stable
+        let order_by = match filters.sort_by.unwrap_or_default() {
+            HistorySortField::Added => format!("seq {direction}"),
+            HistorySortField::DownloadedAt => {
+                format!("downloaded_at {direction}, seq {direction}")
+            }
+            HistorySortField::Title => format!("title COLLATE NOCASE {direction}, seq {direction}"),
+            HistorySortField::FileSize => format!("file_size {direction}, seq {direction}"),
+        };
+        let pagination = match (filters.limit, filters.offset) {
+            (None, None) => String::new(),
+            (limit, offset) => format!(
+                "LIMIT {} OFFSET {}",
+                limit.map_or(-1, i64::from),
+                offset.unwrap_or(0)
+            ),
+        };
+        let sql = format!(
+            "SELECT {COLUMNS} FROM history {where_clause} ORDER BY {order_by} {pagination}"
+        );
+        self.query(&sql, values).unwrap_or_else(|e| {
+            log::warn!("[BE] history_store: search failed: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// Runs a `SELECT {COLUMNS}` query and maps the rows to entries.
+    fn query(&self, sql: &str, values: Vec<Value>) -> Result<Vec<HistoryEntry>, String> {
+        select(&self.conn, sql, values).map_err(|e| e.to_string())
+    }
+}
+
+/// Runs a `SELECT {COLUMNS}` query on `conn`.
+fn select(conn: &Connection, sql: &str, values: Vec<Value>) -> rusqlite::Result<Vec<HistoryEntry>> {
+    let mut stmt = conn.prepare(sql)?;
+    let rows = stmt.query_map(params_from_iter(values), entry_from_row)?;
+    rows.collect()
+}
+
+/// Upgrades stored entries of older versions (see [`HistoryEntry::migrate`]).
+///
+/// # Returns
+///
+/// Number of entries changed.
+fn migrate_entries(conn: &Connection) -> rusqlite::Result<usize> {
+    let entries = select(
+        conn,
+        &format!("SELECT {COLUMNS} FROM history WHERE version != ?"),
+        vec![Value::Text(CURRENT_HISTORY_VERSION.to_string())],
+    )?;
+    let mut migrated = 0;
+    for mut entry in entries {
+        if entry.migrate() {
+            conn.execute(
+                "UPDATE history SET page = ?1, version = ?2 WHERE id = ?3",
+                params![entry.page, entry.version, entry.id],
+            )?;
+            migrated += 1;
+        }
+    }
+    Ok(migrated)
+}
+
+/// Inserts an entry, replacing (or with `keep_existing`, keeping) a row
+/// with the same ID.
+///
+/// Uses an upsert rather than `INSERT OR REPLACE`, whose implicit delete
+/// would not fire the FTS trigger.
+fn insert(conn: &Connection, entry: &HistoryEntry, keep_existing: bool) -> rusqlite::Result<()> {
+    let conflict = if keep_existing {
+        "DO NOTHING"
+    } else {
+        "DO UPDATE SET title = excluded.title, uploader = excluded.uploader, \
+         bvid = excluded.bvid, cid = excluded.cid, url = excluded.url, \
+         downloaded_at = excluded.downloaded_at, status = excluded.status, \
+         file_size = excluded.file_size, quality = excluded.quality, \
+         thumbnail_url = excluded.thumbnail_url, mode = excluded.mode, \
+         stream_files = excluded.stream_files, version = excluded.version, \
+         page = excluded.page, codec = excluded.codec, \
+         duration_seconds = excluded.duration_seconds, output_path = excluded.output_path"
+    };
+    let stream_files = serde_json::to_string(&entry.stream_files).unwrap_or_else(|_| "[]".into());
+    conn.execute(
+        &format!(
+            "INSERT INTO history ({COLUMNS}) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, \
+                     ?15, ?16, ?17, ?18) \
+             ON CONFLICT (id) {conflict}"
+        ),
+        params![
+            entry.id,
+            entry.title,
+            entry.uploader,
+            entry.bvid,
+            entry.cid,
+            entry.url,
+            entry.downloaded_at,
+            entry.status,
+            entry.file_size.map(|size| size as i64),
+            entry.quality,
+            entry.thumbnail_url,
+            entry.mode,
+            stream_files,
+            entry.version,
+            entry.page,
+            entry.codec,
+            entry.duration_seconds,
+            entry.output_path,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Maps a row selected with [`COLUMNS`] to an entry.
+fn entry_from_row(row: &Row) -> rusqlite::Result<HistoryEntry> {
+    let stream_files: String = row.get(12)?;
+    Ok(HistoryEntry {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        uploader: row.get(2)?,
+        bvid: row.get(3)?,
+        cid: row.get(4)?,
+        url: row.get(5)?,
+        downloaded_at: row.get(6)?,
+        status: row.get(7)?,
+        file_size: row.get::<_, Option<i64>>(8)?.map(|size| size as u64),
+        quality: row.get(9)?,
+        thumbnail_url: row.get(10)?,
+        mode: row.get(11)?,
+        stream_files: serde_json::from_str(&stream_files).unwrap_or_default(),
+        version: row.get(13)?,
+        page: row.get(14)?,
+        codec: row.get(15)?,
+        duration_seconds: row.get(16)?,
+        output_path: row.get(17)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn memory_store() -> HistoryStore {
+        HistoryStore::init(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn entry(id: &str, title: &str, status: &str, downloaded_at: &str) -> HistoryEntry {
+        serde_json::from_value(json!({
+            "id": id,
+            "title": title,
+            "uploader": "某UP主",
+            "bvid": "BV1xx411c7XD",
+            "url": format!("https://www.bilibili.com/video/BV1xx411c7XD?p={id}"),
+            "downloadedAt": downloaded_at,
+            "status": status,
+            "fileSize": 1024,
+            "quality": "1080P",
+            "thumbnailUrl": null,
+            "streamFiles": ["a.video.m4v"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn entries_round_trip_newest_first() {
+        let store = memory_store();
+        store
+            .add_entry(entry("1", "First", "completed", "2026-01-01T00:00:00Z"))
+            .unwrap();
+        store
+            .add_entry(entry("2", "Second", "failed", "2026-01-02T00:00:00Z"))
+            .unwrap();
+        let mut updated = entry("1", "First (renamed)", "completed", "2026-01-01T00:00:00Z");
+        updated.file_size = Some(u64::MAX / 2);
+        store.add_entry(updated).unwrap();
+
+        let entries = store.load().unwrap();
+        assert_eq!(
+            entries.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(),
+            ["2", "1"]
+        );
+        assert_eq!(entries[1].title, "First (renamed)");
+        assert_eq!(entries[1].file_size, Some(u64::MAX / 2));
+        assert_eq!(entries[1].stream_files, ["a.video.m4v"]);
+
+        store.remove_entry("2").unwrap();
+        store.remove_entry("missing").unwrap();
+        assert_eq!(store.get_all().len(), 1);
+        store.clear().unwrap();
+        assert!(store.get_all().is_empty());
+    }
+
+    #[test]
+    fn update_locations_writes_only_status_and_path() {
+        let store = memory_store();
+        let loaded = entry("1", "First", "completed", "2026-01-01T00:00:00Z");
+        store.add_entry(loaded.clone()).unwrap();
+        let mut renamed = loaded.clone();
+        renamed.title = "Renamed".to_string();
+        store.add_entry(renamed).unwrap();
+
+        let mut moved = loaded;
+        moved.status = "missing".to_string();
+        moved.output_path = Some("/videos/First.mp4".to_string());
+        let removed = entry("2", "Removed", "missing", "2026-01-02T00:00:00Z");
+        store.update_locations(&[moved, removed]).unwrap();
+
+        let entries = store.load().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].title, "Renamed");
+        assert_eq!(entries[0].status, "missing");
+        assert_eq!(entries[0].output_path.as_deref(), Some("/videos/First.mp4"));
+    }
+
+    #[test]
+    fn search_matches_substrings_and_filters() {
+        let store = memory_store();
+        for (id, title, status, date) in [
+            (
+                "1",
+                "【合集】Rust 入门教程",
+                "success",
+                "2026-01-01T00:00:00Z",
+            ),
+            (
+                "2",
+                "Rust Async Deep Dive",
+                "completed",
+                "2026-02-01T00:00:00Z",
+            ),
+            ("3", "旅行 Vlog", "failed", "2026-03-01T00:00:00Z"),
+        ] {
+            store.add_entry(entry(id, title, status, date)).unwrap();
+        }
+        let ids = |query: Option<&str>, status: Option<&str>, date_from: Option<&str>| {
+            store
+                .search(
+                    query.map(str::to_string),
+                    Some(HistoryFilters {
+                        status: status.map(str::to_string),
+                        date_from: date_from.map(str::to_string),
+                        ..Default::default()
+                    }),
+                )
+                .into_iter()
+                .map(|e| e.id)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(ids(Some("rust"), None, None), ["2", "1"]);
+        assert_eq!(ids(Some("入门教"), None, None), ["1"]);
+        assert_eq!(ids(Some("旅行"), None, None), ["3"]);
+        assert_eq!(ids(Some("UP主"), None, None), ["3", "2", "1"]);
+        assert_eq!(ids(Some("?p=2"), None, None), ["2"]);
+        assert_eq!(ids(Some("%_"), None, None), Vec::<String>::new());
+        assert_eq!(ids(None, Some("completed"), None), ["2", "1"]);
+        assert_eq!(ids(None, Some("failed"), None), ["3"]);
+        assert_eq!(ids(None, Some("all"), Some("2026-02-01")), ["3", "2"]);
+        assert_eq!(ids(Some("deep"), Some("completed"), None), ["2"]);
+    }
+
+    #[test]
+    fn short_queries_fold_unicode_case() {
+        let store = memory_store();
+        store
+            .add_entry(entry(
+                "1",
+                "ÄRGER im Ωmega",
+                "completed",
+                "2026-01-01T00:00:00Z",
+            ))
+            .unwrap();
+        store
+            .add_entry(entry("2", "Ordinary", "completed", "2026-01-02T00:00:00Z"))
+            .unwrap();
+        for query in ["är", "ωM", "Är"] {
+            let ids: Vec<String> = store
+                .search(Some(query.to_string()), None)
+                .into_iter()
+                .map(|e| e.id)
+                .collect();
+            assert_eq!(ids, ["1"], "{query}");
+        }
+    }
+
+    #[test]
+    fn search_sorts_and_paginates() {
+        let store = memory_store();
+        for (id, title, uploader, quality, size, date) in [
+            ("1", "beta", "UpA", "1080P", 300, "2026-01-31T23:00:00Z"),
+            ("2", "Alpha", "upa", "720P", 100, "2026-01-15T00:00:00Z"),
+            ("3", "gamma", "UpB", "1080P", 200, "2026-02-01T00:00:00Z"),
+        ] {
+            let mut e = entry(id, title, "completed", date);
+            e.uploader = Some(uploader.to_string());
+            e.quality = Some(quality.to_string());
+            e.file_size = Some(size);
+            store.add_entry(e).unwrap();
+        }
+        let ids = |filters: HistoryFilters| {
+            store
+                .search(None, Some(filters))
+                .into_iter()
+                .map(|e| e.id)
+                .collect::<Vec<_>>()
+        };
+
+        let sorted = |sort_by, sort_order| HistoryFilters {
+            sort_by: Some(sort_by),
+            sort_order: Some(sort_order),
+            ..Default::default()
+        };
+        assert_eq!(ids(HistoryFilters::default()), ["3", "2", "1"]);
+        assert_eq!(
+            ids(sorted(HistorySortField::Title, SortOrder::Asc)),
+            ["2", "1", "3"]
+        );
+        assert_eq!(
+            ids(sorted(HistorySortField::FileSize, SortOrder::Desc)),
+            ["1", "3", "2"]
+        );
+        assert_eq!(
+            ids(sorted(HistorySortField::DownloadedAt, SortOrder::Asc)),
+            ["2", "1", "3"]
+        );
+
+        let date_to = |date_to: &str| HistoryFilters {
+            date_to: Some(date_to.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(ids(date_to("2026-01-31")), ["2", "1"]);
+        assert_eq!(ids(date_to("2026-01-31T12:00:00Z")), ["2"]);
+
+        assert_eq!(
+            ids(HistoryFilters {
+                quality: Some("1080P".to_string()),
+                ..Default::default()
+            }),
+            ["3", "1"]
+        );
+        assert_eq!(
+            ids(HistoryFilters {
+                uploader: Some("UPA".to_string()),
+                ..Default::default()
+            }),
+            ["2", "1"]
+        );
+        assert_eq!(
+            ids(HistoryFilters {
+                min_file_size: Some(150),
+                max_file_size: Some(300),
+                ..Default::default()
+            }),
+            ["3", "1"]
+        );
+        assert_eq!(
+            ids(HistoryFilters {
+                max_file_size: Some(u64::MAX),
+                ..Default::default()
             })
-            .collect()
+            .len(),
+            3
+        );
+        assert!(ids(HistoryFilters {
+            min_file_size: Some(u64::MAX),
+            ..Default::default()
+        })
+        .is_empty());
+
+        let page = |limit, offset| HistoryFilters {
+            limit,
+            offset,
+            ..sorted(HistorySortField::Title, SortOrder::Asc)
+        };
+        assert_eq!(ids(page(Some(2), None)), ["2", "1"]);
+        assert_eq!(ids(page(Some(2), Some(2))), ["3"]);
+        assert_eq!(ids(page(None, Some(1))), ["1", "3"]);
+    }
+
+    #[test]
+    fn version_1_databases_are_migrated() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(SCHEMA_V1).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO history (id, title, url, downloaded_at, status, version) \
+             VALUES ('1', 'Old', 'https://www.bilibili.com/video/BV1xx411c7XD?vd_source=x&p=3', \
+             '2026-01-01T00:00:00Z', 'completed', '1.0')",
+            [],
+        )
+        .unwrap();
+
+        let store = HistoryStore::init(conn).unwrap();
+        let entries = store.get_all();
+        assert_eq!(entries[0].page, Some(3));
+        assert_eq!(entries[0].version, CURRENT_HISTORY_VERSION);
+
+        let mut e = entry("2", "New", "completed", "2026-01-02T00:00:00Z");
+        e.codec = Some("HEVC".to_string());
+        e.duration_seconds = Some(754);
+        e.output_path = Some("/videos/New.mp4".to_string());
+        store.add_entry(e).unwrap();
+        let newest = &store.get_all()[0];
+        assert_eq!(newest.page, Some(2));
+        assert_eq!(newest.codec.as_deref(), Some("HEVC"));
+        assert_eq!(newest.duration_seconds, Some(754));
+        assert_eq!(newest.output_path.as_deref(), Some("/videos/New.mp4"));
+        assert_eq!(newest.version, CURRENT_HISTORY_VERSION);
+    }
+
+    #[test]
+    fn legacy_json_is_imported_once() {
+        let dir = std::env::temp_dir().join(format!("history_store_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(LEGACY_JSON_FILE);
+        std::fs::write(
+            &path,
+            json!({
+                "__version__": "1.0",
+                "entries": [
+                    entry("new", "Newer", "completed", "2026-01-02T00:00:00Z"),
+                    entry("old", "Older", "completed", "2026-01-01T00:00:00Z"),
+                ]
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        let store = memory_store();
+        assert_eq!(store.import_legacy_json(&path), Ok(2));
+        assert!(!path.exists());
+        assert_eq!(store.import_legacy_json(&path), Ok(0));
+        assert_eq!(
+            store
+                .get_all()
+                .iter()
+                .map(|e| e.id.as_str())
+                .collect::<Vec<_>>(),
+            ["new", "old"]
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
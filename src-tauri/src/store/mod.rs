@@ -1,7 +1,7 @@
 //! Storage modules
 //!
-//! This module contains persistent storage implementations using
-//! tauri-plugin-store for various data types.
+//! This module contains persistent storage implementations for various
+//! data types (download history in SQLite).
 
 pub mod history_store;
 
@@ -11,10 +11,11 @@ use std::sync::Mutex;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
 use once_cell::sync::Lazy;
-use reqwest::Client;
 use serde_json::{json, Map, Value};
 use tauri::AppHandle;
 
+use crate::utils::proxy::{self, ProxyTarget};
+
 /// Global tracking of download start times for duration calculation.
 static DOWNLOAD_STARTS: Lazy<Mutex<HashMap<String, Instant>>> =
     Lazy::new(|| Mutex::new(HashMap::new()));
@@ -339,7 +340,7 @@ async fn send_event_internal(
         GA_MEASUREMENT_ID.unwrap_or(""),
         GA_API_SECRET.unwrap_or("")
     );
-    let client = Client::new();
+    let client = proxy::plain_client(ProxyTarget::Api);
     let resp = client.post(url).json(&body).send().await;
     match resp {
         Ok(r) => {
@@ -0,0 +1,191 @@
+//! Global Bandwidth Limiter
+//!
+//! This module provides a process-wide token bucket shared by every download
+//! stream. Segment downloads and the single-stream fallback in
+//! [`crate::utils::downloads`] draw tokens for each received chunk, so the
+//! configured cap bounds the combined throughput of all parallel segments
+//! across all files instead of each connection individually.
+//!
+//! The cap comes from [`crate::models::settings::BandwidthLimit`] and is
+//! re-resolved on every draw, so schedule windows take effect without a
+//! restart.
+
+use crate::models::settings::{BandwidthLimit, Settings};
+use chrono::Timelike;
+use once_cell::sync::Lazy;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Global limiter shared by all downloads.
+pub static BANDWIDTH_LIMITER: Lazy<BandwidthLimiter> = Lazy::new(BandwidthLimiter::new);
+
+/// Token bucket limiting the combined download rate.
+///
+/// The bucket holds at most one second worth of tokens. A draw larger than
+/// the available tokens drives the balance negative and the caller sleeps
+/// until it would be repaid, which keeps concurrent callers queued fairly
+/// without a separate waiter list.
+pub struct BandwidthLimiter {
+    state: Mutex<LimiterState>,
+}
+
+struct LimiterState {
+    /// Configured limit (None = unlimited)
+    config: Option<BandwidthLimit>,
+    /// Rate resolved on the last draw, in bytes per second
+    rate: Option<u64>,
+    /// Current token balance in bytes (negative = outstanding debt)
+    tokens: f64,
+    /// Time of the last refill
+    last_refill: Instant,
+}
+
+impl BandwidthLimiter {
+    /// Creates an unlimited limiter.
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(LimiterState {
+                config: None,
+                rate: None,
+                tokens: 0.0,
+                last_refill: Instant::now(),
+            }),
+        }
+    }
+
+    /// Replaces the limit configuration.
+    ///
+    /// Called at startup and whenever settings are saved, so running
+    /// downloads pick up the new cap on their next chunk.
+    pub fn configure(&self, config: Option<BandwidthLimit>) {
+        if let Ok(mut st) = self.state.lock() {
+            log::info!("[BE] BandwidthLimiter: configured {:?}", config);
+            st.config = config;
+        }
+    }
+
+    /// Applies the bandwidth limit from application settings.
+    pub fn apply_settings(&self, settings: &Settings) {
+        self.configure(settings.bandwidth_limit.clone());
+    }
+
+    /// Draws `bytes` tokens, sleeping if the bucket is in debt.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - Number of bytes just received
+    ///
+    /// # Returns
+    ///
+    /// The time spent sleeping. Callers measuring throughput subtract it so
+    /// a deliberately throttled stream is not mistaken for a slow CDN.
+    pub async fn acquire(&self, bytes: u64) -> Duration {
+        let wait = self.reserve(bytes, Instant::now(), local_minute_of_day());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        wait
+    }
+
+    /// Refills the bucket, withdraws `bytes`, and returns the required wait.
+    fn reserve(&self, bytes: u64, now: Instant, minute_of_day: u32) -> Duration {
+        let Ok(mut st) = self.state.lock() else {
+            return Duration::ZERO;
+        };
+        let rate = st
+            .config
+            .as_ref()
+            .and_then(|c| c.bytes_per_sec_at(minute_of_day));
+
+        // Why: start each new rate with a full bucket. Carrying the old
+        //   balance over would make a switch from a high to a low cap (e.g.
+        //   entering a schedule window) repay the old debt at the new,
+        //   slower rate and stall downloads for a long time.
+        if rate != st.rate {
+            st.rate = rate;
+            st.tokens = rate.unwrap_or(0) as f64;
+            st.last_refill = now;
+        }
+        let Some(rate) = rate else {
+            return Duration::ZERO;
+        };
+
+        let rate = rate as f64;
+        let elapsed = now.saturating_duration_since(st.last_refill).as_secs_f64();
+        st.tokens = (st.tokens + elapsed * rate).min(rate);
+        st.last_refill = now;
+        st.tokens -= bytes as f64;
+
+        if st.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-st.tokens / rate)
+        }
+    }
+}
+
+impl Default for BandwidthLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the current local time as minutes since midnight.
+fn local_minute_of_day() -> u32 {
+    let now = chrono::Local::now();
+    now.hour() * 60 + now.minute()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(limit_kbps: u32) -> BandwidthLimiter {
+        let limiter = BandwidthLimiter::new();
+        limiter.configure(Some(BandwidthLimit {
+            enabled: true,
+            limit_kbps,
+            schedule: vec![],
+        }));
+        limiter
+    }
+
+    #[test]
+    fn unlimited_never_waits() {
+        let limiter = BandwidthLimiter::new();
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(u64::MAX / 2, now, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn burst_then_debt_is_repaid_at_rate() {
+        // 100 KB/s: first second of data passes, the next 50 KB waits 0.5 s
+        let limiter = limiter(100);
+        let now = Instant::now();
+        assert_eq!(limiter.reserve(100 * 1024, now, 0), Duration::ZERO);
+        let wait = limiter.reserve(50 * 1024, now, 0);
+        assert_eq!(wait, Duration::from_millis(500));
+    }
+
+    #[test]
+    fn tokens_refill_over_time() {
+        let limiter = limiter(100);
+        let start = Instant::now();
+        assert_eq!(limiter.reserve(100 * 1024, start, 0), Duration::ZERO);
+        let later = start + Duration::from_secs(1);
+        assert_eq!(limiter.reserve(100 * 1024, later, 0), Duration::ZERO);
+    }
+
+    #[test]
+    fn rate_change_resets_debt() {
+        let limiter = limiter(100);
+        let now = Instant::now();
+        limiter.reserve(1024 * 1024, now, 0);
+        limiter.configure(Some(BandwidthLimit {
+            enabled: true,
+            limit_kbps: 1,
+            schedule: vec![],
+        }));
+        assert_eq!(limiter.reserve(1024, now, 0), Duration::ZERO);
+    }
+}
@@ -13,6 +13,7 @@
 
 use crate::constants::{CDN_PROBE_CONCURRENCY, CDN_PROBE_TIMEOUT_SECS, REFERER, USER_AGENT};
 use crate::utils::downloads::is_media_content_type;
+use crate::utils::proxy::{self, ProxyTarget};
 use futures::stream::{FuturesUnordered, StreamExt};
 use reqwest::header;
 use std::sync::Arc;
@@ -229,7 +230,9 @@ pub async fn select_best_cdns(urls: Vec<String>, cookie: Option<String>) -> Prob
     // Why: probes must fail fast (CDN_PROBE_TIMEOUT_SECS) so a dead or slow CDN
     //   node does not add the 120s download-client timeout to pre-selection
     //   latency, which would defeat the purpose of ranking by responsiveness.
-    let client = match reqwest::Client::builder()
+    // Note: probes go through the CDN proxy like the downloads they rank,
+    //   otherwise the latency ordering would describe a different route.
+    let client = match proxy::apply(reqwest::Client::builder(), ProxyTarget::Cdn)
         .user_agent(USER_AGENT)
         .timeout(Duration::from_secs(CDN_PROBE_TIMEOUT_SECS))
         .build()
@@ -2,7 +2,13 @@
 //!
 //! This module provides functionality for selecting video streams based on
 //! codec priority preferences. It supports AV1, H.265 (HEVC), and H.264 (AVC)
-//! codecs with configurable fallback behavior.
+//! codecs with configurable fallback behavior, chosen per quality so HDR,
+//! Dolby Vision and 8K streams (often HEVC-only) stay selectable. It also
+//! classifies the dynamic range of video qualities and DASH audio
+//! streams (AAC, Dolby, Hi-Res FLAC) so the merge can pick an
+//! [`OutputContainer`] that supports the codec.
+
+use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
@@ -16,6 +22,16 @@ pub const CODECID_AVC: i16 = 7;
 pub const CODECID_HEVC: i16 = 12;
 pub const CODECID_AV1: i16 = 13;
 
+/// Returns the display label of a video codec ID (`AVC`, `HEVC`, `AV1`).
+pub fn codec_label(codecid: i16) -> Option<&'static str> {
+    match codecid {
+        CODECID_AVC => Some("AVC"),
+        CODECID_HEVC => Some("HEVC"),
+        CODECID_AV1 => Some("AV1"),
+        _ => None,
+    }
+}
+
 /// Video codec priority preference.
 ///
 /// Determines the order of codec selection with automatic fallback to
@@ -133,6 +149,170 @@ pub fn select_video_stream(
     None
 }
 
+/// Selects a codec for each quality of a DASH video stream list.
+///
+/// Applies [`select_video_stream`] to the codecs available at every quality
+/// instead of across the whole list, so a codec preference never hides a
+/// quality that is only offered in another codec (e.g. Dolby Vision is
+/// HEVC-only). Qualities without any priority codec are left out of the map.
+///
+/// # Arguments
+///
+/// * `priority` - Codec priority preference
+/// * `available` - `(quality ID, codec ID)` of every DASH video stream
+///
+/// # Examples
+///
+/// ```
+/// use bilibili_downloader_gui_lib::utils::codec::{
+///     select_video_stream_per_quality, VideoCodecPriority, CODECID_AVC, CODECID_HEVC,
+/// };
+/// let available = [(126, CODECID_HEVC), (80, CODECID_HEVC), (80, CODECID_AVC)];
+/// let selections = select_video_stream_per_quality(&VideoCodecPriority::AvcOnly, &available);
+/// assert_eq!(selections[&80].codecid, CODECID_AVC);
+/// assert!(!selections.contains_key(&126));
+/// ```
+pub fn select_video_stream_per_quality(
+    priority: &VideoCodecPriority,
+    available: &[(i32, i16)],
+) -> BTreeMap<i32, VideoStreamSelection> {
+    let mut codecs_by_quality: BTreeMap<i32, Vec<i16>> = BTreeMap::new();
+    for &(quality, codecid) in available {
+        codecs_by_quality.entry(quality).or_default().push(codecid);
+    }
+    codecs_by_quality
+        .into_iter()
+        .filter_map(|(quality, codecs)| {
+            select_video_stream(priority, &codecs).map(|selection| (quality, selection))
+        })
+        .collect()
+}
+
+/// Video quality ID of HDR (HDR10/HLG) streams.
+pub const QUALITY_HDR: i32 = 125;
+/// Video quality ID of Dolby Vision streams.
+pub const QUALITY_DOLBY_VISION: i32 = 126;
+/// Video quality ID of 8K streams.
+pub const QUALITY_8K: i32 = 127;
+
+/// Dynamic range of a video quality.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DynamicRange {
+    #[default]
+    Sdr,
+    /// HDR10 or HLG (qn 125)
+    Hdr,
+    /// Dolby Vision (qn 126)
+    DolbyVision,
+}
+
+impl DynamicRange {
+    /// Classifies a video quality ID.
+    pub fn from_quality(quality: i32) -> Self {
+        match quality {
+            QUALITY_HDR => Self::Hdr,
+            QUALITY_DOLBY_VISION => Self::DolbyVision,
+            _ => Self::Sdr,
+        }
+    }
+
+    /// Whether the stream carries HDR transfer characteristics.
+    pub fn is_hdr(self) -> bool {
+        self != Self::Sdr
+    }
+}
+
+/// Container of a merged download.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputContainer {
+    /// MP4 (`.mp4`, default): subtitles as `mov_text`, cover as an
+    /// `attached_pic` stream.
+    #[default]
+    Mp4,
+    /// Matroska (`.mkv`): subtitles copied as-is (ASS keeps its styling),
+    /// cover as an attachment. Required for Hi-Res FLAC audio.
+    Mkv,
+    /// QuickTime (`.mov`): same streams as MP4, for editing software.
+    Mov,
+}
+
+impl OutputContainer {
+    /// Returns the file extension of the container (without the dot).
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Mp4 => "mp4",
+            Self::Mkv => "mkv",
+            Self::Mov => "mov",
+        }
+    }
+
+    /// Whether the container is Matroska.
+    pub fn is_matroska(self) -> bool {
+        self == Self::Mkv
+    }
+
+    /// Returns the container to use for an audio stream: the preferred one,
+    /// or Matroska when the audio codec requires it.
+    pub fn for_audio(self, audio: AudioStreamKind) -> Self {
+        if audio.requires_matroska() {
+            Self::Mkv
+        } else {
+            self
+        }
+    }
+}
+
+/// Audio quality ID of the Dolby Atmos (E-AC-3) stream in `dash.dolby`.
+pub const AUDIO_QUALITY_DOLBY: i32 = 30250;
+/// Audio quality ID of the Hi-Res lossless (FLAC) stream in `dash.flac`.
+pub const AUDIO_QUALITY_HIRES: i32 = 30251;
+
+/// Codec family of a DASH audio stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AudioStreamKind {
+    /// Standard AAC audio (`dash.audio`)
+    #[default]
+    Aac,
+    /// Dolby Atmos, E-AC-3 (`dash.dolby`)
+    Dolby,
+    /// Hi-Res lossless, FLAC (`dash.flac`)
+    Flac,
+}
+
+impl AudioStreamKind {
+    /// Classifies an audio stream by its quality ID.
+    pub fn from_quality(quality: Option<i32>) -> Self {
+        match quality {
+            Some(AUDIO_QUALITY_DOLBY) => Self::Dolby,
+            Some(AUDIO_QUALITY_HIRES) => Self::Flac,
+            _ => Self::Aac,
+        }
+    }
+
+    /// Whether the merged file must be Matroska.
+    ///
+    /// FLAC in MP4 is still flagged experimental by ffmpeg and unsupported
+    /// by many players, so lossless downloads are written as MKV. E-AC-3 is
+    /// a registered MP4 codec (`ec-3`) and stays in MP4.
+    pub fn requires_matroska(self) -> bool {
+        self == Self::Flac
+    }
+
+    /// Returns the extension of the raw DASH stream (fragmented MP4) when it
+    /// is kept as a file of its own.
+    ///
+    /// Only AAC is conventionally stored as `.m4a`; E-AC-3 and FLAC in an
+    /// MP4 container use the generic `.mp4`.
+    pub fn stream_extension(self) -> &'static str {
+        match self {
+            Self::Aac => "m4a",
+            Self::Dolby | Self::Flac => "mp4",
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -231,4 +411,56 @@ mod tests {
         let default = VideoCodecPriority::default();
         assert_eq!(default, VideoCodecPriority::Av1First);
     }
+
+    #[test]
+    fn test_audio_stream_kind_container() {
+        let flac = AudioStreamKind::from_quality(Some(AUDIO_QUALITY_HIRES));
+        assert_eq!(OutputContainer::Mp4.for_audio(flac), OutputContainer::Mkv);
+        assert_eq!(OutputContainer::Mov.for_audio(flac).extension(), "mkv");
+        assert_eq!(
+            AudioStreamKind::from_quality(Some(AUDIO_QUALITY_DOLBY)),
+            AudioStreamKind::Dolby
+        );
+        assert_eq!(
+            OutputContainer::Mov.for_audio(AudioStreamKind::Dolby),
+            OutputContainer::Mov
+        );
+        assert_eq!(
+            AudioStreamKind::from_quality(Some(30280)),
+            AudioStreamKind::Aac
+        );
+        assert_eq!(AudioStreamKind::from_quality(None), AudioStreamKind::Aac);
+    }
+
+    #[test]
+    fn test_select_video_stream_per_quality_keeps_hdr() {
+        let available = [
+            (QUALITY_DOLBY_VISION, CODECID_HEVC),
+            (QUALITY_HDR, CODECID_AV1),
+            (QUALITY_HDR, CODECID_HEVC),
+            (80, CODECID_AV1),
+            (80, CODECID_AVC),
+        ];
+        let selections = select_video_stream_per_quality(&VideoCodecPriority::Av1First, &available);
+        assert_eq!(
+            selections[&QUALITY_DOLBY_VISION],
+            VideoStreamSelection {
+                codecid: CODECID_HEVC,
+                fallback: true,
+            }
+        );
+        assert_eq!(selections[&QUALITY_HDR].codecid, CODECID_AV1);
+        assert!(!selections[&80].fallback);
+    }
+
+    #[test]
+    fn test_dynamic_range_from_quality() {
+        assert_eq!(DynamicRange::from_quality(QUALITY_HDR), DynamicRange::Hdr);
+        assert_eq!(
+            DynamicRange::from_quality(QUALITY_DOLBY_VISION),
+            DynamicRange::DolbyVision
+        );
+        assert_eq!(DynamicRange::from_quality(QUALITY_8K), DynamicRange::Sdr);
+        assert!(!DynamicRange::from_quality(80).is_hdr());
+    }
 }
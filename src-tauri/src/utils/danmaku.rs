@@ -0,0 +1,672 @@
+//! Danmaku (bullet comment) parsing and ASS conversion
+//!
+//! Bilibili serves danmaku in two formats: the legacy XML document
+//! (`<d p="...">text</d>` per comment) and the segmented protobuf
+//! `DmSegMobileReply` returned by the `seg.so` endpoint. Both are parsed into
+//! [`Danmaku`] here, can be written back as XML for external players, and are
+//! laid out as scrolling/top/bottom ASS events for ffmpeg.
+
+use serde::{Deserialize, Serialize};
+
+/// ASS play resolution. Players scale the script to the actual video size.
+const PLAY_RES_X: f64 = 1920.0;
+const PLAY_RES_Y: f64 = 1080.0;
+
+/// Bilibili's "normal" comment font size; other sizes scale relative to it.
+const BASE_FONT_SIZE: f64 = 25.0;
+
+/// A single danmaku comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Danmaku {
+    /// Comment ID (`dmid`)
+    pub id: i64,
+    /// Appearance time in seconds from the start of the video
+    pub time: f64,
+    /// Display mode (1-3 scroll, 4 bottom, 5 top, 6 reverse, 7+ special)
+    pub mode: i32,
+    /// Font size as set by the sender (18 small, 25 normal, 36 large)
+    pub font_size: i32,
+    /// Colour as `0xRRGGBB`
+    pub color: u32,
+    /// Unix timestamp the comment was sent at
+    pub ctime: i64,
+    /// Comment pool (0 normal, 1 subtitle, 2 special)
+    pub pool: i32,
+    /// Hashed sender ID
+    pub mid_hash: String,
+    /// Smart-filter weight (0-10, higher is more likely to be shown)
+    pub weight: i32,
+    /// Comment text
+    pub text: String,
+}
+
+/// Where a comment is placed on screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Placement {
+    Scroll,
+    Top,
+    Bottom,
+}
+
+impl Danmaku {
+    /// Returns the on-screen placement, or `None` for advanced/code/BAS
+    /// comments that cannot be represented as plain ASS text.
+    fn placement(&self) -> Option<Placement> {
+        match self.mode {
+            1..=3 | 6 => Some(Placement::Scroll),
+            4 => Some(Placement::Bottom),
+            5 => Some(Placement::Top),
+            _ => None,
+        }
+    }
+}
+
+/// ASS rendering options for danmaku.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DanmakuOptions {
+    /// Font family name
+    pub font_name: String,
+    /// Font size (at 1080p) of a normal-sized comment; small and large
+    /// comments scale relative to it
+    pub font_size: u32,
+    /// Text opacity, from 0.0 (invisible) to 1.0 (opaque)
+    pub opacity: f64,
+    /// Maximum comments started per second of video (0 = no limit)
+    pub density: u32,
+    /// Fraction of the screen height used by scrolling lanes, from the top
+    pub scroll_area: f64,
+    /// Fraction of the screen height used by top and by bottom lanes
+    pub fixed_area: f64,
+    /// Seconds a scrolling comment takes to cross the screen
+    pub scroll_duration: f64,
+    /// Seconds a top/bottom comment stays on screen
+    pub fixed_duration: f64,
+}
+
+impl Default for DanmakuOptions {
+    fn default() -> Self {
+        Self {
+            font_name: "sans-serif".to_string(),
+            font_size: 48,
+            opacity: 0.8,
+            density: 0,
+            scroll_area: 1.0,
+            fixed_area: 0.5,
+            scroll_duration: 8.0,
+            fixed_duration: 4.0,
+        }
+    }
+}
+
+// ============================================================================
+// XML
+// ============================================================================
+
+/// Parses a Bilibili danmaku XML document.
+///
+/// The `p` attribute holds `time,mode,size,color,ctime,pool,midHash,dmid`
+/// (plus `weight` in newer documents). Malformed entries are skipped.
+pub fn parse_xml(xml: &str) -> Vec<Danmaku> {
+    let mut result = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find("<d p=\"") {
+        rest = &rest[start + 6..];
+        let Some(attr_end) = rest.find('"') else {
+            break;
+        };
+        let attrs = &rest[..attr_end];
+        rest = &rest[attr_end..];
+        let Some(text_start) = rest.find('>') else {
+            break;
+        };
+        rest = &rest[text_start + 1..];
+        let Some(text_end) = rest.find("</d>") else {
+            break;
+        };
+        let text = unescape_xml(&rest[..text_end]);
+        rest = &rest[text_end + 4..];
+
+        let fields: Vec<&str> = attrs.split(',').collect();
+        if fields.len() < 8 {
+            continue;
+        }
+        let (Ok(time), Ok(mode)) = (fields[0].parse::<f64>(), fields[1].parse::<i32>()) else {
+            continue;
+        };
+        result.push(Danmaku {
+            id: fields[7].parse().unwrap_or_default(),
+            time,
+            mode,
+            font_size: fields[2].parse().unwrap_or(BASE_FONT_SIZE as i32),
+            color: fields[3].parse().unwrap_or(0xFFFFFF),
+            ctime: fields[4].parse().unwrap_or_default(),
+            pool: fields[5].parse().unwrap_or_default(),
+            mid_hash: fields[6].to_string(),
+            weight: fields
+                .get(8)
+                .and_then(|w| w.parse().ok())
+                .unwrap_or_default(),
+            text,
+        });
+    }
+    result
+}
+
+/// Serializes comments as a Bilibili danmaku XML document.
+///
+/// Used to store comments fetched from `seg.so` in the same format as the
+/// XML endpoint, which external players understand.
+pub fn to_xml(cid: i64, danmaku: &[Danmaku]) -> String {
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<i><chatserver>chat.bilibili.com</chatserver>\
+         <chatid>{cid}</chatid><mission>0</mission><maxlimit>{}</maxlimit><state>0</state>\
+         <real_name>0</real_name><source>k-v</source>\n",
+        danmaku.len()
+    );
+    for d in danmaku {
+        xml.push_str(&format!(
+            "<d p=\"{:.5},{},{},{},{},{},{},{},{}\">{}</d>\n",
+            d.time,
+            d.mode,
+            d.font_size,
+            d.color,
+            d.ctime,
+            d.pool,
+            escape_xml(&d.mid_hash),
+            d.id,
+            d.weight,
+            escape_xml(&d.text)
+        ));
+    }
+    xml.push_str("</i>\n");
+    xml
+}
+
+fn escape_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let Some(semi) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..semi];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                rest = &rest[semi + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// ============================================================================
+// Protobuf (seg.so)
+// ============================================================================
+
+/// Parses one `DmSegMobileReply` protobuf segment from `seg.so`.
+///
+/// Only the fields of `DanmakuElem` that map onto [`Danmaku`] are read;
+/// unknown fields are skipped.
+///
+/// # Errors
+///
+/// Returns an error if the buffer is not valid protobuf.
+pub fn parse_segment(buf: &[u8]) -> Result<Vec<Danmaku>, String> {
+    let mut reader = ProtoReader::new(buf);
+    let mut result = Vec::new();
+    while let Some((field, wire)) = reader.key()? {
+        match (field, wire) {
+            (1, WIRE_LEN) => result.push(parse_elem(reader.bytes()?)?),
+            _ => reader.skip(wire)?,
+        }
+    }
+    Ok(result)
+}
+
+fn parse_elem(buf: &[u8]) -> Result<Danmaku, String> {
+    let mut reader = ProtoReader::new(buf);
+    let mut d = Danmaku {
+        id: 0,
+        time: 0.0,
+        mode: 1,
+        font_size: BASE_FONT_SIZE as i32,
+        color: 0xFFFFFF,
+        ctime: 0,
+        pool: 0,
+        mid_hash: String::new(),
+        weight: 0,
+        text: String::new(),
+    };
+    while let Some((field, wire)) = reader.key()? {
+        match (field, wire) {
+            (1, WIRE_VARINT) => d.id = reader.varint()? as i64,
+            (2, WIRE_VARINT) => d.time = reader.varint()? as i32 as f64 / 1000.0,
+            (3, WIRE_VARINT) => d.mode = reader.varint()? as i32,
+            (4, WIRE_VARINT) => d.font_size = reader.varint()? as i32,
+            (5, WIRE_VARINT) => d.color = reader.varint()? as u32,
+            (6, WIRE_LEN) => d.mid_hash = String::from_utf8_lossy(reader.bytes()?).into_owned(),
+            (7, WIRE_LEN) => d.text = String::from_utf8_lossy(reader.bytes()?).into_owned(),
+            (8, WIRE_VARINT) => d.ctime = reader.varint()? as i64,
+            (9, WIRE_VARINT) => d.weight = reader.varint()? as i32,
+            (11, WIRE_VARINT) => d.pool = reader.varint()? as i32,
+            _ => reader.skip(wire)?,
+        }
+    }
+    Ok(d)
+}
+
+const WIRE_VARINT: u8 = 0;
+const WIRE_I64: u8 = 1;
+const WIRE_LEN: u8 = 2;
+const WIRE_I32: u8 = 5;
+
+/// Minimal protobuf wire-format reader.
+struct ProtoReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ProtoReader<'a> {
+    fn new(buf: &'a [u8]) -> Self {
+        Self { buf, pos: 0 }
+    }
+
+    /// Reads the next field key, or `None` at the end of the buffer.
+    fn key(&mut self) -> Result<Option<(u64, u8)>, String> {
+        if self.pos >= self.buf.len() {
+            return Ok(None);
+        }
+        let key = self.varint()?;
+        Ok(Some((key >> 3, (key & 0x7) as u8)))
+    }
+
+    fn varint(&mut self) -> Result<u64, String> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .buf
+                .get(self.pos)
+                .ok_or_else(|| "ERR::DANMAKU_DECODE".to_string())?;
+            self.pos += 1;
+            value |= u64::from(byte & 0x7F) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err("ERR::DANMAKU_DECODE".to_string())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.buf.len())
+            .ok_or_else(|| "ERR::DANMAKU_DECODE".to_string())?;
+        let slice = &self.buf[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.varint()? as usize;
+        self.take(len)
+    }
+
+    fn skip(&mut self, wire: u8) -> Result<(), String> {
+        match wire {
+            WIRE_VARINT => self.varint().map(|_| ()),
+            WIRE_I64 => self.take(8).map(|_| ()),
+            WIRE_LEN => self.bytes().map(|_| ()),
+            WIRE_I32 => self.take(4).map(|_| ()),
+            _ => Err("ERR::DANMAKU_DECODE".to_string()),
+        }
+    }
+}
+
+// ============================================================================
+// ASS
+// ============================================================================
+
+/// A comment already placed on a lane.
+struct Placed {
+    start: f64,
+    width: f64,
+}
+
+/// Converts comments to an ASS script.
+///
+/// Comments are sorted by time, then assigned to the first free lane of
+/// their placement: scrolling lanes fill the top `scroll_area` of the screen,
+/// top lanes stack downwards and bottom lanes upwards within `fixed_area`.
+/// A scrolling lane is free once the previous comment has fully entered the
+/// screen and cannot be caught up by the new one. Comments with no free lane,
+/// or beyond the `density` limit for their second, are dropped.
+///
+/// # Arguments
+///
+/// * `danmaku` - Comments in any order
+/// * `options` - Rendering options
+/// * `max_duration_secs` - Drops comments starting at or after the video
+///   end when `Some(d)` with `d > 0.0`
+pub fn danmaku_to_ass(
+    danmaku: &[Danmaku],
+    options: &DanmakuOptions,
+    max_duration_secs: Option<f64>,
+) -> String {
+    let base_size = f64::from(options.font_size.max(1));
+    let lane_height = base_size * 1.2;
+    let lane_count =
+        |area: f64| ((PLAY_RES_Y * area.clamp(0.0, 1.0)) / lane_height).floor() as usize;
+    let mut scroll_lanes: Vec<Option<Placed>> = (0..lane_count(options.scroll_area).max(1))
+        .map(|_| None)
+        .collect();
+    let fixed_lanes = lane_count(options.fixed_area).max(1);
+    let mut top_lanes: Vec<f64> = vec![f64::MIN; fixed_lanes];
+    let mut bottom_lanes: Vec<f64> = vec![f64::MIN; fixed_lanes];
+
+    let mut sorted: Vec<&Danmaku> = danmaku
+        .iter()
+        .filter(|d| d.time >= 0.0)
+        .filter(|d| match max_duration_secs.filter(|&m| m > 0.0) {
+            Some(max) => d.time < max,
+            None => true,
+        })
+        .collect();
+    sorted.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+    let alpha = format!(
+        "{:02X}",
+        ((1.0 - options.opacity.clamp(0.0, 1.0)) * 255.0).round() as u8
+    );
+    let scroll_duration = options.scroll_duration.max(1.0);
+    let fixed_duration = options.fixed_duration.max(1.0);
+
+    let mut events = Vec::new();
+    let mut second = i64::MIN;
+    let mut started_in_second = 0u32;
+    for d in sorted {
+        let Some(placement) = d.placement() else {
+            continue;
+        };
+        let text = escape_ass(&d.text);
+        if text.is_empty() {
+            continue;
+        }
+        if options.density > 0 {
+            let s = d.time.floor() as i64;
+            if s != second {
+                second = s;
+                started_in_second = 0;
+            }
+            if started_in_second >= options.density {
+                continue;
+            }
+        }
+
+        let size = (base_size * f64::from(d.font_size.max(1)) / BASE_FONT_SIZE).round();
+        let width = text_width(&d.text, size);
+        let color = format!(
+            "{:02X}{:02X}{:02X}",
+            d.color & 0xFF,
+            (d.color >> 8) & 0xFF,
+            (d.color >> 16) & 0xFF
+        );
+        let style = format!("\\c&H{color}&\\fs{size}");
+
+        let event = match placement {
+            Placement::Scroll => {
+                let Some(lane) = scroll_lanes.iter().position(|lane| match lane {
+                    Some(prev) => scroll_lane_free(prev, d.time, width, scroll_duration),
+                    None => true,
+                }) else {
+                    continue;
+                };
+                scroll_lanes[lane] = Some(Placed {
+                    start: d.time,
+                    width,
+                });
+                let y = (lane as f64 * lane_height).round();
+                format!(
+                    "Dialogue: 0,{},{},Danmaku,,0,0,0,,{{\\an7\\move({},{},{},{}){style}}}{text}",
+                    format_ass_time(d.time),
+                    format_ass_time(d.time + scroll_duration),
+                    PLAY_RES_X,
+                    y,
+                    -width.round(),
+                    y
+                )
+            }
+            Placement::Top | Placement::Bottom => {
+                let lanes = if placement == Placement::Top {
+                    &mut top_lanes
+                } else {
+                    &mut bottom_lanes
+                };
+                let Some(lane) = lanes.iter().position(|&end| end <= d.time) else {
+                    continue;
+                };
+                lanes[lane] = d.time + fixed_duration;
+                let (align, y) = if placement == Placement::Top {
+                    (8, (lane as f64 * lane_height).round())
+                } else {
+                    (2, (PLAY_RES_Y - lane as f64 * lane_height).round())
+                };
+                format!(
+                    "Dialogue: 1,{},{},Danmaku,,0,0,0,,{{\\an{align}\\pos({},{}){style}}}{text}",
+                    format_ass_time(d.time),
+                    format_ass_time(d.time + fixed_duration),
+                    PLAY_RES_X / 2.0,
+                    y
+                )
+            }
+        };
+        started_in_second += 1;
+        events.push(event);
+    }
+
+    format!(
+        "\u{FEFF}[Script Info]\n\
+         ScriptType: v4.00+\n\
+         PlayResX: {PLAY_RES_X}\n\
+         PlayResY: {PLAY_RES_Y}\n\
+         WrapStyle: 2\n\
+         ScaledBorderAndShadow: yes\n\
+         \n\
+         [V4+ Styles]\n\
+         Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, \
+         Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, \
+         Shadow, Alignment, MarginL, MarginR, MarginV, Encoding\n\
+         Style: Danmaku,{},{},&H{alpha}FFFFFF,&H{alpha}FFFFFF,&H{alpha}000000,&H{alpha}000000,\
+         0,0,0,0,100,100,0,0,1,1.5,0,7,0,0,0,1\n\
+         \n\
+         [Events]\n\
+         Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text\n\
+         {}",
+        options.font_name,
+        base_size,
+        events
+            .iter()
+            .map(|e| format!("{e}\n"))
+            .collect::<String>()
+    )
+}
+
+/// Whether a new scrolling comment can follow `prev` on the same lane.
+fn scroll_lane_free(prev: &Placed, time: f64, width: f64, duration: f64) -> bool {
+    let prev_speed = (PLAY_RES_X + prev.width) / duration;
+    let speed = (PLAY_RES_X + width) / duration;
+    // The previous comment's tail must already be on screen...
+    let entered = prev.start + prev.width / prev_speed <= time;
+    // ...and the new head must not reach the left edge before that tail.
+    let no_catch_up = time + PLAY_RES_X / speed >= prev.start + duration;
+    entered && no_catch_up
+}
+
+/// Estimates rendered text width: full-width glyphs take `size`, ASCII half.
+fn text_width(text: &str, size: f64) -> f64 {
+    text.chars()
+        .map(|c| if c.is_ascii() { size * 0.5 } else { size })
+        .sum()
+}
+
+/// Keeps comment text from being parsed as ASS override tags.
+fn escape_ass(text: &str) -> String {
+    text.trim()
+        .replace(['\r', '\n'], " ")
+        .replace('\\', "＼")
+        .replace('{', "｛")
+        .replace('}', "｝")
+}
+
+fn format_ass_time(seconds: f64) -> String {
+    let cs = (seconds.max(0.0) * 100.0).round() as u64;
+    format!(
+        "{}:{:02}:{:02}.{:02}",
+        cs / 360_000,
+        (cs / 6000) % 60,
+        (cs / 100) % 60,
+        cs % 100
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scroll(time: f64, text: &str) -> Danmaku {
+        Danmaku {
+            id: 1,
+            time,
+            mode: 1,
+            font_size: 25,
+            color: 0xFF0000,
+            ctime: 0,
+            pool: 0,
+            mid_hash: "abc".to_string(),
+            weight: 0,
+            text: text.to_string(),
+        }
+    }
+
+    fn dialogues(ass: &str) -> Vec<&str> {
+        ass.lines().filter(|l| l.starts_with("Dialogue:")).collect()
+    }
+
+    #[test]
+    fn xml_round_trips() {
+        let xml = r#"<?xml version="1.0"?><i><chatid>1</chatid>
+            <d p="12.5,5,25,16777215,1700000000,0,abcd,42,7">a &amp; &lt;b&gt; &#x4F60;</d>
+            <d p="1,1,25,255,1700000000,0,ef,43">old format</d>
+            <d p="broken">skip</d></i>"#;
+        let parsed = parse_xml(xml);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].text, "a & <b> 你");
+        assert_eq!(parsed[0].mode, 5);
+        assert_eq!(parsed[0].id, 42);
+        assert_eq!(parsed[0].weight, 7);
+        assert_eq!(parsed[1].weight, 0);
+        assert_eq!(parse_xml(&to_xml(1, &parsed)), parsed);
+    }
+
+    #[test]
+    fn parses_protobuf_segment() {
+        // DanmakuElem { id: 9, progress: 1500, mode: 4, content: "hi" }
+        let elem = [
+            0x08, 0x09, 0x10, 0xDC, 0x0B, 0x18, 0x04, 0x3A, 0x02, b'h', b'i',
+        ];
+        let mut reply = vec![0x0A, elem.len() as u8];
+        reply.extend_from_slice(&elem);
+        // Unknown length-delimited field is skipped.
+        reply.extend_from_slice(&[0x12, 0x01, 0x00]);
+
+        let parsed = parse_segment(&reply).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].id, 9);
+        assert_eq!(parsed[0].time, 1.5);
+        assert_eq!(parsed[0].mode, 4);
+        assert_eq!(parsed[0].text, "hi");
+        assert!(parse_segment(&[0x0A, 0x05, 0x08]).is_err());
+    }
+
+    #[test]
+    fn scrolling_comments_use_separate_lanes() {
+        let options = DanmakuOptions::default();
+        let ass = danmaku_to_ass(
+            &[scroll(0.0, "first"), scroll(0.1, "second")],
+            &options,
+            None,
+        );
+        let lines = dialogues(&ass);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\\move(1920,0,"));
+        assert!(lines[1].contains("\\move(1920,58,"));
+        assert!(lines[0].contains("\\c&H0000FF&"));
+    }
+
+    #[test]
+    fn density_and_lane_limits_drop_comments() {
+        let options = DanmakuOptions {
+            density: 2,
+            ..Default::default()
+        };
+        let many: Vec<Danmaku> = (0..5)
+            .map(|i| scroll(i as f64 * 0.1, "a long comment"))
+            .collect();
+        assert_eq!(dialogues(&danmaku_to_ass(&many, &options, None)).len(), 2);
+
+        let options = DanmakuOptions {
+            scroll_area: 0.0,
+            ..Default::default()
+        };
+        assert_eq!(dialogues(&danmaku_to_ass(&many, &options, None)).len(), 1);
+    }
+
+    #[test]
+    fn fixed_comments_and_escaping() {
+        let mut top = scroll(1.0, "{\\b1}top");
+        top.mode = 5;
+        let mut bottom = scroll(1.0, "bottom");
+        bottom.mode = 4;
+        let mut special = scroll(1.0, "code");
+        special.mode = 8;
+        let options = DanmakuOptions {
+            opacity: 0.5,
+            ..Default::default()
+        };
+        let ass = danmaku_to_ass(&[top, bottom, special], &options, Some(10.0));
+        let lines = dialogues(&ass);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("\\an8\\pos(960,0)"));
+        assert!(lines[0].ends_with("｛＼b1｝top"));
+        assert!(lines[1].contains("\\an2\\pos(960,1080)"));
+        assert!(ass.contains("&H80FFFFFF"));
+    }
+}
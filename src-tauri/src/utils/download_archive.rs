@@ -0,0 +1,385 @@
+//! Download Archive
+//!
+//! This module keeps a yt-dlp-style archive of finished downloads in
+//! `download_archive.txt` (app data directory), one item per line:
+//!
+//! ```text
+//! bilibili BV1xx411c7XD 279786 80
+//! bilibili-video BV1xx411c7XD 279786 80
+//! bilibili-audio BV1xx411c7XD 279786 30280
+//! bilibili BV1yy411c7XE 123456
+//! ```
+//!
+//! The fields are the kind (`bilibili` for videos with sound,
+//! `bilibili-video` for silent video-only downloads, `bilibili-audio` for
+//! audio-only downloads), BV ID, CID and quality ID. A missing quality
+//! matches any quality; such lines are written when importing history
+//! entries that did not record one.
+//!
+//! `download_video` checks the archive before any network work and, in
+//! [`ArchiveMode::Skip`], fails with [`ARCHIVED_ERROR`] instead of
+//! downloading (see [`archived_error`]). Every finished download is
+//! recorded. The file is plain text so it can be edited or
+//! shared with other tools.
+
+use crate::models::history::HistoryEntry;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+/// Error code of a download skipped because it is already archived.
+pub const ARCHIVED_ERROR: &str = "ERR::ARCHIVED";
+
+/// Serializes read-modify-append cycles of parallel downloads.
+static ARCHIVE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// How a download treats an item that is already in the archive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ArchiveMode {
+    /// Skip the download and report the existing output path
+    #[default]
+    Skip,
+    /// Download again next to the existing file (`name (1).mp4`)
+    Redownload,
+    /// Download again and replace the existing file
+    Overwrite,
+}
+
+/// What an archive line records.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// Video download with sound (merged, or merged with the raw streams
+    /// kept)
+    Video,
+    /// Silent video-only download
+    VideoOnly,
+    /// Audio-only download
+    Audio,
+}
+
+impl ArchiveKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ArchiveKind::Video => "bilibili",
+            ArchiveKind::VideoOnly => "bilibili-video",
+            ArchiveKind::Audio => "bilibili-audio",
+        }
+    }
+
+    /// Returns the kind of a history entry from its recorded mode label.
+    pub fn from_history_mode(mode: Option<&str>) -> Self {
+        match mode {
+            Some("audio") => ArchiveKind::Audio,
+            Some("video") => ArchiveKind::VideoOnly,
+            _ => ArchiveKind::Video,
+        }
+    }
+}
+
+/// A single archive line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveKey {
+    pub kind: ArchiveKind,
+    pub bvid: String,
+    pub cid: i64,
+    /// Video quality ID (audio quality ID for [`ArchiveKind::Audio`]);
+    /// `None` matches any quality.
+    pub quality: Option<i32>,
+}
+
+impl ArchiveKey {
+    /// Parses an archive line. Blank lines, `#` comments and lines of other
+    /// kinds (e.g. from yt-dlp) yield `None`.
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let kind = match fields.next()? {
+            "bilibili" => ArchiveKind::Video,
+            "bilibili-video" => ArchiveKind::VideoOnly,
+            "bilibili-audio" => ArchiveKind::Audio,
+            _ => return None,
+        };
+        let bvid = fields.next()?.to_string();
+        let cid = fields.next()?.parse().ok()?;
+        let quality = match fields.next() {
+            Some(q) => Some(q.parse().ok()?),
+            None => None,
+        };
+        Some(Self {
+            kind,
+            bvid,
+            cid,
+            quality,
+        })
+    }
+
+    /// Returns `true` if this archived item satisfies `requested`.
+    ///
+    /// A `None` quality on either side matches any quality, so a download
+    /// of the best available quality skips any archived copy.
+    pub fn matches(&self, requested: &ArchiveKey) -> bool {
+        self.kind == requested.kind
+            && self.bvid == requested.bvid
+            && self.cid == requested.cid
+            && (self.quality.is_none()
+                || requested.quality.is_none()
+                || self.quality == requested.quality)
+    }
+}
+
+impl fmt::Display for ArchiveKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.kind.as_str(), self.bvid, self.cid)?;
+        if let Some(quality) = self.quality {
+            write!(f, " {quality}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Reads all archive lines. A missing file is an empty archive.
+///
+/// # Errors
+///
+/// Returns an error if the file exists but cannot be read.
+pub async fn load(path: &Path) -> Result<Vec<ArchiveKey>, String> {
+    match tokio::fs::read_to_string(path).await {
+        Ok(content) => Ok(content.lines().filter_map(ArchiveKey::parse).collect()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(format!("Failed to read download archive: {e}")),
+    }
+}
+
+/// Returns `true` if the archive has an item matching `requested`.
+///
+/// An unreadable archive is logged and treated as empty so downloads are
+/// never blocked by it.
+pub async fn contains(path: &Path, requested: &ArchiveKey) -> bool {
+    match load(path).await {
+        Ok(keys) => keys.iter().any(|key| key.matches(requested)),
+        Err(e) => {
+            log::warn!("[BE] download_archive: {}", e);
+            false
+        }
+    }
+}
+
+/// Appends items that are not archived yet.
+///
+/// # Arguments
+///
+/// * `path` - Archive file
+/// * `keys` - Items to record; exact duplicates are written once
+///
+/// # Returns
+///
+/// Number of lines appended.
+///
+/// # Errors
+///
+/// Returns an error if the archive cannot be read or written.
+pub async fn record(path: &Path, keys: &[ArchiveKey]) -> Result<usize, String> {
+    let _guard = ARCHIVE_LOCK.lock().await;
+    let mut archived = load(path).await?;
+    let mut lines = String::new();
+    for key in keys {
+        if !archived.contains(key) {
+            lines.push_str(&format!("{key}\n"));
+            archived.push(key.clone());
+        }
+    }
+    if lines.is_empty() {
+        return Ok(0);
+    }
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .map_err(|e| format!("Failed to open download archive: {e}"))?;
+    file.write_all(lines.as_bytes())
+        .await
+        .map_err(|e| format!("Failed to write download archive: {e}"))?;
+    Ok(lines.lines().count())
+}
+
+/// Builds archive items from completed history entries.
+///
+/// Entries recorded before the CID was tracked are keyed by the CID of
+/// their page (see [`history_page`]), which `cid_of` looks up by BV ID and
+/// page. Entries without a BV ID, or whose CID cannot be resolved, are
+/// left out. `quality_id` maps the recorded quality label back to its ID;
+/// unknown labels produce an any-quality item.
+pub fn keys_from_history(
+    entries: &[HistoryEntry],
+    quality_id: impl Fn(&str) -> Option<i32>,
+    cid_of: impl Fn(&str, i32) -> Option<i64>,
+) -> Vec<ArchiveKey> {
+    entries
+        .iter()
+        .filter(|entry| matches!(entry.status.as_str(), "completed" | "success"))
+        .filter_map(|entry| {
+            let kind = ArchiveKind::from_history_mode(entry.mode.as_deref());
+            let bvid = entry.bvid.clone()?;
+            let cid = entry.cid.or_else(|| cid_of(&bvid, history_page(entry)))?;
+            Some(ArchiveKey {
+                kind,
+                bvid,
+                cid,
+                quality: entry.quality.as_deref().and_then(&quality_id),
+            })
+        })
+        .collect()
+}
+
+/// Builds the error a skipped download returns, carrying the output path
+/// its history entry recorded (see [`parse_archived_error`]).
+pub fn archived_error(output_path: Option<&str>) -> String {
+    match output_path {
+        Some(path) => format!("{ARCHIVED_ERROR}: {path}"),
+        None => ARCHIVED_ERROR.to_string(),
+    }
+}
+
+/// Parses an error built by [`archived_error`].
+///
+/// # Returns
+///
+/// `None` if `error` is not a skip, else the recorded output path, if any.
+pub fn parse_archived_error(error: &str) -> Option<Option<String>> {
+    let rest = error.strip_prefix(ARCHIVED_ERROR)?;
+    match rest.strip_prefix(": ") {
+        Some(path) => Some(Some(path.to_string())),
+        None => rest.is_empty().then_some(None),
+    }
+}
+
+/// Returns the page a history entry downloaded: the recorded page, else
+/// the page of its URL (see [`HistoryEntry::url_page`]), else the first
+/// page.
+pub fn history_page(entry: &HistoryEntry) -> i32 {
+    entry
+        .page
+        .filter(|&page| page > 0)
+        .or_else(|| entry.url_page())
+        .unwrap_or(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(kind: ArchiveKind, cid: i64, quality: Option<i32>) -> ArchiveKey {
+        ArchiveKey {
+            kind,
+            bvid: "BV1xx411c7XD".to_string(),
+            cid,
+            quality,
+        }
+    }
+
+    #[test]
+    fn lines_round_trip() {
+        for key in [
+            key(ArchiveKind::Video, 1, Some(80)),
+            key(ArchiveKind::VideoOnly, 1, Some(80)),
+            key(ArchiveKind::Audio, 2, Some(30280)),
+            key(ArchiveKind::Video, 3, None),
+        ] {
+            assert_eq!(ArchiveKey::parse(&key.to_string()), Some(key));
+        }
+        assert_eq!(ArchiveKey::parse(""), None);
+        assert_eq!(ArchiveKey::parse("# comment"), None);
+        assert_eq!(ArchiveKey::parse("youtube dQw4w9WgXcQ"), None);
+        assert_eq!(ArchiveKey::parse("bilibili BV1 notacid"), None);
+    }
+
+    #[test]
+    fn archived_errors_carry_the_output_path() {
+        let with_path = archived_error(Some("/videos/a.mp4"));
+        assert_eq!(
+            parse_archived_error(&with_path),
+            Some(Some("/videos/a.mp4".to_string()))
+        );
+        assert_eq!(parse_archived_error(&archived_error(None)), Some(None));
+        assert_eq!(parse_archived_error("ERR::ARCHIVED_OTHER"), None);
+        assert_eq!(parse_archived_error("ERR::NETWORK::x"), None);
+    }
+
+    #[test]
+    fn missing_quality_matches_any_quality() {
+        let archived = key(ArchiveKind::Video, 1, Some(80));
+        assert!(archived.matches(&key(ArchiveKind::Video, 1, Some(80))));
+        assert!(archived.matches(&key(ArchiveKind::Video, 1, None)));
+        assert!(!archived.matches(&key(ArchiveKind::Video, 1, Some(64))));
+        assert!(!archived.matches(&key(ArchiveKind::Video, 2, Some(80))));
+        assert!(!archived.matches(&key(ArchiveKind::Audio, 1, Some(80))));
+        assert!(!archived.matches(&key(ArchiveKind::VideoOnly, 1, Some(80))));
+        assert!(key(ArchiveKind::Video, 1, None).matches(&key(ArchiveKind::Video, 1, Some(64))));
+    }
+
+    #[test]
+    fn history_keys_resolve_missing_cids_by_page() {
+        let entry = |cid: Option<i64>, url: &str| -> HistoryEntry {
+            serde_json::from_value(serde_json::json!({
+                "id": "1",
+                "title": "Title",
+                "bvid": "BV1xx411c7XD",
+                "cid": cid,
+                "url": url,
+                "downloadedAt": "2024-01-01T00:00:00Z",
+                "status": "completed",
+                "fileSize": null,
+                "quality": "1080P",
+                "thumbnailUrl": null,
+                "version": "1.0"
+            }))
+            .unwrap()
+        };
+        let entries = [
+            entry(Some(1), "https://www.bilibili.com/video/BV1xx411c7XD"),
+            entry(
+                None,
+                "https://www.bilibili.com/video/BV1xx411c7XD?vd_source=x&p=3",
+            ),
+            entry(None, "https://www.bilibili.com/video/BV1xx411c7XD"),
+            entry(None, "https://www.bilibili.com/video/BV1xx411c7XD?p=9"),
+        ];
+        let keys = keys_from_history(
+            &entries,
+            |_| Some(80),
+            |bvid, page| (bvid == "BV1xx411c7XD" && page <= 3).then_some(100 + i64::from(page)),
+        );
+        assert_eq!(
+            keys,
+            vec![
+                key(ArchiveKind::Video, 1, Some(80)),
+                key(ArchiveKind::Video, 103, Some(80)),
+                key(ArchiveKind::Video, 101, Some(80)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn record_appends_only_new_items() {
+        let path =
+            std::env::temp_dir().join(format!("download_archive_test_{}.txt", std::process::id()));
+        let _ = tokio::fs::remove_file(&path).await;
+
+        let first = key(ArchiveKind::Video, 1, Some(80));
+        let second = key(ArchiveKind::Audio, 1, None);
+        assert_eq!(record(&path, std::slice::from_ref(&first)).await, Ok(1));
+        assert_eq!(
+            record(&path, &[first.clone(), second.clone(), second.clone()]).await,
+            Ok(1)
+        );
+        assert_eq!(load(&path).await.unwrap(), vec![first.clone(), second]);
+        assert!(contains(&path, &key(ArchiveKind::Video, 1, None)).await);
+        assert!(!contains(&path, &key(ArchiveKind::Video, 2, None)).await);
+
+        let _ = tokio::fs::remove_file(&path).await;
+    }
+}
@@ -0,0 +1,263 @@
+//! Resumable Download Manifest
+//!
+//! This module persists the progress of a segmented download in a small
+//! JSON sidecar file written next to the target (e.g.
+//! `temp_video_BV1xx_123.m4s.manifest.json`). The manifest records which
+//! stream the file belongs to, its total size, the segment size used to plan
+//! the Range requests, and a bitmap of segments already flushed to disk.
+//!
+//! When [`crate::utils::downloads::download_url`] starts against a target
+//! that still has a matching manifest, it keeps the partially written file
+//! and only requests the missing ranges. The manifest is removed once the
+//! download completes.
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Current manifest format version. Manifests with a different version are
+/// treated as absent so the download restarts cleanly.
+const MANIFEST_VERSION: u32 = 1;
+
+/// Suffix appended to the target file name to build the sidecar path.
+pub const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Resume keys currently claimed by running downloads.
+///
+/// Temp files are named by content (bvid + cid) so a download restarted
+/// after an app restart finds its predecessor's partial file. Two downloads
+/// of the same part running at the same time must not share that file, so
+/// the second one falls back to a per-download name.
+static ACTIVE_RESUME_KEYS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// Progress record for a resumable segmented download.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadManifest {
+    /// Manifest format version
+    pub version: u32,
+    /// Stream identity derived from the CDN URL (see [`url_identity`])
+    pub url_identity: String,
+    /// Total size of the target file in bytes
+    pub total_size: u64,
+    /// Segment size used when planning Range requests
+    pub segment_size: u64,
+    /// Number of segments the file was split into
+    pub segment_count: usize,
+    /// Bitmap of completed segments (bit `i` set = segment `i` on disk)
+    pub completed: Vec<u8>,
+}
+
+impl DownloadManifest {
+    /// Creates an empty manifest with no completed segments.
+    pub fn new(
+        url_identity: String,
+        total_size: u64,
+        segment_size: u64,
+        segment_count: usize,
+    ) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            url_identity,
+            total_size,
+            segment_size,
+            segment_count,
+            completed: vec![0; segment_count.div_ceil(8)],
+        }
+    }
+
+    /// Returns `true` when this manifest describes the same stream and
+    /// segment layout, meaning the partial file can be resumed.
+    pub fn matches(
+        &self,
+        url_identity: &str,
+        total_size: u64,
+        segment_size: u64,
+        segment_count: usize,
+    ) -> bool {
+        self.version == MANIFEST_VERSION
+            && self.url_identity == url_identity
+            && self.total_size == total_size
+            && self.segment_size == segment_size
+            && self.segment_count == segment_count
+            && self.completed.len() == segment_count.div_ceil(8)
+    }
+
+    /// Returns `true` if segment `idx` has already been written.
+    pub fn is_done(&self, idx: usize) -> bool {
+        self.completed
+            .get(idx / 8)
+            .is_some_and(|byte| byte & (1 << (idx % 8)) != 0)
+    }
+
+    /// Marks segment `idx` as written. Out-of-range indices are ignored.
+    pub fn mark_done(&mut self, idx: usize) {
+        if idx >= self.segment_count {
+            return;
+        }
+        if let Some(byte) = self.completed.get_mut(idx / 8) {
+            *byte |= 1 << (idx % 8);
+        }
+    }
+
+    /// Sums the byte length of all completed segments.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - Inclusive `(start, end)` ranges, as produced by
+    ///   `calculate_segments`
+    pub fn completed_bytes(&self, segments: &[(u64, u64)]) -> u64 {
+        segments
+            .iter()
+            .enumerate()
+            .filter(|(idx, _)| self.is_done(*idx))
+            .map(|(_, (s, e))| e - s + 1)
+            .sum()
+    }
+
+    /// Loads the manifest stored next to `target`.
+    ///
+    /// Returns `None` when the sidecar is missing, unreadable, or malformed;
+    /// callers then start the download from scratch.
+    pub async fn load(target: &Path) -> Option<Self> {
+        let bytes = tokio::fs::read(manifest_path(target)).await.ok()?;
+        serde_json::from_slice::<Self>(&bytes).ok()
+    }
+
+    /// Writes the manifest next to `target`.
+    ///
+    /// Writes to a temporary file first and renames it over the sidecar so a
+    /// crash mid-write never leaves a truncated manifest behind.
+    pub async fn save(&self, target: &Path) -> Result<()> {
+        let path = manifest_path(target);
+        let tmp = path.with_extension("json.tmp");
+        tokio::fs::write(&tmp, serde_json::to_vec(self)?).await?;
+        tokio::fs::rename(&tmp, &path).await?;
+        Ok(())
+    }
+}
+
+/// Returns the sidecar manifest path for a download target.
+pub fn manifest_path(target: &Path) -> PathBuf {
+    let mut name = target.as_os_str().to_os_string();
+    name.push(MANIFEST_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Removes the sidecar manifest for `target`, ignoring a missing file.
+pub async fn remove_manifest(target: &Path) {
+    let _ = tokio::fs::remove_file(manifest_path(target)).await;
+}
+
+/// Derives a stable stream identity from a CDN URL.
+///
+/// Bilibili signs playurls with short-lived query parameters and serves the
+/// same file from several mirror hosts, so neither the host nor the query
+/// identifies the stream. The last path segment (e.g.
+/// `1234567-1-100050.m4s`) does: it encodes the cid, quality and codec.
+pub fn url_identity(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(parsed) => parsed
+            .path_segments()
+            .and_then(|mut segs| segs.next_back().map(|s| s.to_string()))
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| parsed.path().to_string()),
+        Err(_) => url.split('?').next().unwrap_or(url).to_string(),
+    }
+}
+
+/// Claims a resume key for the duration of a download.
+///
+/// Returns `false` when another running download already holds the key.
+pub fn claim_resume_key(key: &str) -> bool {
+    ACTIVE_RESUME_KEYS
+        .lock()
+        .map(|mut keys| keys.insert(key.to_string()))
+        .unwrap_or(false)
+}
+
+/// Releases a key previously claimed with [`claim_resume_key`].
+pub fn release_resume_key(key: &str) {
+    if let Ok(mut keys) = ACTIVE_RESUME_KEYS.lock() {
+        keys.remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_identity_ignores_host_and_query() {
+        let a = "https://cn-gdfs-ct-01-01.bilivideo.com/upgcxcode/12/34/5678/5678-1-100050.m4s?e=abc&deadline=1";
+        let b = "https://upos-sz-mirrorcos.bilivideo.com/upgcxcode/12/34/5678/5678-1-100050.m4s?e=xyz&deadline=2";
+        assert_eq!(url_identity(a), "5678-1-100050.m4s");
+        assert_eq!(url_identity(a), url_identity(b));
+    }
+
+    #[test]
+    fn url_identity_differs_per_quality() {
+        let q80 = "https://upos.bilivideo.com/upgcxcode/5678-1-100026.m4s?e=1";
+        let q64 = "https://upos.bilivideo.com/upgcxcode/5678-1-100024.m4s?e=1";
+        assert_ne!(url_identity(q80), url_identity(q64));
+    }
+
+    #[test]
+    fn bitmap_marks_and_counts_segments() {
+        let segments = vec![(0, 9), (10, 19), (20, 24)];
+        let mut manifest = DownloadManifest::new("id".into(), 25, 10, segments.len());
+        assert!(!manifest.is_done(1));
+
+        manifest.mark_done(1);
+        manifest.mark_done(2);
+        manifest.mark_done(99); // out of range: ignored
+
+        assert!(!manifest.is_done(0));
+        assert!(manifest.is_done(1));
+        assert!(manifest.is_done(2));
+        assert_eq!(manifest.completed_bytes(&segments), 15);
+    }
+
+    #[test]
+    fn matches_rejects_changed_layout() {
+        let manifest = DownloadManifest::new("a.m4s".into(), 100, 10, 10);
+        assert!(manifest.matches("a.m4s", 100, 10, 10));
+        assert!(!manifest.matches("b.m4s", 100, 10, 10));
+        assert!(!manifest.matches("a.m4s", 101, 10, 11));
+        assert!(!manifest.matches("a.m4s", 100, 20, 5));
+    }
+
+    #[tokio::test]
+    async fn save_and_load_round_trip() {
+        let dir = std::env::temp_dir().join(format!("manifest_test_{}", std::process::id()));
+        tokio::fs::create_dir_all(&dir).await.unwrap();
+        let target = dir.join("temp_video_x.m4s");
+
+        let mut manifest = DownloadManifest::new("x.m4s".into(), 30, 10, 3);
+        manifest.mark_done(0);
+        manifest.save(&target).await.unwrap();
+
+        assert_eq!(
+            manifest_path(&target).file_name().unwrap(),
+            "temp_video_x.m4s.manifest.json"
+        );
+        assert_eq!(DownloadManifest::load(&target).await, Some(manifest));
+
+        remove_manifest(&target).await;
+        assert_eq!(DownloadManifest::load(&target).await, None);
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    #[test]
+    fn resume_key_is_exclusive_until_released() {
+        let key = "BV1test_42";
+        assert!(claim_resume_key(key));
+        assert!(!claim_resume_key(key));
+        release_resume_key(key);
+        assert!(claim_resume_key(key));
+        release_resume_key(key);
+    }
+}
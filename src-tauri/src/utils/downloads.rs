@@ -7,6 +7,8 @@
 //! - Disk space checking
 //! - Fallback to single-stream download when Range is not supported
 //! - Download cancellation support
+//! - Global bandwidth limiting shared across all downloads
+//! - Resuming partial downloads from a sidecar manifest
 
 use crate::{
     constants::{
@@ -15,7 +17,10 @@ use crate::{
     },
     emits::Emits,
     handlers::concurrency::DOWNLOAD_CANCEL_REGISTRY,
+    utils::bandwidth::BANDWIDTH_LIMITER,
     utils::cdn_selector,
+    utils::download_manifest::{self, DownloadManifest},
+    utils::proxy::{self, ProxyTarget},
 };
 
 /// Error type for segment download failures.
@@ -36,16 +41,17 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager};
-use tokio::sync::Semaphore;
+use tokio::sync::{Mutex, Semaphore};
 use tokio::{fs, io::AsyncSeekExt, io::AsyncWriteExt};
 use tokio_util::sync::CancellationToken;
 
 /// Builds the shared reqwest::Client for downloads with connection pooling
 /// tuned for parallel segment fetches (see inline comments for each option).
 /// Centralized here so the shared client (lib.rs) and the fallback client
-/// (download_url) stay identical without manual mirroring.
+/// (download_url) stay identical without manual mirroring. CDN traffic is
+/// routed through the proxy when its scope covers downloads.
 pub fn build_download_client() -> reqwest::Client {
-    reqwest::Client::builder()
+    proxy::apply(reqwest::Client::builder(), ProxyTarget::Cdn)
         .user_agent(USER_AGENT)
         // Overall request timeout. CDN rotation handles truly stuck
         // transfers.
@@ -75,6 +81,45 @@ pub fn build_download_client() -> reqwest::Client {
         .expect("Failed to build HTTP client")
 }
 
+/// Shared download client held in app state.
+///
+/// The client is behind a lock so a proxy change can swap in a rebuilt one.
+/// Downloads clone the inner `Arc` when they start, so a running download
+/// finishes on the client it began with.
+pub struct SharedDownloadClient(std::sync::RwLock<Arc<reqwest::Client>>);
+
+impl SharedDownloadClient {
+    /// Builds the initial client via [`build_download_client`].
+    pub fn new() -> Self {
+        Self(std::sync::RwLock::new(Arc::new(build_download_client())))
+    }
+
+    /// Returns the current client.
+    pub fn get(&self) -> Arc<reqwest::Client> {
+        match self.0.read() {
+            Ok(client) => client.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Replaces the client with a fresh one built from the current proxy
+    /// settings.
+    pub fn rebuild(&self) {
+        let client = Arc::new(build_download_client());
+        match self.0.write() {
+            Ok(mut current) => *current = client,
+            Err(poisoned) => *poisoned.into_inner() = client,
+        }
+        log::info!("[BE] SharedDownloadClient: rebuilt after proxy change");
+    }
+}
+
+impl Default for SharedDownloadClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Sets the download stage based on filename pattern.
 ///
 /// Files starting with "temp_audio" are marked as "audio" stage,
@@ -162,11 +207,16 @@ enum SpeedCheckResult {
 /// requirements. This improves detection of slow networks compared to the
 /// previous byte-threshold approach.
 ///
+/// Time spent waiting on [`BANDWIDTH_LIMITER`] is excluded from the elapsed
+/// time, so a stream held back by the user's bandwidth cap is measured at
+/// the speed the CDN actually delivers rather than the capped rate.
+///
 /// # Arguments
 ///
 /// * `received` - Total bytes received so far
 /// * `last_check_time` - Time of the last speed check
 /// * `last_check_bytes` - Bytes received at the last speed check
+/// * `throttled` - Time spent in the bandwidth limiter since the last check
 /// * `cdn_rotation_count` - Current CDN rotation count
 /// * `cdn_urls_len` - Total number of available CDN URLs
 ///
@@ -179,6 +229,7 @@ fn check_download_speed(
     received: u64,
     last_check_time: Instant,
     last_check_bytes: u64,
+    throttled: Duration,
     cdn_rotation_count: u8,
     cdn_urls_len: usize,
 ) -> SpeedCheckResult {
@@ -188,14 +239,18 @@ fn check_download_speed(
         return SpeedCheckResult::InsufficientData;
     }
 
-    // Time elapsed check (3 seconds)
-    let elapsed = last_check_time.elapsed().as_secs();
-    if elapsed < SPEED_CHECK_INTERVAL_SECS {
+    // Time elapsed check (3 seconds of unthrottled transfer)
+    // Why: with a global cap, the limiter sleeps between chunks and the
+    //   wall-clock speed drops to the cap share of this stream (often far
+    //   below MIN_SPEED_THRESHOLD with 8 segments per file). Counting that
+    //   sleep would rotate CDNs forever for a deliberately throttled stream.
+    let elapsed = last_check_time.elapsed().saturating_sub(throttled);
+    if elapsed.as_secs() < SPEED_CHECK_INTERVAL_SECS {
         return SpeedCheckResult::InsufficientData;
     }
 
     // Calculate speed
-    let speed = (bytes_since_check as f64 / elapsed as f64) as u64;
+    let speed = (bytes_since_check as f64 / elapsed.as_secs_f64()) as u64;
 
     // Check if rotation limit reached (CDN count × MAX_CDN_LOOPS)
     // Use saturating operations to prevent overflow with large CDN lists
@@ -223,13 +278,17 @@ fn check_download_speed(
 /// 4. Splits the payload into 8 MB segments (concurrency pinned to 1
 ///    because Bilibili's CDN is unstable with parallel range requests).
 /// 5. Pre-allocates the output file and emits progress updates via
-///    [`Emits`] to the frontend.
+///    [`Emits`] to the frontend. When a sidecar [`DownloadManifest`] from an
+///    earlier run matches the stream identity, total size and segment
+///    layout, the partial file is kept and only missing segments are
+///    fetched.
 /// 6. Streams each segment through [`download_segment_with_speed_check`],
 ///    transparently rotating CDN URLs when throughput drops below
 ///    [`MIN_SPEED_THRESHOLD`] and rolling back any progress that was
 ///    already reported for a segment being retried.
 /// 7. Verifies the final byte count against the advertised total and
-///    emits either `complete` or `stop` to the frontend.
+///    emits either `complete` or `stop` to the frontend. The manifest is
+///    removed on success and kept on failure so a later call can resume.
 ///
 /// When download speed drops below threshold, automatically switches to
 /// backup CDN URLs if provided. Supports cancellation via global registry.
@@ -242,7 +301,8 @@ fn check_download_speed(
 /// * `output_path` - Destination file path
 /// * `cookie` - Optional Cookie header value for authenticated requests
 /// * `is_override` - When `true`, overwrites an existing file; otherwise
-///   returns `ERR::FILE_EXISTS`
+///   returns `ERR::FILE_EXISTS`. A file with a sidecar manifest is a partial
+///   download and is resumed regardless of this flag.
 /// * `download_id` - Optional unique ID used to register a cancellation
 ///   token and scope emitted events
 /// * `override_stage` - Optional stage label (e.g., `"audio"`, `"video"`)
@@ -312,7 +372,15 @@ pub async fn download_url(
     check_cancelled(&cancel_token)?;
 
     // File existence check
-    if output_path.exists() {
+    // Note: a target with a sidecar manifest is a partial download from an
+    //   earlier run. Keep it until the CDN size is known so the completed
+    //   segments can be verified and resumed instead of deleted.
+    let previous_manifest = if output_path.exists() {
+        DownloadManifest::load(&output_path).await
+    } else {
+        None
+    };
+    if output_path.exists() && previous_manifest.is_none() {
         if is_override {
             fs::remove_file(&output_path).await?;
         } else {
@@ -329,8 +397,8 @@ pub async fn download_url(
     // Note: the fallback is a safety net for call paths where the managed
     //   shared client is unavailable (e.g. non-app contexts); it mirrors the
     //   shared client's timeout and pool sizing (issue #491).
-    let client: Arc<reqwest::Client> = match app.try_state::<Arc<reqwest::Client>>() {
-        Some(state) => state.inner().clone(),
+    let client: Arc<reqwest::Client> = match app.try_state::<SharedDownloadClient>() {
+        Some(state) => state.get(),
         None => {
             log::warn!("[BE] Shared client not found in app state, using local client");
             Arc::new(build_download_client())
@@ -355,6 +423,20 @@ pub async fn download_url(
     let total = match cdn_outcome.total_size {
         Some(size) => size,
         None => {
+            // Caution: a resumable partial file must not be handed to the
+            //   single-stream path, which truncates it. Every probe failing on
+            //   a stream that previously served ranges almost always means
+            //   the signed playurl expired, so surface a plain (retryable)
+            //   error and let retry_download refetch fresh URLs via
+            //   refetch_dash_urls while the partial file stays intact.
+            if previous_manifest.is_some() {
+                log::warn!(
+                    "[BE] download_url: size probe failed for resumable target, URLs likely expired"
+                );
+                return Err(anyhow::anyhow!(
+                    "size probe failed for resumable download (playurl likely expired)"
+                ));
+            }
             // Range not supported or size unknown → fallback to single stream.
             // Prefer the best-ranked CDN over the original (possibly P2P) URL.
             // Why: the original `url` may itself be the P2P node that
@@ -391,8 +473,46 @@ pub async fn download_url(
     //   (#490), so users may raise parallelism safely (issue #491).
     log::info!("[BE] download_url: using concurrency: {}", concurrency);
 
-    // ---- 3. Pre-allocate file ----
-    preallocate_file(&output_path, total).await?;
+    // ---- 3. Resume or pre-allocate file ----
+    // Why: the identity is taken from the stream URL's file name rather than
+    //   the full URL, because a restarted download gets freshly signed URLs
+    //   (and possibly a different mirror host) for the same stream.
+    let identity = download_manifest::url_identity(&url);
+    let on_disk_len = fs::metadata(&output_path).await.map(|m| m.len()).ok();
+    let manifest = match previous_manifest {
+        Some(m)
+            if m.matches(&identity, total, segment_size, segments.len())
+                && on_disk_len == Some(total) =>
+        {
+            log::info!(
+                "[BE] download_url: resuming from manifest, {}/{} bytes already on disk",
+                m.completed_bytes(&segments),
+                total
+            );
+            m
+        }
+        stale => {
+            if stale.is_some() {
+                log::info!("[BE] download_url: manifest does not match stream, restarting");
+            }
+            preallocate_file(&output_path, total).await?;
+            let m = DownloadManifest::new(identity, total, segment_size, segments.len());
+            // Resume is best-effort: a manifest that cannot be written only
+            // means a later run restarts from zero.
+            if let Err(e) = m.save(&output_path).await {
+                log::warn!("[BE] download_url: failed to write manifest: {}", e);
+            }
+            m
+        }
+    };
+    let resumed_bytes = manifest.completed_bytes(&segments);
+    let pending: Vec<(usize, (u64, u64))> = segments
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(idx, _)| !manifest.is_done(*idx))
+        .collect();
+    let manifest = Arc::new(Mutex::new(manifest));
 
     // ---- 4. Setup progress emitter ----
     let id_for_emit = download_id.clone().unwrap_or_else(|| filename.to_string());
@@ -401,13 +521,16 @@ pub async fn download_url(
     if let Some(stage) = override_stage {
         let _ = emits.set_stage(stage).await;
     }
+    if resumed_bytes > 0 {
+        emits.set_resumed_bytes(resumed_bytes).await;
+    }
 
-    let downloaded_total = Arc::new(AtomicU64::new(0));
+    let downloaded_total = Arc::new(AtomicU64::new(resumed_bytes));
     let sem = Arc::new(Semaphore::new(concurrency));
 
-    // ---- 5. Download segments in parallel ----
+    // ---- 5. Download missing segments in parallel ----
     let mut futs = FuturesUnordered::new();
-    for (idx, (s, e)) in segments.iter().cloned().enumerate() {
+    for (idx, (s, e)) in pending {
         let cdn_urls_c = cdn_urls.clone();
         let cookie_c = cookie.clone();
         let path_c = output_path.clone();
@@ -416,6 +539,7 @@ pub async fn download_url(
         let emits_c = emits.clone();
         let sem_c = sem.clone();
         let cancel_token_c = cancel_token.clone();
+        let manifest_c = manifest.clone();
         futs.push(tokio::spawn(async move {
             let _permit = sem_c.acquire().await.unwrap();
 
@@ -629,6 +753,19 @@ pub async fn download_url(
                         // Write to file
                         write_segment(&path_c, s, &buf).await?;
 
+                        // Record the segment only after it is on disk, so a
+                        // crash between the two steps re-fetches it rather
+                        // than trusting unwritten bytes.
+                        let mut m = manifest_c.lock().await;
+                        m.mark_done(idx);
+                        if let Err(e) = m.save(&path_c).await {
+                            log::warn!(
+                                "[BE] download_url: failed to update manifest after segment {}: {}",
+                                idx,
+                                e
+                            );
+                        }
+
                         return Ok(());
                     }
                     Err(e) => {
@@ -710,6 +847,7 @@ pub async fn download_url(
         "[BE] download_url: download complete, total_bytes={}",
         final_downloaded
     );
+    download_manifest::remove_manifest(&output_path).await;
 
     if emit_complete {
         emits.complete().await;
@@ -725,6 +863,8 @@ pub async fn download_url(
 /// This function downloads data from a response stream while performing
 /// periodic speed checks at configured intervals. If the download speed
 /// falls below the minimum threshold, it signals that a reconnect is needed.
+/// Each chunk draws from the global [`BANDWIDTH_LIMITER`] before the next
+/// one is read; the time spent throttled is excluded from the speed check.
 ///
 /// # Arguments
 ///
@@ -757,6 +897,7 @@ async fn download_segment_with_speed_check(
     // Time-based speed check variables
     let mut last_check_time = Instant::now();
     let mut last_check_bytes: u64 = 0;
+    let mut throttled = Duration::ZERO;
 
     loop {
         match resp.chunk().await {
@@ -768,11 +909,15 @@ async fn download_segment_with_speed_check(
                 // Report progress on chunk received
                 on_chunk_received(chunk_len);
 
+                // Hold the stream back while over the global bandwidth cap
+                throttled += BANDWIDTH_LIMITER.acquire(chunk_len).await;
+
                 // Perform time-based speed check
                 match check_download_speed(
                     received,
                     last_check_time,
                     last_check_bytes,
+                    throttled,
                     cdn_rotation_count,
                     cdn_urls_len,
                 ) {
@@ -781,6 +926,7 @@ async fn download_segment_with_speed_check(
                         // Reset check counters for next interval
                         last_check_time = Instant::now();
                         last_check_bytes = received;
+                        throttled = Duration::ZERO;
                     }
                     SpeedCheckResult::InsufficientData => {}
                 }
@@ -848,7 +994,8 @@ async fn write_segment(path: &PathBuf, pos: u64, buf: &[u8]) -> Result<(), anyho
 /// per-chunk cancellation checks and progress emission.
 ///
 /// Note: CDN rotation is not implemented in fallback mode since parallel
-/// downloads are not possible without Range support.
+/// downloads are not possible without Range support. The stream still draws
+/// from the global [`BANDWIDTH_LIMITER`] for every chunk.
 ///
 /// # Arguments
 ///
@@ -969,6 +1116,7 @@ async fn single_stream_fallback(
         downloaded += chunk.len() as u64;
         // Emit progress update via watch channel (non-blocking)
         emits_for_callback.update_progress(downloaded);
+        BANDWIDTH_LIMITER.acquire(chunk.len() as u64).await;
     }
 
     file.flush().await.map_err(map_io_error)?;
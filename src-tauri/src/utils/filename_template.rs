@@ -0,0 +1,446 @@
+//! Filename Templates
+//!
+//! Renders the output filename of a download from a user-defined template
+//! such as `{uploader}/{title} - {page:02} {part}`. Placeholders are
+//! replaced with the values of the part, each value sanitized with the
+//! title replacement rules; `/` (or `\`) in the template creates
+//! subfolders below the download directory.
+//!
+//! | Placeholder | Value                                  | Format spec          |
+//! |-------------|----------------------------------------|----------------------|
+//! | `title`     | Video title                            |                      |
+//! | `part`      | Part name                              |                      |
+//! | `page`      | Page number                            | width, e.g. `{page:02}` |
+//! | `bvid`      | BV identifier                          |                      |
+//! | `uploader`  | Uploader name                          |                      |
+//! | `pubdate`   | Publish date, UTC+8 (`%Y-%m-%d` by default) | strftime, e.g. `{pubdate:%Y%m}` |
+//! | `quality`   | Resolved video quality (e.g. `1080P`)  |                      |
+//! | `codec`     | Resolved video codec (`AVC`, `HEVC`, `AV1`) |                 |
+//! | `season`    | Bangumi season title                   |                      |
+//!
+//! `{{` and `}}` produce literal braces. Folders whose placeholders are all
+//! empty (e.g. `{season}/` for a regular video) are dropped.
+
+use chrono::format::{Item, StrftimeItems};
+use serde::{Deserialize, Serialize};
+
+use crate::models::frontend_dto::{Video, VideoPart};
+use crate::models::settings::TitleReplacement;
+use crate::utils::metadata::publish_datetime;
+use crate::utils::sanitize::apply_title_replacements;
+
+/// Date format of `{pubdate}` without a format spec.
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Maximum number of digits of a `{page:N}` width (more would only pad
+/// the file name with zeros).
+const MAX_PAGE_WIDTH_DIGITS: usize = 4;
+
+/// Values substituted into a filename template.
+///
+/// Sent by the frontend (or built by the queue) with the fields only it
+/// knows; the download fills in the resolved `quality` and `codec`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TemplateValues {
+    /// Video title
+    pub title: String,
+    /// Part name
+    #[serde(default)]
+    pub part: Option<String>,
+    /// Page number
+    #[serde(default)]
+    pub page: Option<i32>,
+    /// BV identifier
+    #[serde(default)]
+    pub bvid: String,
+    /// Uploader name
+    #[serde(default)]
+    pub uploader: Option<String>,
+    /// Publish date as a Unix timestamp in seconds
+    #[serde(default)]
+    pub pubdate: Option<i64>,
+    /// Video quality label (e.g. "1080P60")
+    #[serde(default)]
+    pub quality: Option<String>,
+    /// Video codec label (e.g. "HEVC")
+    #[serde(default)]
+    pub codec: Option<String>,
+    /// Bangumi season title
+    #[serde(default)]
+    pub season: Option<String>,
+}
+
+/// Placeholder of a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Title,
+    Part,
+    Page,
+    Bvid,
+    Uploader,
+    Pubdate,
+    Quality,
+    Codec,
+    Season,
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "title" => Self::Title,
+            "part" => Self::Part,
+            "page" => Self::Page,
+            "bvid" => Self::Bvid,
+            "uploader" => Self::Uploader,
+            "pubdate" => Self::Pubdate,
+            "quality" => Self::Quality,
+            "codec" => Self::Codec,
+            "season" => Self::Season,
+            _ => return None,
+        })
+    }
+}
+
+/// Parsed piece of a template.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Literal(String),
+    Separator,
+    Placeholder { field: Field, spec: Option<String> },
+}
+
+/// Parses and validates a template.
+fn parse(template: &str) -> Result<Vec<Token>, String> {
+    if template.trim().is_empty() {
+        return Err("ERR::TEMPLATE_EMPTY".to_string());
+    }
+    let mut tokens = Vec::new();
+    let mut literal = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '}' => return Err("ERR::TEMPLATE_UNMATCHED_BRACE".to_string()),
+            '{' => {
+                let mut body = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => body.push(c),
+                        None => return Err("ERR::TEMPLATE_UNMATCHED_BRACE".to_string()),
+                    }
+                }
+                let (name, spec) = match body.split_once(':') {
+                    Some((name, spec)) => (name.trim(), Some(spec.to_string())),
+                    None => (body.trim(), None),
+                };
+                let field = Field::from_name(name)
+                    .ok_or_else(|| format!("ERR::TEMPLATE_UNKNOWN_PLACEHOLDER: {name}"))?;
+                if let Some(spec) = &spec {
+                    validate_spec(field, spec)?;
+                }
+                if !literal.is_empty() {
+                    tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(Token::Placeholder { field, spec });
+            }
+            '/' | '\\' => {
+                if !literal.is_empty() {
+                    tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                }
+                tokens.push(Token::Separator);
+            }
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        tokens.push(Token::Literal(literal));
+    }
+    validate_path(&tokens)?;
+    Ok(tokens)
+}
+
+/// Checks the format spec of a placeholder.
+fn validate_spec(field: Field, spec: &str) -> Result<(), String> {
+    let invalid = || format!("ERR::TEMPLATE_INVALID_FORMAT: {spec}");
+    match field {
+        Field::Page
+            if (1..=MAX_PAGE_WIDTH_DIGITS).contains(&spec.len())
+                && spec.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            Ok(())
+        }
+        Field::Pubdate
+            if !spec.is_empty()
+                && !StrftimeItems::new(spec).any(|item| matches!(item, Item::Error)) =>
+        {
+            Ok(())
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// Rejects templates that would leave the download directory.
+fn validate_path(tokens: &[Token]) -> Result<(), String> {
+    let invalid = || "ERR::TEMPLATE_INVALID_PATH".to_string();
+    if matches!(tokens.first(), Some(Token::Separator))
+        || matches!(tokens.last(), Some(Token::Separator))
+    {
+        return Err(invalid());
+    }
+    for (index, segment) in tokens.split(|t| *t == Token::Separator).enumerate() {
+        let is_literal_only = segment.iter().all(|t| matches!(t, Token::Literal(_)));
+        let text: String = segment
+            .iter()
+            .filter_map(|t| match t {
+                Token::Literal(s) => Some(s.as_str()),
+                _ => None,
+            })
+            .collect();
+        let is_drive = index == 0 && is_literal_only && text.ends_with(':');
+        if segment.is_empty()
+            || is_drive
+            || (is_literal_only && matches!(text.trim(), "" | "." | ".."))
+        {
+            return Err(invalid());
+        }
+    }
+    Ok(())
+}
+
+/// Validates a filename template.
+///
+/// # Errors
+///
+/// Returns strings beginning with `ERR::TEMPLATE_*`:
+/// - `ERR::TEMPLATE_EMPTY`
+/// - `ERR::TEMPLATE_UNMATCHED_BRACE`
+/// - `ERR::TEMPLATE_UNKNOWN_PLACEHOLDER: {name}`
+/// - `ERR::TEMPLATE_INVALID_FORMAT: {spec}` (a spec on a placeholder that
+///   takes none, a page width that is not 1–4 digits or an invalid date
+///   format)
+/// - `ERR::TEMPLATE_INVALID_PATH` (leading/trailing or doubled separators,
+///   `.`/`..` folders, or a leading drive letter)
+pub fn validate_template(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// Renders a filename template.
+///
+/// Values are sanitized with `replacements` (the default rules when
+/// `None`); separators left in a value are replaced with `-` so values can
+/// never create folders. Empty folders are dropped.
+///
+/// # Returns
+///
+/// The relative path of the download without extension, folders separated
+/// by `/`.
+///
+/// # Errors
+///
+/// Returns the errors of [`validate_template`], or
+/// `ERR::TEMPLATE_EMPTY_RESULT` when the file name renders empty.
+pub fn render_template(
+    template: &str,
+    values: &TemplateValues,
+    replacements: Option<&[TitleReplacement]>,
+) -> Result<String, String> {
+    let tokens = parse(template)?;
+    let count = tokens.split(|t| *t == Token::Separator).count();
+    let mut segments: Vec<String> = Vec::new();
+    for (index, segment) in tokens.split(|t| *t == Token::Separator).enumerate() {
+        let mut rendered = String::new();
+        let mut has_value = false;
+        let mut has_placeholder = false;
+        for token in segment {
+            match token {
+                Token::Literal(s) => rendered.push_str(s),
+                Token::Placeholder { field, spec } => {
+                    has_placeholder = true;
+                    let value = field_value(*field, spec.as_deref(), values);
+                    let value =
+                        apply_title_replacements(&value, replacements).replace(['/', '\\'], "-");
+                    has_value |= !value.trim().is_empty();
+                    rendered.push_str(&value);
+                }
+                Token::Separator => {}
+            }
+        }
+        let rendered = rendered.trim();
+        if index + 1 == count {
+            if rendered.is_empty() || !has_value && has_placeholder {
+                return Err("ERR::TEMPLATE_EMPTY_RESULT".to_string());
+            }
+        } else if has_placeholder && !has_value {
+            continue;
+        }
+        segments.push(rendered.to_string());
+    }
+    Ok(segments.join("/"))
+}
+
+/// Returns the unsanitized value of a placeholder (empty when unknown).
+fn field_value(field: Field, spec: Option<&str>, values: &TemplateValues) -> String {
+    let text = |value: &Option<String>| value.clone().unwrap_or_default();
+    match field {
+        Field::Title => values.title.clone(),
+        Field::Part => text(&values.part),
+        Field::Page => match (values.page, spec) {
+            (Some(page), Some(width)) => {
+                let width = width.parse().unwrap_or(0);
+                format!("{page:0width$}")
+            }
+            (Some(page), None) => page.to_string(),
+            (None, _) => String::new(),
+        },
+        Field::Bvid => values.bvid.clone(),
+        Field::Uploader => text(&values.uploader),
+        Field::Pubdate => values
+            .pubdate
+            .and_then(publish_datetime)
+            .map(|date| date.format(spec.unwrap_or(DEFAULT_DATE_FORMAT)).to_string())
+            .unwrap_or_default(),
+        Field::Quality => text(&values.quality),
+        Field::Codec => text(&values.codec),
+        Field::Season => text(&values.season),
+    }
+}
+
+/// Returns the example values used by the template preview.
+pub fn example_values() -> TemplateValues {
+    TemplateValues {
+        title: "【4K】Example Video".to_string(),
+        part: Some("Part 1".to_string()),
+        page: Some(1),
+        bvid: "BV1xx411c7XD".to_string(),
+        uploader: Some("Uploader".to_string()),
+        pubdate: Some(1_700_000_000),
+        quality: Some("1080P".to_string()),
+        codec: Some("HEVC".to_string()),
+        season: Some("Season 1".to_string()),
+    }
+}
+
+/// Returns the template values of one part of a video.
+///
+/// `quality` and `codec` are left empty; they are only known once the
+/// download has resolved its streams.
+pub fn values_for_part(video: &Video, part: &VideoPart) -> TemplateValues {
+    let metadata = video.metadata.as_ref();
+    TemplateValues {
+        // The metadata keeps the title before the replacement rules.
+        title: metadata
+            .map(|m| m.title.clone())
+            .unwrap_or_else(|| video.title.clone()),
+        part: Some(part.part.clone()),
+        page: Some(part.page),
+        bvid: video.bvid.clone(),
+        uploader: metadata.and_then(|m| m.uploader.clone()),
+        pubdate: metadata.and_then(|m| m.publish_date),
+        quality: None,
+        codec: None,
+        season: video.season_title.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            title: "Title: A/B".to_string(),
+            part: Some("Intro".to_string()),
+            page: Some(3),
+            bvid: "BV1xx411c7XD".to_string(),
+            uploader: Some("up".to_string()),
+            pubdate: Some(1_700_000_000),
+            quality: Some("1080P".to_string()),
+            codec: Some("HEVC".to_string()),
+            season: None,
+        }
+    }
+
+    #[test]
+    fn renders_placeholders_with_formats_and_folders() {
+        let rendered = render_template(
+            "{uploader}/{pubdate:%Y%m} {title} - P{page:02} {part} [{quality} {codec}] {bvid}",
+            &values(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            rendered,
+            "up/202311 Title_ A-B - P03 Intro [1080P HEVC] BV1xx411c7XD"
+        );
+        assert_eq!(
+            render_template("{{{pubdate}}} {page}", &values(), None).unwrap(),
+            "{2023-11-15} 3"
+        );
+    }
+
+    #[test]
+    fn pubdate_uses_bilibili_time_zone() {
+        // 2023-11-14 16:00 UTC is midnight of the next day in UTC+8.
+        let at_midnight = TemplateValues {
+            pubdate: Some(1_699_977_600),
+            ..values()
+        };
+        assert_eq!(
+            render_template("{pubdate:%Y-%m-%d %H}", &at_midnight, None).unwrap(),
+            "2023-11-15 00"
+        );
+    }
+
+    #[test]
+    fn empty_folders_are_dropped() {
+        assert_eq!(
+            render_template("{season}/{title}", &values(), None).unwrap(),
+            "Title_ A-B"
+        );
+        let no_part = TemplateValues {
+            part: None,
+            ..values()
+        };
+        assert_eq!(
+            render_template("{part}", &no_part, None),
+            Err("ERR::TEMPLATE_EMPTY_RESULT".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_templates_are_rejected() {
+        let cases = [
+            ("", "ERR::TEMPLATE_EMPTY"),
+            ("{title", "ERR::TEMPLATE_UNMATCHED_BRACE"),
+            ("title}", "ERR::TEMPLATE_UNMATCHED_BRACE"),
+            ("{name}", "ERR::TEMPLATE_UNKNOWN_PLACEHOLDER: name"),
+            ("{title:02}", "ERR::TEMPLATE_INVALID_FORMAT: 02"),
+            ("{page:x}", "ERR::TEMPLATE_INVALID_FORMAT: x"),
+            ("{page:99999}", "ERR::TEMPLATE_INVALID_FORMAT: 99999"),
+            ("{page:}", "ERR::TEMPLATE_INVALID_FORMAT: "),
+            ("{pubdate:%Q}", "ERR::TEMPLATE_INVALID_FORMAT: %Q"),
+            ("/{title}", "ERR::TEMPLATE_INVALID_PATH"),
+            ("../{title}", "ERR::TEMPLATE_INVALID_PATH"),
+            ("a//{title}", "ERR::TEMPLATE_INVALID_PATH"),
+            ("C:\\{title}", "ERR::TEMPLATE_INVALID_PATH"),
+        ];
+        for (template, error) in cases {
+            assert_eq!(
+                validate_template(template),
+                Err(error.to_string()),
+                "{template}"
+            );
+        }
+        assert!(validate_template("{uploader}\\{title} {page:3}").is_ok());
+        assert!(validate_template("{page:0004}").is_ok());
+    }
+}
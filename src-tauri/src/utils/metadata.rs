@@ -0,0 +1,249 @@
+//! Container metadata tags
+//!
+//! Builds the per-part [`VideoMetadata`] of a download and the ffmpeg
+//! `-metadata` arguments that write it into MP4 files, so media library
+//! tools can index downloads without a separate database. Chapters are
+//! written through an ffmetadata file (see [`ffmetadata_chapters`]).
+
+use chrono::{DateTime, FixedOffset};
+
+use crate::models::frontend_dto::{Chapter, Video, VideoMetadata, VideoPart};
+
+/// UTC offset of Bilibili's publish dates (China Standard Time).
+const BILIBILI_UTC_OFFSET_SECS: i32 = 8 * 3600;
+
+/// Converts a Unix publish timestamp into Bilibili's local time.
+///
+/// # Returns
+///
+/// Returns `None` for non-positive or out-of-range timestamps.
+pub fn publish_datetime(timestamp: i64) -> Option<DateTime<FixedOffset>> {
+    // Why: the site dates videos in UTC+8; formatting in UTC would date a
+    // video published shortly after midnight there as the previous day.
+    let offset = FixedOffset::east_opt(BILIBILI_UTC_OFFSET_SECS)?;
+    Some(timestamp)
+        .filter(|&ts| ts > 0)
+        .and_then(|ts| DateTime::from_timestamp(ts, 0))
+        .map(|date| date.with_timezone(&offset))
+}
+
+/// Returns the metadata of one part of a video.
+///
+/// Parts other than a single-part video's only page get the part name
+/// appended to the title (matching the download filename) and a source URL
+/// pointing at that page or episode.
+pub fn part_metadata(video: &Video, part: &VideoPart) -> Option<VideoMetadata> {
+    let base = video.metadata.as_ref()?;
+    let is_only_page = video.parts.len() == 1 && part.ep_id.is_none();
+    let title = if is_only_page || base.title == part.part {
+        base.title.clone()
+    } else {
+        format!("{} {}", base.title, part.part)
+    };
+    let source_url = match part.ep_id {
+        Some(ep_id) => Some(format!("https://www.bilibili.com/bangumi/play/ep{ep_id}")),
+        None if video.parts.len() > 1 => base
+            .source_url
+            .as_ref()
+            .map(|url| format!("{url}?p={}", part.page)),
+        None => base.source_url.clone(),
+    };
+    Some(VideoMetadata {
+        title,
+        source_url,
+        ..base.clone()
+    })
+}
+
+/// Builds ffmpeg `-metadata` arguments for MP4 container tags.
+///
+/// | Field          | Tag                                   |
+/// |----------------|---------------------------------------|
+/// | `title`        | `title`                               |
+/// | `uploader`     | `artist`                              |
+/// | `description`  | `description`, `synopsis`             |
+/// | `publish_date` | `date` (`YYYY-MM-DD`), `creation_time` |
+/// | `tags`         | `keywords` (comma-separated)          |
+/// | `source_url`   | `comment`                             |
+///
+/// Empty fields are left out.
+pub fn ffmpeg_metadata_args(metadata: &VideoMetadata) -> Vec<String> {
+    let mut tags: Vec<(&str, String)> = vec![("title", metadata.title.clone())];
+    if let Some(uploader) = &metadata.uploader {
+        tags.push(("artist", uploader.clone()));
+    }
+    if let Some(description) = &metadata.description {
+        tags.push(("description", description.clone()));
+        tags.push(("synopsis", description.clone()));
+    }
+    if let Some(published) = metadata.publish_date.and_then(publish_datetime) {
+        tags.push(("date", published.format("%Y-%m-%d").to_string()));
+        tags.push((
+            "creation_time",
+            published.to_utc().format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        ));
+    }
+    if !metadata.tags.is_empty() {
+        tags.push(("keywords", metadata.tags.join(", ")));
+    }
+    if let Some(url) = &metadata.source_url {
+        tags.push(("comment", url.clone()));
+    }
+
+    tags.into_iter()
+        .filter(|(_, value)| !value.trim().is_empty())
+        .flat_map(|(key, value)| ["-metadata".to_string(), format!("{key}={value}")])
+        .collect()
+}
+
+/// Builds an ffmetadata document containing the given chapters.
+///
+/// The file is passed to ffmpeg as an extra input and copied with
+/// `-map_chapters`. Times use a millisecond timebase; titles have the
+/// ffmetadata special characters (`=`, `;`, `#`, `\`, newline) escaped.
+pub fn ffmetadata_chapters(chapters: &[Chapter]) -> String {
+    let mut doc = String::from(";FFMETADATA1\n");
+    for chapter in chapters {
+        doc.push_str("\n[CHAPTER]\nTIMEBASE=1/1000\n");
+        doc.push_str(&format!("START={}\n", chapter.start * 1000));
+        doc.push_str(&format!("END={}\n", chapter.end * 1000));
+        doc.push_str(&format!("title={}\n", escape_ffmetadata(&chapter.title)));
+    }
+    doc
+}
+
+/// Escapes a value for an ffmetadata file.
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::frontend_dto::Thumbnail;
+
+    fn part(page: i32, name: &str) -> VideoPart {
+        VideoPart {
+            cid: page as i64,
+            page,
+            part: name.to_string(),
+            sanitized_part: None,
+            duration: 60,
+            thumbnail: Thumbnail { url: String::new() },
+            video_qualities: vec![],
+            audio_qualities: vec![],
+            subtitles: vec![],
+            ep_id: None,
+            status: None,
+            aid: None,
+            is_preview: None,
+        }
+    }
+
+    fn video(parts: Vec<VideoPart>) -> Video {
+        Video {
+            title: "Title".to_string(),
+            bvid: "BV1xx411c7XD".to_string(),
+            parts,
+            is_limited_quality: false,
+            content_type: "video".to_string(),
+            ep_id: None,
+            season_title: None,
+            collection: None,
+            extra_sections: vec![],
+            metadata: Some(VideoMetadata {
+                title: "Title".to_string(),
+                uploader: Some("up".to_string()),
+                source_url: Some("https://www.bilibili.com/video/BV1xx411c7XD".to_string()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn part_metadata_follows_the_page() {
+        let single = video(vec![part(1, "Title")]);
+        let m = part_metadata(&single, &single.parts[0]).unwrap();
+        assert_eq!(m.title, "Title");
+        assert_eq!(
+            m.source_url.as_deref(),
+            Some("https://www.bilibili.com/video/BV1xx411c7XD")
+        );
+
+        // The only page is often named after the uploaded file.
+        let renamed = video(vec![part(1, "upload_final_v2")]);
+        let m = part_metadata(&renamed, &renamed.parts[0]).unwrap();
+        assert_eq!(m.title, "Title");
+
+        let multi = video(vec![part(1, "Intro"), part(2, "Outro")]);
+        let m = part_metadata(&multi, &multi.parts[1]).unwrap();
+        assert_eq!(m.title, "Title Outro");
+        assert_eq!(m.uploader.as_deref(), Some("up"));
+        assert!(m.source_url.unwrap().ends_with("?p=2"));
+
+        let mut episode = part(1, "EP1");
+        episode.ep_id = Some(42);
+        let m = part_metadata(&single, &episode).unwrap();
+        assert_eq!(
+            m.source_url.as_deref(),
+            Some("https://www.bilibili.com/bangumi/play/ep42")
+        );
+    }
+
+    #[test]
+    fn metadata_args_skip_empty_fields() {
+        let args = ffmpeg_metadata_args(&VideoMetadata {
+            title: "Title".to_string(),
+            uploader: Some(String::new()),
+            description: None,
+            publish_date: Some(1_700_000_000),
+            tags: vec!["a".to_string(), "b".to_string()],
+            source_url: Some("https://example.com".to_string()),
+            cover_url: Some("https://example.com/cover.jpg".to_string()),
+        });
+        assert_eq!(
+            args,
+            [
+                "-metadata",
+                "title=Title",
+                "-metadata",
+                "date=2023-11-15",
+                "-metadata",
+                "creation_time=2023-11-14T22:13:20Z",
+                "-metadata",
+                "keywords=a, b",
+                "-metadata",
+                "comment=https://example.com",
+            ]
+        );
+    }
+
+    #[test]
+    fn chapters_are_written_in_milliseconds() {
+        let doc = ffmetadata_chapters(&[
+            Chapter {
+                title: "Intro".to_string(),
+                start: 0,
+                end: 90,
+            },
+            Chapter {
+                title: "Q&A; a=b #1".to_string(),
+                start: 90,
+                end: 600,
+            },
+        ]);
+        assert_eq!(
+            doc,
+            ";FFMETADATA1\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=0\nEND=90000\ntitle=Intro\n\
+             \n[CHAPTER]\nTIMEBASE=1/1000\nSTART=90000\nEND=600000\ntitle=Q&A\\; a\\=b \\#1\n"
+        );
+    }
+}
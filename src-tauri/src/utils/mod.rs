@@ -1,18 +1,26 @@
 //! Utility Modules
 //!
-//! This module contains utility functions for downloads, path resolution,
-//! analytics (currently disabled), WBI signature generation, subtitle conversion,
-//! filename sanitization, error handling, and log cleanup.
+//! This module contains utility functions for downloads, resumable download
+//! manifests, the download archive, bandwidth limiting, proxy configuration, path resolution, analytics (currently disabled), WBI signature
+//! generation, subtitle and danmaku conversion, container metadata tags, filename templates and sanitization, error
+//! handling, and log cleanup.
 
 pub mod analytics;
+pub mod bandwidth;
 pub mod cdn_selector;
 pub mod codec;
+pub mod danmaku;
+pub mod download_archive;
+pub mod download_manifest;
 pub mod downloads;
 pub mod error_handler;
 pub mod ffmpeg_probe;
 pub mod ffmpeg_progress;
+pub mod filename_template;
 pub mod log_cleanup;
+pub mod metadata;
 pub mod paths;
+pub mod proxy;
 pub mod sanitize;
 pub mod secure_storage;
 pub mod subtitle;
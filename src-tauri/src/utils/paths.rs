@@ -9,7 +9,8 @@
 //! ```text
 //! app_data_dir()/
 //! ├── settings.json         ← Fixed (user cannot change)
-//! └── history.json          ← Managed by tauri-plugin-store
+//! ├── history.db            ← Download history (SQLite, see store::history_store)
+//! └── download_archive.txt  ← Finished downloads (see utils::download_archive)
 //!
 //! user-specified libPath/   (default: app_data_dir()/lib/)
 //! ├── ffmpeg/
@@ -163,6 +164,25 @@ pub fn get_settings_path(app: &AppHandle) -> PathBuf {
         .join("settings.json")
 }
 
+/// Returns the path to the download archive.
+///
+/// Like the settings file it lives in the app data directory, so skipping
+/// already-downloaded items survives a change of the lib path.
+///
+/// # Arguments
+///
+/// * `app` - Tauri application handle for resolving the app data directory
+///
+/// # Returns
+///
+/// Returns the absolute path to `download_archive.txt`.
+pub fn get_download_archive_path(app: &AppHandle) -> PathBuf {
+    app.path()
+        .app_data_dir()
+        .unwrap_or_else(|_| PathBuf::from("."))
+        .join("download_archive.txt")
+}
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -1,10 +1,73 @@
 //! Filename Sanitization Utilities
 //!
 //! This module provides functions to sanitize filenames by applying
-//! user-configurable character replacement rules.
+//! user-configurable character replacement rules, followed by a
+//! platform-aware stage ([`sanitize_filename`]) that makes the result a
+//! valid file name regardless of what the rules left behind.
 
 use crate::models::settings::{default_title_replacements, TitleReplacement};
 use std::collections::HashMap;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Maximum length of a file name in bytes.
+///
+/// ext4 and APFS limit names to 255 bytes; NTFS counts 255 UTF-16 units,
+/// which the byte limit also satisfies. CJK characters take 3 bytes in
+/// UTF-8, so an 85-character title already reaches the limit.
+pub const MAX_FILENAME_BYTES: usize = 255;
+
+/// Bytes kept free below [`MAX_FILENAME_BYTES`] for names derived from a
+/// sanitized download path: sidecars replace the extension
+/// (`.danmaku.ass`, `.audio.flac`) and `auto_rename` appends ` (n)`.
+pub const FILENAME_HEADROOM_BYTES: usize = 24;
+
+/// Characters Windows does not allow in file names.
+const WINDOWS_INVALID_CHARS: &[char] = &['<', '>', ':', '"', '/', '\\', '|', '?', '*'];
+
+/// Device names Windows reserves regardless of case or extension.
+const WINDOWS_RESERVED_NAMES: &[&str] = &[
+    "CON", "PRN", "AUX", "NUL", "COM0", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7",
+    "COM8", "COM9", "COM¹", "COM²", "COM³", "LPT0", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6",
+    "LPT7", "LPT8", "LPT9", "LPT¹", "LPT²", "LPT³",
+];
+
+/// File name rules of an operating system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilenamePlatform {
+    /// `<>:"/\|?*` are invalid, names may not end in a dot or space and
+    /// device names (`CON`, `NUL`, `COM1`, ...) are reserved.
+    Windows,
+    /// `/` is invalid and `:` is shown as `/` by Finder.
+    MacOs,
+    /// Only `/` is invalid.
+    Unix,
+}
+
+impl FilenamePlatform {
+    /// Returns the platform the app is running on.
+    pub fn current() -> Self {
+        if cfg!(windows) {
+            Self::Windows
+        } else if cfg!(target_os = "macos") {
+            Self::MacOs
+        } else {
+            Self::Unix
+        }
+    }
+
+    fn is_invalid(self, c: char) -> bool {
+        match self {
+            Self::Windows => WINDOWS_INVALID_CHARS.contains(&c),
+            Self::MacOs => c == '/' || c == ':',
+            Self::Unix => c == '/',
+        }
+    }
+
+    /// Characters trimmed from the end of a name.
+    fn is_trailing_trimmed(self, c: char) -> bool {
+        c.is_whitespace() || (self == Self::Windows && c == '.')
+    }
+}
 
 /// Applies title replacement rules to sanitize a filename.
 pub fn apply_title_replacements(
@@ -24,6 +87,134 @@ pub fn apply_title_replacements(
     result
 }
 
+/// Makes a file name valid on the current platform.
+///
+/// See [`sanitize_filename_for`].
+pub fn sanitize_filename(name: &str, extension: &str, max_bytes: usize) -> String {
+    sanitize_filename_for(name, extension, max_bytes, FilenamePlatform::current())
+}
+
+/// Makes a file name valid on the given platform.
+///
+/// Runs after [`apply_title_replacements`]: control characters are removed,
+/// characters the platform rejects become `_`, surrounding whitespace (and
+/// on Windows trailing dots) is trimmed and Windows device names get a `_`
+/// appended (`CON` -> `CON_`). Names longer than `max_bytes` are truncated
+/// on a grapheme boundary, keeping the extension and a trailing page suffix
+/// such as ` (2)` (see [`resolve_duplicate_titles`]).
+///
+/// # Arguments
+///
+/// * `name` - File name without extension
+/// * `extension` - Extension to append without the dot, or `""` for none
+/// * `max_bytes` - Maximum length of the result in UTF-8 bytes
+///
+/// # Returns
+///
+/// A non-empty `"{name}.{extension}"` (or just the name when `extension` is
+/// empty) of at most `max_bytes` bytes, given `max_bytes` leaves room for
+/// the extension and a few bytes of name.
+pub fn sanitize_filename_for(
+    name: &str,
+    extension: &str,
+    max_bytes: usize,
+    platform: FilenamePlatform,
+) -> String {
+    let budget = if extension.is_empty() {
+        max_bytes
+    } else {
+        max_bytes.saturating_sub(extension.len() + 1)
+    };
+    let cleaned: String = name
+        .chars()
+        .filter(|c| !c.is_control())
+        .map(|c| if platform.is_invalid(c) { '_' } else { c })
+        .collect();
+
+    let mut stem = fit_to_budget(&cleaned, budget, platform);
+    if matches!(stem.as_str(), "" | "." | "..") {
+        stem = "_".to_string();
+    }
+    if platform == FilenamePlatform::Windows && is_windows_reserved(&stem) {
+        let base_len = stem.split('.').next().unwrap_or_default().trim_end().len();
+        stem.insert(base_len, '_');
+        stem = fit_to_budget(&stem, budget, platform);
+    }
+
+    if extension.is_empty() {
+        stem
+    } else {
+        format!("{stem}.{extension}")
+    }
+}
+
+/// Trims `name` and truncates it to `budget` bytes, keeping the page suffix.
+fn fit_to_budget(name: &str, budget: usize, platform: FilenamePlatform) -> String {
+    let trim = |s: &str| {
+        s.trim_start()
+            .trim_end_matches(|c| platform.is_trailing_trimmed(c))
+            .to_string()
+    };
+    let name = trim(name);
+    if name.len() <= budget {
+        return name;
+    }
+    let (head, suffix) = split_page_suffix(&name);
+    let suffix = if suffix.len() < budget { suffix } else { "" };
+    let head = truncate_graphemes(head, budget - suffix.len());
+    trim(&format!("{}{suffix}", trim(head)))
+}
+
+/// Splits a trailing ` (n)` page suffix off a name.
+fn split_page_suffix(name: &str) -> (&str, &str) {
+    let suffix_start = name
+        .strip_suffix(')')
+        .and_then(|rest| rest.rfind(" ("))
+        .filter(|&start| {
+            let digits = &name[start + 2..name.len() - 1];
+            !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit())
+        });
+    match suffix_start {
+        Some(start) if start > 0 => name.split_at(start),
+        _ => (name, ""),
+    }
+}
+
+/// Returns the longest prefix of `s` of at most `max_bytes` bytes that ends
+/// on a grapheme boundary.
+///
+/// Falls back to a char boundary when the first grapheme alone is too long
+/// (e.g. a letter carrying hundreds of combining marks).
+fn truncate_graphemes(s: &str, max_bytes: usize) -> &str {
+    if s.len() <= max_bytes {
+        return s;
+    }
+    let fits = |&end: &usize| end <= max_bytes;
+    let end = s
+        .grapheme_indices(true)
+        .map(|(start, grapheme)| start + grapheme.len())
+        .take_while(fits)
+        .last()
+        .or_else(|| {
+            s.char_indices()
+                .map(|(i, c)| i + c.len_utf8())
+                .take_while(fits)
+                .last()
+        })
+        .unwrap_or(0);
+    &s[..end]
+}
+
+/// Returns whether Windows treats `name` as a device name.
+///
+/// Only the part before the first dot counts, so `nul.tar` is reserved too.
+fn is_windows_reserved(name: &str) -> bool {
+    let base = name.split('.').next().unwrap_or_default().trim_end();
+    WINDOWS_RESERVED_NAMES
+        .iter()
+        .any(|reserved| base.eq_ignore_ascii_case(reserved))
+}
+
 /// Resolves duplicate titles by adding index suffixes (e.g., "hoge" -> "hoge (1)").
 ///
 /// Processes a list of titles and adds numerical suffixes to duplicate entries
@@ -71,6 +262,104 @@ pub fn resolve_duplicate_titles(titles: &[String]) -> Vec<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
+
+    const PLATFORMS: [FilenamePlatform; 3] = [
+        FilenamePlatform::Windows,
+        FilenamePlatform::MacOs,
+        FilenamePlatform::Unix,
+    ];
+
+    #[test]
+    fn test_sanitize_filename_windows_rules() {
+        let windows =
+            |name: &str| sanitize_filename_for(name, "mp4", 255, FilenamePlatform::Windows);
+        assert_eq!(windows("CON"), "CON_.mp4");
+        assert_eq!(windows("nul.tar"), "nul_.tar.mp4");
+        assert_eq!(windows("Com1 "), "Com1_.mp4");
+        assert_eq!(windows("CONSOLE"), "CONSOLE.mp4");
+        assert_eq!(windows("Title... "), "Title.mp4");
+        assert_eq!(windows("a\u{0}b\tc\u{7f}d"), "abcd.mp4");
+        assert_eq!(windows("a:b|c?"), "a_b_c_.mp4");
+        assert_eq!(windows("..."), "_.mp4");
+        assert_eq!(
+            sanitize_filename_for("CON", "", 255, FilenamePlatform::Unix),
+            "CON"
+        );
+        assert_eq!(
+            sanitize_filename_for("..", "", 255, FilenamePlatform::Unix),
+            "_"
+        );
+        assert_eq!(
+            sanitize_filename_for("a:b", "", 255, FilenamePlatform::MacOs),
+            "a_b"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_filename_truncates_cjk_keeping_suffix() {
+        // 100 CJK characters are 300 bytes in UTF-8.
+        let title = format!("{} (12)", "字".repeat(100));
+        let result = sanitize_filename_for(&title, "mp4", 255, FilenamePlatform::Unix);
+        assert!(result.len() <= 255);
+        assert!(result.ends_with("字 (12).mp4"));
+        assert_eq!(result.len(), 3 * 82 + " (12).mp4".len());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_sanitized_names_are_valid(
+            name in any::<String>(),
+            extension in "[a-z0-9]{0,4}",
+            max_bytes in 16usize..=255,
+        ) {
+            for platform in PLATFORMS {
+                let result = sanitize_filename_for(&name, &extension, max_bytes, platform);
+                prop_assert!(result.len() <= max_bytes, "{result:?} exceeds {max_bytes}");
+                let stem = if extension.is_empty() {
+                    result.as_str()
+                } else {
+                    let suffix = format!(".{extension}");
+                    prop_assert!(result.ends_with(&suffix));
+                    &result[..result.len() - suffix.len()]
+                };
+                prop_assert!(!matches!(stem, "" | "." | ".."));
+                prop_assert!(!stem.chars().any(|c| c.is_control() || platform.is_invalid(c)));
+                prop_assert_eq!(stem.trim(), stem);
+                if platform == FilenamePlatform::Windows {
+                    prop_assert!(!stem.ends_with('.'));
+                    prop_assert!(!is_windows_reserved(stem));
+                }
+            }
+        }
+
+        #[test]
+        fn prop_sanitize_is_idempotent(name in any::<String>(), max_bytes in 16usize..=255) {
+            for platform in PLATFORMS {
+                let once = sanitize_filename_for(&name, "", max_bytes, platform);
+                let twice = sanitize_filename_for(&once, "", max_bytes, platform);
+                prop_assert_eq!(once, twice);
+            }
+        }
+
+        #[test]
+        fn prop_truncation_keeps_graphemes_and_page_suffix(
+            name in "[a-z字🇯🇵](e\u{301}|👨\u{200d}👩\u{200d}👧|字|🇯🇵|[a-z ]){0,150}",
+            page in 1u32..1000,
+            max_bytes in 32usize..=255,
+        ) {
+            let name = name.trim_end();
+            let suffix = format!(" ({page})");
+            let title = format!("{name}{suffix}");
+            let result = sanitize_filename_for(&title, "mp4", max_bytes, FilenamePlatform::Unix);
+            prop_assert!(result.len() <= max_bytes);
+            let stem = result.strip_suffix(".mp4").unwrap();
+            let head = stem.strip_suffix(suffix.as_str()).unwrap();
+            prop_assert!(name.starts_with(head));
+            prop_assert!(name.grapheme_indices(true).any(|(i, _)| i == head.len())
+                || head.len() == name.len());
+        }
+    }
 
     #[test]
     fn test_apply_replacements_with_defaults() {
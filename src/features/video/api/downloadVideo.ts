@@ -8,6 +8,9 @@ import {
 } from '@/shared/queue/queueSlice'
 import { invoke } from '@tauri-apps/api/core'
 
+/** Error code of a download skipped because it is already archived. */
+const ARCHIVED_ERROR = 'ERR::ARCHIVED'
+
 /**
  * Initiates a video download via the Tauri backend.
  *
@@ -111,6 +114,20 @@ export const downloadVideo = async (
     store.dispatch(updateQueueStatus({ downloadId, status: 'done' }))
   } catch (error) {
     const errorMessage = error instanceof Error ? error.message : String(error)
+    // @why: ERR::ARCHIVED means the item is already in the download archive
+    // and was skipped on purpose; the path its history entry recorded
+    // follows the code.
+    if (errorMessage.startsWith(ARCHIVED_ERROR)) {
+      const outputPath = errorMessage.slice(ARCHIVED_ERROR.length + 2)
+      logger.info(`downloadVideo: skipped archived id=${downloadId}`)
+      if (outputPath) {
+        store.dispatch(
+          updateQueueItem({ downloadId, outputPath, title: filename }),
+        )
+      }
+      store.dispatch(updateQueueStatus({ downloadId, status: 'done' }))
+      return
+    }
     logger.error(`downloadVideo: failed id=${downloadId}`, error)
     // Cancel-induced reject: don't mark as error. Check the error string AND
     // the queue status (self/parent) so a non-ERR::CANCELLED message during a